@@ -0,0 +1,276 @@
+//! # Action Log
+//!
+//! Records what the agent actually did during a task (file changes, commands, verification runs)
+//! so the engine can write `walkthrough.md` and `progress.md` itself instead of asking the model
+//! to hand-edit them. Entries collect until a checklist item is ticked; that item's walkthrough
+//! section is generated from them.
+
+use serde::{Deserialize, Serialize};
+
+/// Heading of the walkthrough section the generated entries go under.
+const CHANGES_HEADING: &str = "## Changes";
+/// Commands listed per walkthrough section before the rest are summarized.
+const MAX_COMMANDS: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum LoggedAction {
+    /// A write or edit: lines added and removed, and whether the file was new
+    File {
+        path: String,
+        added: usize,
+        removed: usize,
+        created: bool,
+    },
+    Command { command: String, success: bool },
+    Verification { summary: String, success: bool },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompletedItem {
+    pub item: String,
+    pub actions: Vec<LoggedAction>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ActionLog {
+    /// Actions since the last ticked item
+    pub pending: Vec<LoggedAction>,
+    pub completed: Vec<CompletedItem>,
+}
+
+impl ActionLog {
+    pub fn record(&mut self, action: LoggedAction) {
+        self.pending.push(action);
+    }
+
+    /// Closes the pending actions under the items ticked in one step and returns their
+    /// walkthrough section. Items ticked together share the actions.
+    pub fn complete(&mut self, items: &[String]) -> Option<String> {
+        let (first, rest) = items.split_first()?;
+        let actions = std::mem::take(&mut self.pending);
+        let section = walkthrough_section(first, rest, &actions);
+        self.completed.push(CompletedItem {
+            item: first.clone(),
+            actions,
+        });
+        self.completed.extend(rest.iter().map(|item| CompletedItem {
+            item: item.clone(),
+            actions: Vec::new(),
+        }));
+        Some(section)
+    }
+
+    /// Everything recorded during the task, completed items first.
+    fn all_actions(&self) -> impl Iterator<Item = &LoggedAction> {
+        self.completed
+            .iter()
+            .flat_map(|c| c.actions.iter())
+            .chain(self.pending.iter())
+    }
+
+    /// Entry appended to `progress.md` when the task completes.
+    pub fn progress_entry(&self, date: &str, title: &str, task_rel: &str, outcome: &str) -> String {
+        let actions: Vec<&LoggedAction> = self.all_actions().collect();
+        let files = file_summary(&actions);
+        let (commands, failed) = actions.iter().fold((0, 0), |(n, f), a| match a {
+            LoggedAction::Command { success, .. } => (n + 1, f + usize::from(!success)),
+            _ => (n, f),
+        });
+        let verification = actions.iter().rev().find_map(|a| match a {
+            LoggedAction::Verification { summary, success } => Some(format!("{} {}", status(*success), summary)),
+            _ => None,
+        });
+
+        let mut out = format!("## [{}] {}\n", date, title);
+        out.push_str(&format!("- **Task**: `{}`\n", task_rel));
+        if self.completed.is_empty() {
+            out.push_str("- **Items Completed**: none\n");
+        } else {
+            out.push_str(&format!("- **Items Completed** ({}):\n", self.completed.len()));
+            for c in &self.completed {
+                out.push_str(&format!("  - {}\n", c.item));
+            }
+        }
+        out.push_str(&format!(
+            "- **Files Changed**: {}\n",
+            if files.is_empty() { "none".to_string() } else { files.join(", ") }
+        ));
+        let failed = if failed > 0 { format!(" ({} failed)", failed) } else { String::new() };
+        out.push_str(&format!("- **Commands Run**: {}{}\n", commands, failed));
+        if let Some(v) = verification {
+            out.push_str(&format!("- **Verification**: {}\n", v));
+        }
+        out.push_str(&format!("- **Outcome**: {}\n", outcome));
+        out
+    }
+}
+
+fn walkthrough_section(item: &str, also: &[String], actions: &[LoggedAction]) -> String {
+    let refs: Vec<&LoggedAction> = actions.iter().collect();
+    let mut out = format!("### ✅ {}\n", item);
+    if !also.is_empty() {
+        out.push_str(&format!("- **Also completed**: {}\n", also.join("; ")));
+    }
+
+    let files = file_summary(&refs);
+    if files.is_empty() {
+        out.push_str("- **Files**: no changes recorded\n");
+    } else {
+        out.push_str(&format!("- **Files**: {}\n", files.join(", ")));
+    }
+
+    let commands: Vec<String> = actions
+        .iter()
+        .filter_map(|a| match a {
+            LoggedAction::Command { command, success } => Some(format!("`{}` {}", command, status(*success))),
+            _ => None,
+        })
+        .collect();
+    if !commands.is_empty() {
+        let mut line = commands.iter().take(MAX_COMMANDS).cloned().collect::<Vec<_>>().join(", ");
+        if commands.len() > MAX_COMMANDS {
+            line.push_str(&format!(" and {} more", commands.len() - MAX_COMMANDS));
+        }
+        out.push_str(&format!("- **Commands**: {}\n", line));
+    }
+
+    if let Some((summary, success)) = actions.iter().rev().find_map(|a| match a {
+        LoggedAction::Verification { summary, success } => Some((summary, *success)),
+        _ => None,
+    }) {
+        out.push_str(&format!("- **Verification**: {} {}\n", status(success), summary));
+    }
+    out
+}
+
+/// `` `src/cli.rs` (new, +80) ``, `` `src/main.rs` (+12/-3) `` per file, in first-touched order.
+fn file_summary(actions: &[&LoggedAction]) -> Vec<String> {
+    let mut files: Vec<(&str, usize, usize, bool)> = Vec::new();
+    for action in actions {
+        if let LoggedAction::File { path, added, removed, created } = action {
+            match files.iter_mut().find(|(p, ..)| p == path) {
+                Some(entry) => {
+                    entry.1 += added;
+                    entry.2 += removed;
+                }
+                None => files.push((path, *added, *removed, *created)),
+            }
+        }
+    }
+    files
+        .into_iter()
+        .map(|(path, added, removed, created)| {
+            if created {
+                format!("`{}` (new, +{})", path, added)
+            } else {
+                format!("`{}` (+{}/-{})", path, added, removed)
+            }
+        })
+        .collect()
+}
+
+fn status(success: bool) -> &'static str {
+    if success { "✅" } else { "❌" }
+}
+
+/// Inserts a generated section at the end of the walkthrough's `## Changes` part (adding the
+/// heading if the file has none) and fills in the `[title]` placeholder.
+pub fn add_to_walkthrough(doc: &str, title: &str, section: &str) -> String {
+    let doc = doc.replacen("[title]", title, 1);
+    let mut lines: Vec<&str> = doc.lines().collect();
+    let Some(heading) = lines.iter().position(|l| l.trim() == CHANGES_HEADING) else {
+        let mut out = doc.trim_end().to_string();
+        out.push_str(&format!("\n\n{}\n\n{}", CHANGES_HEADING, section));
+        return out;
+    };
+
+    // Drop the template's placeholder bullets once real entries arrive
+    let mut end = lines[heading + 1..]
+        .iter()
+        .position(|l| l.starts_with("## "))
+        .map(|p| heading + 1 + p)
+        .unwrap_or(lines.len());
+    let placeholder: Vec<usize> = (heading + 1..end)
+        .filter(|&i| lines[i].contains("[what was changed]") || lines[i].contains("use more points if needed"))
+        .collect();
+    for &i in placeholder.iter().rev() {
+        lines.remove(i);
+        end -= 1;
+    }
+    // Keep one blank line between the last entry and the next heading
+    while end > heading + 1 && lines[end - 1].trim().is_empty() {
+        lines.remove(end - 1);
+        end -= 1;
+    }
+
+    let mut block: Vec<&str> = vec![""];
+    block.extend(section.trim_end().lines());
+    if end < lines.len() {
+        block.push("");
+    }
+    lines.splice(end..end, block);
+    let mut out = lines.join("\n");
+    out.push('\n');
+    out
+}
+
+/// Marks the walkthrough's status line as completed.
+pub fn complete_walkthrough(doc: &str) -> String {
+    doc.replacen("🟡 In Progress", "✅ Completed", 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, added: usize, removed: usize, created: bool) -> LoggedAction {
+        LoggedAction::File {
+            path: path.to_string(),
+            added,
+            removed,
+            created,
+        }
+    }
+
+    #[test]
+    fn test_item_sections_and_progress_entry() {
+        let mut log = ActionLog::default();
+        log.record(file("src/cli.rs", 80, 0, true));
+        log.record(LoggedAction::Command { command: "cargo build".into(), success: false });
+        log.record(file("src/cli.rs", 2, 1, false));
+        log.record(file("src/main.rs", 5, 3, false));
+        log.record(LoggedAction::Verification { summary: "2/2 passed".into(), success: true });
+
+        let section = log.complete(&["Parse flags".to_string(), "Wire into main".to_string()]).unwrap();
+        assert_eq!(
+            section,
+            "### ✅ Parse flags\n- **Also completed**: Wire into main\n- **Files**: `src/cli.rs` (new, +82), `src/main.rs` (+5/-3)\n- **Commands**: `cargo build` ❌\n- **Verification**: ✅ 2/2 passed\n"
+        );
+        assert!(log.pending.is_empty());
+        assert!(log.complete(&[]).is_none());
+
+        log.record(LoggedAction::Command { command: "cargo test".into(), success: true });
+        let entry = log.progress_entry("2026-01-02 10:00", "Add CLI flags", "tasks/003-add-cli-flags", "Completed");
+        assert!(entry.starts_with("## [2026-01-02 10:00] Add CLI flags\n- **Task**: `tasks/003-add-cli-flags`\n"));
+        assert!(entry.contains("- **Items Completed** (2):\n  - Parse flags\n  - Wire into main\n"));
+        assert!(entry.contains("- **Commands Run**: 2 (1 failed)\n"));
+        assert!(entry.contains("- **Verification**: ✅ 2/2 passed\n"));
+    }
+
+    #[test]
+    fn test_add_to_walkthrough() {
+        let template = crate::strings::templates::WALKTHROUGH_TEMPLATE;
+        let doc = add_to_walkthrough(template, "Add CLI flags", "### ✅ One\n- **Files**: `a.rs` (new, +1)\n");
+        let doc = add_to_walkthrough(&doc, "Add CLI flags", "### ✅ Two\n- **Files**: no changes recorded\n");
+        assert!(doc.starts_with("# Walkthrough - Add CLI flags\n"));
+        assert!(!doc.contains("[what was changed]"));
+        let one = doc.find("### ✅ One").unwrap();
+        let two = doc.find("### ✅ Two").unwrap();
+        assert!(one < two && two < doc.find("## Verification").unwrap());
+        assert!(doc.contains("no changes recorded\n\n## Verification"));
+        assert!(complete_walkthrough(&doc).contains("✅ Completed"));
+
+        let bare = add_to_walkthrough("# Notes\n", "x", "### ✅ One\n");
+        assert_eq!(bare, "# Notes\n\n## Changes\n\n### ✅ One\n");
+    }
+}
@@ -0,0 +1,126 @@
+//! # Task Checkpoints
+//!
+//! Crash-safe snapshots of a running task, stored as `checkpoint.json` inside the task folder.
+//! The engine writes one after every step so that a restart can resume the task exactly where it stopped.
+
+use crate::application::action_log::ActionLog;
+use crate::application::feed::FeedSnapshot;
+use crate::application::state::TaskPhase;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskCheckpoint {
+    /// The task prompt passed to `run_task`
+    pub task: String,
+    #[serde(default)]
+    pub display_task: Option<String>,
+    pub agent_name: String,
+    #[serde(default)]
+    pub working_dir: Option<String>,
+    #[serde(default)]
+    pub phase: TaskPhase,
+    /// Number of engine steps already taken
+    pub steps: usize,
+    /// The engine transcript (`history`) at the time of the checkpoint
+    pub history: String,
+    /// Command that was waiting for `.approve` / `.deny` when the checkpoint was written
+    #[serde(default)]
+    pub pending_approval: Option<String>,
+    #[serde(default)]
+    pub feed: Option<FeedSnapshot>,
+    /// Actions recorded for the walkthrough and progress log so far
+    #[serde(default)]
+    pub action_log: ActionLog,
+    #[serde(default)]
+    pub updated_at: i64,
+}
+
+impl TaskCheckpoint {
+    pub fn new(
+        task: &str,
+        display_task: Option<&str>,
+        agent_name: &str,
+        working_dir: Option<String>,
+    ) -> Self {
+        Self {
+            task: task.to_string(),
+            display_task: display_task.map(|s| s.to_string()),
+            agent_name: agent_name.to_string(),
+            working_dir,
+            phase: TaskPhase::default(),
+            steps: 0,
+            history: String::new(),
+            pending_approval: None,
+            feed: None,
+            action_log: ActionLog::default(),
+            updated_at: 0,
+        }
+    }
+
+    /// Returns the checkpoint location for a task folder (e.g. `{wd}/tasks/003-foo/checkpoint.json`)
+    pub fn path(workdir: &str, task_rel: &str) -> PathBuf {
+        Path::new(workdir)
+            .join(task_rel)
+            .join(crate::domain::paths::CHECKPOINT_FILE)
+    }
+
+    /// Loads the checkpoint of a task folder, if one exists and parses.
+    pub fn load(workdir: &str, task_rel: &str) -> Option<Self> {
+        let content = std::fs::read_to_string(Self::path(workdir, task_rel)).ok()?;
+        match serde_json::from_str(&content) {
+            Ok(cp) => Some(cp),
+            Err(e) => {
+                tracing::warn!("Ignoring unreadable checkpoint for {}: {}", task_rel, e);
+                None
+            }
+        }
+    }
+
+    /// Writes the checkpoint atomically (temp file + rename) so a crash never leaves a torn file.
+    pub fn save(&self, workdir: &str, task_rel: &str) -> Result<()> {
+        let path = Self::path(workdir, task_rel);
+        let tmp = path.with_extension("json.tmp");
+        let content = serde_json::to_string_pretty(self).context("Failed to serialize checkpoint")?;
+        std::fs::write(&tmp, content).context("Failed to write checkpoint")?;
+        std::fs::rename(&tmp, &path).context("Failed to commit checkpoint")?;
+        Ok(())
+    }
+
+    /// Removes the checkpoint of a task folder (task finished or abandoned).
+    pub fn clear(workdir: &str, task_rel: &str) {
+        let path = Self::path(workdir, task_rel);
+        if path.exists() {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkpoint_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let wd = dir.path().to_string_lossy().to_string();
+        std::fs::create_dir_all(dir.path().join("tasks/001-init")).unwrap();
+
+        let mut cp = TaskCheckpoint::new("tasks/001-init", None, "default", Some(wd.clone()));
+        cp.phase = TaskPhase::Execution;
+        cp.steps = 4;
+        cp.history = "Agent: hello".to_string();
+        cp.pending_approval = Some("rm -rf /tmp/x".to_string());
+        cp.save(&wd, "tasks/001-init").unwrap();
+
+        let loaded = TaskCheckpoint::load(&wd, "tasks/001-init").unwrap();
+        assert_eq!(loaded.steps, 4);
+        assert_eq!(loaded.phase, TaskPhase::Execution);
+        assert_eq!(loaded.history, "Agent: hello");
+        assert_eq!(loaded.pending_approval.as_deref(), Some("rm -rf /tmp/x"));
+
+        TaskCheckpoint::clear(&wd, "tasks/001-init");
+        assert!(TaskCheckpoint::load(&wd, "tasks/001-init").is_none());
+    }
+}
@@ -0,0 +1,120 @@
+//! # Sub-agent Delegation
+//!
+//! A parent run can hand a narrowed goal to a child `ExecutionEngine` run (```delegate``` blocks).
+//! The child works in the same project with its own small step budget and an optional file scope,
+//! and its final summary is appended to the parent's history.
+
+/// Maximum nesting: the top-level run plus this many levels of sub-agents.
+pub const MAX_DEPTH: usize = 2;
+/// Step budget used when the delegate block does not set one.
+pub const DEFAULT_STEPS: usize = 8;
+/// Upper bound for a child's step budget.
+pub const MAX_STEPS: usize = 15;
+
+/// Limits that apply to a child run.
+#[derive(Debug, Clone)]
+pub struct DelegationScope {
+    /// 1 for a direct child of the top-level run.
+    pub depth: usize,
+    pub goal: String,
+    /// Files, directories or glob patterns the child may modify. Empty means the whole project.
+    pub files: Vec<String>,
+    pub max_steps: usize,
+}
+
+impl DelegationScope {
+    pub fn new(depth: usize, goal: &str, files: Vec<String>, max_steps: usize) -> Self {
+        let max_steps = if max_steps == 0 { DEFAULT_STEPS } else { max_steps.min(MAX_STEPS) };
+        Self {
+            depth,
+            goal: goal.to_string(),
+            files,
+            max_steps,
+        }
+    }
+
+    /// Whether the child may write to this (agent-visible, project-relative) path.
+    pub fn allows(&self, path: &str) -> bool {
+        if self.files.is_empty() {
+            return true;
+        }
+        let path = path.trim_start_matches("./");
+        self.files.iter().any(|entry| {
+            let entry = entry.trim_start_matches("./").trim_end_matches('/');
+            path == entry
+                || path.starts_with(&format!("{}/", entry))
+                || glob::Pattern::new(entry).is_ok_and(|p| p.matches(path))
+        })
+    }
+
+    /// The task text handed to the child run.
+    pub fn child_task(&self) -> String {
+        let scope = if self.files.is_empty() {
+            "the whole project".to_string()
+        } else {
+            self.files.join(", ")
+        };
+        format!(
+            "SUB-TASK (delegated by the lead developer): {}\n\nYou may only modify: {}.\nYou have at most {} steps. When the sub-task is finished, reply with a short summary of what you changed followed by `NO_MORE_STEPS`.",
+            self.goal, scope, self.max_steps
+        )
+    }
+}
+
+/// Parses the body of a ```delegate``` block:
+///
+/// ```text
+/// goal: Implement the tokenizer
+/// files: src/lexer.rs, src/token.rs
+/// steps: 8
+/// ```
+///
+/// Lines without a known key are appended to the goal.
+pub fn parse_request(body: &str) -> (String, Vec<String>, usize) {
+    let mut goal = Vec::new();
+    let mut files = Vec::new();
+    let mut steps = 0;
+
+    for line in body.lines() {
+        let trimmed = line.trim();
+        if let Some(rest) = trimmed.strip_prefix("goal:") {
+            goal.push(rest.trim().to_string());
+        } else if let Some(rest) = trimmed.strip_prefix("files:") {
+            files.extend(
+                rest.split(',')
+                    .map(|f| f.trim().trim_matches('`').to_string())
+                    .filter(|f| !f.is_empty()),
+            );
+        } else if let Some(rest) = trimmed.strip_prefix("steps:") {
+            steps = rest.trim().parse().unwrap_or(0);
+        } else if !trimmed.is_empty() {
+            goal.push(trimmed.to_string());
+        }
+    }
+
+    (goal.join("\n"), files, steps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_request_and_scope() {
+        let (goal, files, steps) =
+            parse_request("goal: Add the lexer\nfiles: src/lexer.rs, tests/\nsteps: 40\nKeep it small.");
+        assert_eq!(goal, "Add the lexer\nKeep it small.");
+        assert_eq!(files, vec!["src/lexer.rs", "tests/"]);
+
+        let scope = DelegationScope::new(1, &goal, files, steps);
+        assert_eq!(scope.max_steps, MAX_STEPS);
+        assert!(scope.allows("src/lexer.rs"));
+        assert!(scope.allows("./tests/lexer_test.rs"));
+        assert!(!scope.allows("src/main.rs"));
+
+        let open = DelegationScope::new(1, "x", vec!["src/**/*.rs".into()], 0);
+        assert_eq!(open.max_steps, DEFAULT_STEPS);
+        assert!(open.allows("src/a/b.rs"));
+        assert!(!open.allows("Cargo.toml"));
+    }
+}
@@ -8,14 +8,25 @@ use std::path::Path;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
+use crate::application::action_log::{ActionLog, LoggedAction};
+use crate::application::checkpoint::TaskCheckpoint;
+use crate::application::delegation::DelegationScope;
+use crate::application::stagnation::{Stagnation, StagnationDetector};
 use crate::application::feed::FeedManager;
-use crate::domain::config::AppConfig;
+use crate::domain::checklist::Checklist;
+use crate::domain::config::{AppConfig, Hook, HookEvent};
 use crate::domain::traits::ChatProvider;
 use crate::domain::traits::LlmProvider;
-use crate::infrastructure::tools::executor::SharedToolExecutor; // Keep ChatProvider for run_task method
+use crate::infrastructure::tools::executor::SharedToolExecutor;
+use crate::infrastructure::tools::outline::SymbolIndex;
+use crate::infrastructure::tools::retrieval::RetrievalIndex;
+use crate::infrastructure::tools::resolver::{PathError, PathResolver};
 
 use crate::application::state::BotState;
 
+/// How long an `ask` question waits for a reply before the agent decides on its own.
+const ANSWER_TIMEOUT_MINUTES: u64 = 15;
+
 #[derive(Clone)]
 pub struct ExecutionEngine {
     _config: AppConfig,
@@ -23,6 +34,20 @@ pub struct ExecutionEngine {
     tools: SharedToolExecutor,
     feed: Arc<Mutex<FeedManager>>,
     state: Arc<Mutex<BotState>>,
+    /// Set when this engine runs a delegated sub-task
+    delegation: Option<DelegationScope>,
+    /// Receiving end of `RoomState::input_tx` for the current run (shared with sub-agents)
+    input_rx: Arc<Mutex<Option<tokio::sync::mpsc::Receiver<String>>>>,
+    /// Receiving end of `RoomState::pause_tx` for the current run (shared with sub-agents)
+    pause_rx: Arc<Mutex<Option<tokio::sync::watch::Receiver<bool>>>>,
+    /// Symbol index of the project, built on the first ```symbol``` lookup (shared with sub-agents)
+    symbols: Arc<Mutex<Option<SymbolIndex>>>,
+    /// Repository map for the prompt context; `None` when files changed since it was built
+    repo_map: Arc<Mutex<Option<String>>>,
+    /// BM25 index over the project's text files, loaded from `data/index/` on first use
+    retrieval: Arc<Mutex<Option<RetrievalIndex>>>,
+    /// Writes, commands and verification runs of the task, for walkthrough.md and progress.md
+    action_log: Arc<Mutex<ActionLog>>,
 }
 
 impl ExecutionEngine {
@@ -39,10 +64,85 @@ impl ExecutionEngine {
             tools,
             feed,
             state,
+            delegation: None,
+            input_rx: Arc::new(Mutex::new(None)),
+            pause_rx: Arc::new(Mutex::new(None)),
+            symbols: Arc::new(Mutex::new(None)),
+            repo_map: Arc::new(Mutex::new(None)),
+            retrieval: Arc::new(Mutex::new(None)),
+            action_log: Arc::new(Mutex::new(ActionLog::default())),
+        }
+    }
+
+    /// Engine for a sub-agent: same project, feed and room, narrowed goal and budget.
+    fn child(&self, scope: DelegationScope) -> Self {
+        Self {
+            delegation: Some(scope),
+            ..self.clone()
+        }
+    }
+
+    /// Engine using the project's config, with its command and path rules handed to the executor.
+    async fn scoped_to(&self, workdir: &str) -> Self {
+        let config = self._config.for_project(workdir);
+        let policy = config
+            .project
+            .as_ref()
+            .map(crate::infrastructure::tools::executor::ProjectPolicy::from_config);
+        self.tools
+            .lock()
+            .await
+            .set_project_policy(Path::new(workdir), policy);
+        Self {
+            _config: config,
+            ..self.clone()
+        }
+    }
+
+    /// Whether a run is a task run of the room, not a sub-agent or an `.ask` conversation.
+    /// `.ask` can run next to a task, so only task runs own the room's channels.
+    async fn is_task_run(
+        &self,
+        room_id: &str,
+        override_phase: Option<&crate::application::state::TaskPhase>,
+    ) -> bool {
+        if self.delegation.is_some() {
+            return false;
         }
+        let phase = match override_phase {
+            Some(phase) => phase.clone(),
+            None => {
+                let guard = self.state.lock().await;
+                guard.rooms.get(room_id).map(|r| r.task_phase.clone()).unwrap_or_default()
+            }
+        };
+        phase != crate::application::state::TaskPhase::Assistant
+    }
+
+    /// Runs a sub-agent to completion. Boxed because `run_task` recurses through here.
+    fn run_child<'a>(
+        &'a self,
+        chat: &'a (impl ChatProvider + 'a),
+        scope: DelegationScope,
+        agent_name: &'a str,
+        working_dir: Option<String>,
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<Option<String>>> + Send + 'a>>
+    {
+        let child = self.child(scope);
+        Box::pin(async move {
+            let task = child
+                .delegation
+                .as_ref()
+                .map(|d| d.child_task())
+                .unwrap_or_default();
+            child
+                .run_task(chat, &task, None, agent_name, working_dir, None, None)
+                .await
+        })
     }
 
     /// Primary execution loop
+    #[allow(clippy::too_many_arguments)]
     pub async fn run_task(
         &self,
         chat: &impl ChatProvider,
@@ -53,12 +153,87 @@ impl ExecutionEngine {
         override_phase: Option<crate::application::state::TaskPhase>,
         conversation_history: Option<String>,
     ) -> Result<Option<String>> {
-        // Initialize Feed
-        {
+        // A top-level run in a project works with its construct.yaml merged over the global config
+        let scoped = match working_dir.as_deref() {
+            Some(wd) if self.delegation.is_none() => Some(self.scoped_to(wd).await),
+            _ => None,
+        };
+        let engine = scoped.as_ref().unwrap_or(self);
+        let task_run = self.is_task_run(&chat.room_id(), override_phase.as_ref()).await;
+        let result = engine
+            .run_steps(
+                chat,
+                task,
+                display_task,
+                agent_name,
+                working_dir.clone(),
+                override_phase,
+                conversation_history,
+                task_run,
+            )
+            .await;
+
+        // However the run ended, show what a dry run would have changed
+        if self.delegation.is_none() {
+            let resolver = self.resolver_for(working_dir.as_deref()).await;
+            self.report_dry_run(chat, &resolver).await;
+            // Snapshot sessions belong to the task run; an `.ask` beside it must not close them
+            if task_run {
+                self.tools.lock().await.end_snapshots(resolver.root());
+            }
+        }
+        result
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn run_steps(
+        &self,
+        chat: &impl ChatProvider,
+        task: &str,
+        display_task: Option<&str>,
+        agent_name: &str,
+        working_dir: Option<String>,
+        override_phase: Option<crate::application::state::TaskPhase>,
+        conversation_history: Option<String>,
+        task_run: bool,
+    ) -> Result<Option<String>> {
+        let is_child = self.delegation.is_some();
+
+        // Take the checkpoint handed over by `.resume` (if any) before touching the feed
+        let resume = if is_child {
+            None
+        } else {
+            let mut guard = self.state.lock().await;
+            guard.get_room_state(&chat.room_id()).resume_checkpoint.take()
+        };
+
+        // Open the room's input and pause channels for this run (an `.ask` next to a task leaves them alone)
+        if task_run {
+            let (tx, rx) = tokio::sync::mpsc::channel(16);
+            *self.input_rx.lock().await = Some(rx);
+            let (pause_tx, pause_rx) = tokio::sync::watch::channel(false);
+            *self.pause_rx.lock().await = Some(pause_rx);
+            let mut guard = self.state.lock().await;
+            let room = guard.get_room_state(&chat.room_id());
+            room.input_tx = Some(tx);
+            room.awaiting_answer = false;
+            room.answer_tx = None;
+            room.pause_tx = Some(Arc::new(pause_tx));
+            // The inactivity auto-start only waits on finished runs, never on this one
+            room.task_completion_time = None;
+        }
+
+        // Initialize Feed (sub-agents write into the parent's feed)
+        if !is_child {
             let mut feed = self.feed.lock().await;
-            // Use display_task if provided, otherwise task
-            let feed_task = display_task.unwrap_or(task).to_string();
-            feed.initialize(feed_task);
+            if let Some(snapshot) = resume.as_ref().and_then(|cp| cp.feed.clone()) {
+                feed.restore(snapshot);
+                feed.add_activity("Task Resumed".to_string());
+            } else {
+                // Use display_task if provided, otherwise task
+                let feed_task = display_task.unwrap_or(task).to_string();
+                feed.initialize(feed_task);
+            }
 
             if matches!(
                 override_phase,
@@ -70,7 +245,12 @@ impl ExecutionEngine {
             let _ = feed.update_feed(chat).await;
         }
 
-        let max_steps = 20;
+        let budgets = self._config.project.as_ref().map(|p| p.budgets.clone()).unwrap_or_default();
+        let max_steps = self
+            .delegation
+            .as_ref()
+            .map(|d| d.max_steps)
+            .unwrap_or(budgets.max_steps.unwrap_or(20));
         let mut steps = 0;
         let mut history = String::new();
         // Pre-seed local history with conversation context if provided
@@ -78,16 +258,90 @@ impl ExecutionEngine {
             history.push_str(&ctx);
         }
 
+        // Every agent-visible path is resolved against the project (or the projects root outside one)
+        let resolver = self.resolver_for(working_dir.as_deref()).await;
+        // Pre/post action hooks: the global ones plus the project's own
+        let hooks = match working_dir.as_deref() {
+            Some(wd) => self._config.hooks.for_project(wd),
+            None => self._config.hooks.clone(),
+        };
+
+        // Dry run: the executor records writes and commands under the project instead of performing them
+        if !is_child {
+            let dry_run = {
+                let guard = self.state.lock().await;
+                guard.rooms.get(&chat.room_id()).is_some_and(|r| r.dry_run)
+            };
+            if dry_run {
+                self.tools.lock().await.begin_dry_run(resolver.root());
+                let mut feed = self.feed.lock().await;
+                feed.add_activity("Dry run: writes and commands are simulated".to_string());
+                let _ = feed.update_feed(chat).await;
+            }
+        }
+
+        // Undo history: writes of this run are snapshotted into the active task folder
+        if task_run {
+            let store = {
+                let guard = self.state.lock().await;
+                guard.rooms.get(&chat.room_id()).and_then(|r| r.snapshot_store())
+            };
+            // Only when the run works in the room's project (not e.g. the projects root)
+            let store = store.filter(|s| working_dir.as_deref().is_some_and(|wd| s.root() == Path::new(wd)));
+            if let Some(store) = store {
+                self.tools.lock().await.begin_snapshots(&store);
+            }
+        }
+
+        let mut checkpoint = TaskCheckpoint::new(task, display_task, agent_name, working_dir.clone());
+        if !is_child {
+            *self.action_log.lock().await = ActionLog::default();
+        }
+        if let Some(cp) = resume {
+            steps = cp.steps;
+            history = cp.history;
+            *self.action_log.lock().await = cp.action_log;
+            if let Some(cmd) = cp.pending_approval {
+                history.push_str(&format!(
+                    "\nSystem: The bot restarted while command `{}` was awaiting approval. It was NOT executed. Re-issue it if it is still needed.\n",
+                    cmd
+                ));
+            }
+        }
+
+        let mut stagnation = StagnationDetector::new();
+        // Checklist items that were already ticked before this run; only new ones get a commit
+        let mut committed_items = if is_child {
+            Vec::new()
+        } else {
+            self.checked_items(&chat.room_id(), working_dir.as_deref()).await
+        };
+        // Same for the walkthrough: only items ticked during this run get a section
+        let mut documented_items = committed_items.clone();
+        let task_title = display_task.unwrap_or(task).lines().next().unwrap_or_default().to_string();
+
         loop {
             if steps >= max_steps {
+                if is_child {
+                    return Ok(Some(format!(
+                        "Sub-agent used its whole budget of {} steps without finishing.",
+                        max_steps
+                    )));
+                }
                 let _ = chat.send_notification("⚠️ Max steps reached.").await;
                 // If max steps reached, consider the task as potentially incomplete or requiring manual intervention.
                 // We don't have a clear "final_msg" here, so we return None.
+                self.clear_checkpoint(&chat.room_id(), working_dir.as_deref()).await;
                 let mut feed = self.feed.lock().await;
                 feed.add_completion_message("Task reached max steps without explicit completion.".to_string());
                 break;
             }
             steps += 1;
+            if task_run {
+                self.tools.lock().await.next_snapshot_step(resolver.root());
+            }
+
+            self.wait_if_paused(chat).await;
 
             // Check for Stop Request & Get Phase
             let task_phase = if let Some(p) = &override_phase {
@@ -96,6 +350,10 @@ impl ExecutionEngine {
                 let mut guard = self.state.lock().await;
                 let room = guard.get_room_state(&chat.room_id());
                 if room.stop_requested {
+                    // Sub-agents leave the flag set so the parent stops (and reports) too
+                    if is_child {
+                        return Ok(None);
+                    }
                     room.stop_requested = false; // Reset flag
                     let _ = chat.send_notification("🛑 **Task Stopped by User**").await;
                     // Update Feed to Failed/Stopped
@@ -121,6 +379,15 @@ impl ExecutionEngine {
                 feed.set_agent_name(role_title.to_string());
             }
 
+            // Live steering: guidance sent with `.say` since the last step
+            for guidance in self.drain_input().await {
+                history.push_str(&format!("\nUser: {}\n", guidance));
+                let mut feed = self.feed.lock().await;
+                let summary = guidance.lines().next().unwrap_or(&guidance).to_string();
+                feed.add_finished_activity(format!("Guidance: {}", summary), true);
+                let _ = feed.update_feed(chat).await;
+            }
+
             // 1. Build Context
             // Resolve Active Task directory relative to CWD
             let active_task_rel_path = {
@@ -196,6 +463,36 @@ impl ExecutionEngine {
                 )
             };
 
+            let repo_map_content = if working_dir.is_some() {
+                let query = format!("{}\n{}\n{}", task, tasks_checklist_content, plan_content);
+                self.repo_map(&resolver, &query).await
+            } else {
+                "(No context)".to_string()
+            };
+
+            let checklist = Checklist::parse(&tasks_checklist_content);
+            // Edits to the checklist are only policed while executing; planning may restructure it
+            let checklist_path = active_task_rel_path
+                .as_ref()
+                .filter(|_| task_phase == crate::application::state::TaskPhase::Execution)
+                .map(|t| resolver.root().join(t).join("tasks.md"));
+            if !is_child {
+                let mut feed = self.feed.lock().await;
+                feed.progress = active_task_rel_path.as_ref().and_then(|_| checklist.progress());
+            }
+
+            // Code for the current checklist item (Developer) or question (Assistant)
+            let relevant_code = match (&task_phase, working_dir.is_some()) {
+                (crate::application::state::TaskPhase::Execution, true) => {
+                    let item = checklist.next_item().map(|i| i.text.as_str()).unwrap_or(task);
+                    self.retrieve(&resolver, item).await
+                }
+                (crate::application::state::TaskPhase::Assistant, true) => {
+                    self.retrieve(&resolver, task).await
+                }
+                _ => "(None)".to_string(),
+            };
+
             let projects_root = {
                 let f = self.feed.lock().await;
                 // Agent name is set based on Phase earlier in the loop
@@ -216,11 +513,15 @@ impl ExecutionEngine {
             // Current Date for contextual awareness in logs
             let current_date = chrono::Local::now().format("%Y-%m-%d %H:%M").to_string();
 
+            // Prompt templates: project overrides, then data/prompts, then the built-in ones
+            let prompts = crate::strings::prompts::PromptSet::load(working_dir.as_deref());
+
             let prompt = match task_phase {
                 crate::application::state::TaskPhase::Planning => {
                     // planning_mode_turn(cwd, roadmap, request, tasks_checklist, plan, architecture, active_task, history)
                     let task_path = active_task_rel_path.as_deref().unwrap_or("tasks/CURRENT");
                     crate::strings::prompts::planning_mode_turn(
+                        &prompts,
                         &cwd_msg,
                         &roadmap_content,
                         &tasks_checklist_content,
@@ -231,11 +532,13 @@ impl ExecutionEngine {
                         &history,
                         &current_date,
                         &guidelines_content,
+                        &repo_map_content,
                     )
                 }
                 crate::application::state::TaskPhase::Execution => {
                     let task_path = active_task_rel_path.as_deref().unwrap_or("tasks/CURRENT");
                     crate::strings::prompts::execution_mode_turn(
+                        &prompts,
                         &cwd_msg,
                         &roadmap_content,
                         &tasks_checklist_content,
@@ -246,10 +549,13 @@ impl ExecutionEngine {
                         &history,
                         &current_date,
                         &guidelines_content,
+                        &repo_map_content,
+                        &relevant_code,
                     )
                 }
                 crate::application::state::TaskPhase::NewProject => {
                     crate::strings::prompts::new_project_prompt(
+                        &prompts,
                         "Project",
                         &tasks_checklist_content,
                         &cwd_msg,
@@ -262,6 +568,7 @@ impl ExecutionEngine {
                         f.set_agent_name("Assistant".to_string());
                     }
                     crate::strings::prompts::assistant_mode_turn(
+                        &prompts,
                         &cwd_msg,
                         &roadmap_content,
                         &tasks_checklist_content,
@@ -270,6 +577,8 @@ impl ExecutionEngine {
                         &progress_content,
                         &history,
                         &guidelines_content,
+                        &repo_map_content,
+                        &relevant_code,
                     )
                 }
             };
@@ -286,9 +595,22 @@ impl ExecutionEngine {
             // 2. LLM Completion
             let _ = chat.typing(true).await;
 
-            // Pass agent_name directly to LlmProvider (which routes via Client)
+            // Pass agent_name directly to LlmProvider (which routes via Client),
+            // unless the project's construct.yaml names an agent or model for this phase
+            let phase_key = match task_phase {
+                crate::application::state::TaskPhase::Planning
+                | crate::application::state::TaskPhase::NewProject => "planning",
+                crate::application::state::TaskPhase::Execution => "execution",
+                crate::application::state::TaskPhase::Assistant => "assistant",
+            };
+            let phase_agent = self._config.project.as_ref().and_then(|p| p.phase_agent(phase_key));
+            let agent = phase_agent.and_then(|a| a.agent.as_deref()).unwrap_or(agent_name);
             let start = std::time::Instant::now();
-            let response = match self.llm.completion(&full_prompt, agent_name).await {
+            let completion = match phase_agent.and_then(|a| a.model.as_deref()) {
+                Some(model) => self.llm.completion_with_model(&full_prompt, agent, model).await,
+                None => self.llm.completion(&full_prompt, agent).await,
+            };
+            let response = match completion {
                 Ok(r) => {
                     let duration = start.elapsed();
                     tracing::info!(
@@ -308,7 +630,8 @@ impl ExecutionEngine {
 
             // 3. Parse Actions
             history.push_str(&format!("\n\nAgent: {}\n", response));
-            let actions_with_indices = crate::application::parsing::parse_actions(&response);
+            let parsed = crate::application::parsing::parse_response(&response);
+            let actions_with_indices = parsed.actions;
 
             // Extract Agent Thought (text before the first code block) for the feed initially
             // This ensures the first thought is shown immediately even before the loop starts
@@ -320,6 +643,22 @@ impl ExecutionEngine {
                 let _ = feed.update_feed(chat).await;
             }
 
+            // Tool blocks the parser could not read go back to the model instead of vanishing
+            if !parsed.issues.is_empty() {
+                for issue in &parsed.issues {
+                    history.push_str(&format!(
+                        "\nSystem: Unparsed action: the {}. It was NOT executed; fix the block and send it again.\n",
+                        issue
+                    ));
+                }
+                let mut feed = self.feed.lock().await;
+                feed.add_activity(format!("⚠️ Unparsed action: {}", parsed.issues[0]));
+                let _ = feed.update_feed(chat).await;
+                if actions_with_indices.is_empty() {
+                    continue;
+                }
+            }
+
             if actions_with_indices.is_empty() {
                 // Conversational response
                 match task_phase {
@@ -351,11 +690,19 @@ impl ExecutionEngine {
                 // If it's just talking, we can consider the loop "paused" or "waiting for user".
                 // But this run_task is a blocking loop.
                 // We'll break for now to release control.
+                if is_child {
+                    return Ok(Some(clean_agent_thought(&response)));
+                }
+                self.clear_checkpoint(&chat.room_id(), working_dir.as_deref()).await;
                 break;
             }
 
             // 4. Execute Actions
             let mut last_response_index = 0;
+            // Code written in this step that has not been verified yet
+            let mut unverified_writes = false;
+            // Any file written in this step (for `batch` hooks)
+            let mut wrote_files = false;
             for (action_ref, start_idx, end_idx) in &actions_with_indices {
                 let action = action_ref.clone();
                 let start_idx = *start_idx;
@@ -375,11 +722,16 @@ impl ExecutionEngine {
                     }
                 }
                 last_response_index = end_idx;
+                // The previous action is finished: park here if `.pause` was requested
+                self.wait_if_paused(chat).await;
                 // Poll for Stop Request between actions
                 {
                     let mut guard = self.state.lock().await;
                     let room = guard.get_room_state(&chat.room_id());
                     if room.stop_requested {
+                        if is_child {
+                            return Ok(None);
+                        }
                         room.stop_requested = false;
                         let _ = chat
                             .send_notification("🛑 **Task Stopped by User (Interrupted)**")
@@ -393,6 +745,8 @@ impl ExecutionEngine {
                     }
                 }
 
+                let observed = action.clone();
+                let history_mark = history.len();
                 match action {
                     crate::domain::types::AgentAction::Done => {
                         // Only squash if we are truly done (Execution Phase)
@@ -452,32 +806,101 @@ impl ExecutionEngine {
                                     let _ = feed.update_feed(chat).await;
                                 }
 
+                                self.clear_checkpoint(&chat.room_id(), working_dir.as_deref()).await;
                                 return Ok(Some(
                                     "Planning Completed. Plan available for review.".to_string(),
                                 ));
                             }
                             crate::application::state::TaskPhase::Execution
                             | crate::application::state::TaskPhase::Assistant => {
+                                if wrote_files
+                                    && let Some(failure) =
+                                        self.run_hooks(chat, &hooks.post, HookEvent::Batch, None, &resolver, false).await
                                 {
-                                    let mut feed = self.feed.lock().await;
-                                    
-                                    // STRIP ACTIONS from the response to avoid dumping code blocks (artifacts) into the feed summary
-                                    let mut sorted_actions = actions_with_indices.clone();
-                                    // Correct Tuple: (Action, start_index, end_index)
-                                    sorted_actions.sort_by_key(|(_, start, _)| *start);
-
-                                    let mut clean_msg = String::new();
-                                    let mut last_idx = 0;
+                                    history.push_str(&format!("\nSystem: Post-step {}\n", failure.to_history()));
+                                }
+                                wrote_files = false;
 
-                                    for (_, start, end) in sorted_actions {
-                                        if start > last_idx {
-                                            clean_msg.push_str(&response[last_idx..start]);
-                                        }
-                                        last_idx = end;
+                                // Verification gate: the task cannot finish while checks are red
+                                if task_phase == crate::application::state::TaskPhase::Execution {
+                                    if unverified_writes
+                                        && let Some(report) = self.run_verification(chat, working_dir.as_deref()).await
+                                    {
+                                        history.push_str(&format!("\nSystem: {}\n", report.to_history()));
+                                    }
+                                    unverified_writes = false;
+
+                                    let blocked = {
+                                        let guard = self.state.lock().await;
+                                        guard
+                                            .rooms
+                                            .get(&chat.room_id())
+                                            .map(|r| r.verification_failing && !r.verification_override)
+                                            .unwrap_or(false)
+                                    };
+                                    if blocked {
+                                        history.push_str("\nSystem: COMPLETION BLOCKED: Verification is failing. Fix the errors shown in the verification results before returning `NO_MORE_STEPS`.\n");
+                                        let mut feed = self.feed.lock().await;
+                                        feed.add_activity("⚠️ Completion blocked: verification failing".to_string());
+                                        let _ = feed.update_feed(chat).await;
+                                        continue;
                                     }
-                                    if last_idx < response.len() {
-                                        clean_msg.push_str(&response[last_idx..]);
+
+                                    // Checklist gate: every item must be ticked or explicitly skipped
+                                    let open = if is_child {
+                                        Vec::new()
+                                    } else {
+                                        self.open_items(&chat.room_id(), working_dir.as_deref()).await
+                                    };
+                                    if !open.is_empty() {
+                                        let shown: Vec<String> =
+                                            open.iter().take(5).map(|i| format!("- [ ] {}", i)).collect();
+                                        history.push_str(&format!(
+                                            "\nSystem: COMPLETION BLOCKED: {} checklist item(s) are still open:\n{}\nFinish them, or mark items you will not do as `[-]` with a reason, before returning `NO_MORE_STEPS`.\n",
+                                            open.len(),
+                                            shown.join("\n")
+                                        ));
+                                        let mut feed = self.feed.lock().await;
+                                        feed.add_activity(format!("⚠️ Completion blocked: {} item(s) open", open.len()));
+                                        let _ = feed.update_feed(chat).await;
+                                        continue;
                                     }
+                                }
+
+                                // A sub-agent hands its closing message back to the parent
+                                if is_child {
+                                    let summary = strip_actions(&response, &actions_with_indices)
+                                        .replace("NO_MORE_STEPS", "")
+                                        .trim()
+                                        .to_string();
+                                    return Ok(Some(summary));
+                                }
+
+                                // The engine, not the model, writes the walkthrough and progress log
+                                if task_phase == crate::application::state::TaskPhase::Execution {
+                                    self.document_checked_items(
+                                        &chat.room_id(),
+                                        working_dir.as_deref(),
+                                        &task_title,
+                                        &mut documented_items,
+                                    )
+                                    .await;
+                                    let closing = strip_actions(&response, &actions_with_indices).replace("NO_MORE_STEPS", "");
+                                    let outcome = closing
+                                        .lines()
+                                        .map(str::trim)
+                                        .find(|l| !l.is_empty())
+                                        .map(|l| format!("Completed. {}", l))
+                                        .unwrap_or_else(|| "Completed".to_string());
+                                    self.log_progress(&chat.room_id(), working_dir.as_deref(), &task_title, &outcome)
+                                        .await;
+                                }
+
+                                {
+                                    let mut feed = self.feed.lock().await;
+                                    
+                                    // STRIP ACTIONS from the response to avoid dumping code blocks (artifacts) into the feed summary
+                                    let clean_msg = strip_actions(&response, &actions_with_indices);
 
                                     let final_msg = clean_msg.replace("NO_MORE_STEPS", "").trim().to_string();
                                     
@@ -506,59 +929,29 @@ impl ExecutionEngine {
                                         .await; // This squashes
                                     let _ = feed.update_feed(chat).await;
                                 }
+                                self.clear_checkpoint(&chat.room_id(), working_dir.as_deref()).await;
                                 return Ok(None);
                             }
                         }
                     }
                     crate::domain::types::AgentAction::ListDir(path) => {
-                        let projects_root = {
-                            let f = self.feed.lock().await;
-                            f.projects_root()
-                        };
-                        let sanitized = crate::application::utils::sanitize_path(
-                            &path,
-                            projects_root.as_deref(),
-                        );
-
+                        let label = resolver.display(&path);
                         {
                             let mut feed = self.feed.lock().await;
-                            feed.add_activity(format!("Listing dir {}", sanitized));
+                            feed.add_activity(format!("Listing dir {}", label));
                             let _ = feed.update_feed(chat).await;
                         }
 
-                        let client = self.tools.lock().await;
-                        // Resolve path
-                        let resolved_path = if Path::new(&path).is_absolute() {
-                            // Smart Resolution:
-                            // If path matches projects_root prefix, use it.
-                            // If not, check if prepending projects_root works (Sandbox View).
-                            if let Some(root) = projects_root.as_deref() {
-                                if path.starts_with(root) {
-                                    path.clone()
-                                } else {
-                                    // Try treating it as relative to root
-                                    let stripped = path.trim_start_matches('/');
-                                    let candidate =
-                                        format!("{}/{}", root.trim_end_matches('/'), stripped);
-                                    tracing::info!(
-                                        "Sandbox Resolution: Mapped virtual path '{}' to real path '{}'",
-                                        path,
-                                        candidate
-                                    );
-                                    candidate
-                                }
-                            } else {
-                                path.clone()
-                            }
-                        } else {
-                            if let Some(wd) = &working_dir {
-                                format!("{}/{}", wd, path)
-                            } else {
-                                path.clone()
+                        let resolved_path = match resolver.resolve(&path) {
+                            Ok(p) => p,
+                            Err(e) => {
+                                self.reject_path(chat, &mut history, format!("List {}", label), &e).await;
+                                continue;
                             }
                         };
 
-                        let result = client.list_dir(&resolved_path).await;
+                        let client = self.tools.lock().await;
+                        let result = client.list_dir(&resolved_path.to_string_lossy()).await;
                         let (out, success) = match result {
                             Ok(listing) => (listing, true),
                             Err(e) => (format!("Error listing directory: {}", e), false),
@@ -567,57 +960,33 @@ impl ExecutionEngine {
                         {
                             let mut feed = self.feed.lock().await;
                             if success {
-                                feed.replace_last_activity(format!("Listed {}", sanitized), true);
+                                feed.replace_last_activity(format!("Listed {}", label), true);
                             } else {
-                                feed.replace_last_activity(
-                                    format!("List {}", sanitized),
-                                    false,
-                                );
+                                feed.replace_last_activity(format!("List {}", label), false);
                             }
                             let _ = feed.update_feed(chat).await;
                         }
 
-                        history.push_str(&format!("\nSystem: {}\n", out));
+                        history.push_str(&format!("\nSystem: {}\n", resolver.scrub(&out)));
                     }
                     crate::domain::types::AgentAction::Find(path, pattern) => {
-                        let projects_root = {
-                            let f = self.feed.lock().await;
-                            f.projects_root()
-                        };
-                        let root_to_use = working_dir.as_deref().or(projects_root.as_deref());
-                        let sanitized_path = crate::application::utils::sanitize_path(
-                            &path,
-                            root_to_use,
-                        );
-                        
+                        let label = resolver.display(&path);
                         {
                             let mut feed = self.feed.lock().await;
-                            feed.add_activity(format!("Finding {} {}", sanitized_path, pattern));
+                            feed.add_activity(format!("Finding {} {}", label, pattern));
                             let _ = feed.update_feed(chat).await;
                         }
 
-                        let client = self.tools.lock().await;
-                        // Resolve path
-                        let resolved_path = if Path::new(&path).is_absolute() {
-                             if let Some(root) = projects_root.as_deref() {
-                                if path.starts_with(root) {
-                                    path.clone()
-                                } else {
-                                    let stripped = path.trim_start_matches('/');
-                                    format!("{}/{}", root.trim_end_matches('/'), stripped)
-                                }
-                            } else {
-                                path.clone()
-                            }
-                        } else {
-                            if let Some(wd) = &working_dir {
-                                format!("{}/{}", wd, path)
-                            } else {
-                                path.clone()
+                        let resolved_path = match resolver.resolve(&path) {
+                            Ok(p) => p,
+                            Err(e) => {
+                                self.reject_path(chat, &mut history, format!("Find {} {}", label, pattern), &e).await;
+                                continue;
                             }
                         };
-                        
-                        let result = client.find_files(&resolved_path, &pattern).await;
+
+                        let client = self.tools.lock().await;
+                        let result = client.find_files(&resolved_path.to_string_lossy(), &pattern).await;
                         let (out, success) = match result {
                             Ok(listing) => (listing, true),
                             Err(e) => (format!("Error finding files: {}", e), false),
@@ -626,209 +995,516 @@ impl ExecutionEngine {
                         {
                             let mut feed = self.feed.lock().await;
                             if success {
-                                feed.replace_last_activity(format!("Found {} {}", sanitized_path, pattern), true);
+                                feed.replace_last_activity(format!("Found {} {}", label, pattern), true);
                             } else {
                                 feed.replace_last_activity(
-                                    format!("Find {} {}", sanitized_path, pattern),
+                                    format!("Find {} {}", label, pattern),
                                     false,
                                 );
                             }
                             let _ = feed.update_feed(chat).await;
                         }
 
-                        history.push_str(&format!("\nSystem: {}\n", out));
+                        history.push_str(&format!("\nSystem: {}\n", resolver.scrub(&out)));
                     }
-                    crate::domain::types::AgentAction::WriteFile(path, content) => {
-                        // SAFETY CHECK: Enforce Planning constraints
-                        // If in Planning phase, ONLY allow .md (or .txt/yaml/json?) files.
-                        // Strictly forbid .rs, .py, etc.
-                        if task_phase == crate::application::state::TaskPhase::Planning
-                            || task_phase == crate::application::state::TaskPhase::NewProject
+                    crate::domain::types::AgentAction::Search(query) => {
+                        let label = resolver.display(&query.path);
                         {
-                            if !path.ends_with(".md")
-                                && !path.ends_with(".txt")
-                                && !path.ends_with(".yaml")
-                                && !path.ends_with(".json")
-                            {
-                                let err_msg = format!(
-                                    "PERMISSION DENIED: You are in the PLANNING phase. You cannot write code files (`{}`) yet. You can only write documentation (.md, .txt, .yaml, .json). If you have finished the plan, output `NO_MORE_STEPS`.",
-                                    path
-                                );
-                                history.push_str(&format!("\nSystem: {}\n", err_msg));
+                            let mut feed = self.feed.lock().await;
+                            feed.add_activity(format!("Searching {} in {}", query.pattern, label));
+                            let _ = feed.update_feed(chat).await;
+                        }
 
-                                // Update feed to show the rejection?
-                                {
-                                    let mut feed = self.feed.lock().await;
-                                    feed.add_activity(format!(
-                                        "⚠️ Blocked write to {} (Planning Only)",
-                                        path
-                                    ));
-                                    let _ = feed.update_feed(chat).await;
-                                }
+                        let resolved_path = match resolver.resolve(&query.path) {
+                            Ok(p) => p,
+                            Err(e) => {
+                                self.reject_path(chat, &mut history, format!("Search {}", query.pattern), &e).await;
                                 continue;
                             }
-                        }
+                        };
 
-                        let projects_root = {
-                            let f = self.feed.lock().await;
-                            f.projects_root()
+                        let client = self.tools.lock().await;
+                        let result = client
+                            .search(&resolved_path.to_string_lossy(), &label, &query)
+                            .await;
+                        drop(client);
+
+                        let (out, feed_label, success) = match result {
+                            Ok(found) if found.hits == 0 => (
+                                format!("No matches for `{}` in {}.", query.pattern, label),
+                                format!("Searched {} (0 hits)", query.pattern),
+                                true,
+                            ),
+                            Ok(found) => {
+                                let mut out = format!(
+                                    "Search `{}` in {}: {} hit(s) in {} file(s)\n{}",
+                                    query.pattern, label, found.hits, found.files, found.text
+                                );
+                                if found.truncated {
+                                    out.push_str(&format!(
+                                        "[Stopped after {} hits. Narrow the path or glob.]\n",
+                                        crate::infrastructure::tools::search::MAX_HITS
+                                    ));
+                                }
+                                let plus = if found.truncated { "+" } else { "" };
+                                (out, format!("Searched {} ({}{} hits)", query.pattern, found.hits, plus), true)
+                            }
+                            Err(e) => (
+                                format!("Error searching: {}", e),
+                                format!("Search {}", query.pattern),
+                                false,
+                            ),
                         };
-                        let root_to_use = working_dir.as_deref().or(projects_root.as_deref());
-                        let sanitized = crate::application::utils::sanitize_path(
-                            &path,
-                            root_to_use,
-                        );
 
                         {
                             let mut feed = self.feed.lock().await;
-                            feed.add_activity(format!("Writing {}", sanitized));
+                            feed.replace_last_activity(feed_label, success);
                             let _ = feed.update_feed(chat).await;
                         }
 
-                        let client = self.tools.lock().await;
-                        // Resolve path against working_dir
-                        let resolved_path = if Path::new(&path).is_absolute() {
-                            if let Some(root) = projects_root.as_deref() {
-                                if path.starts_with(root) {
-                                    path.clone()
-                                } else {
-                                    let stripped = path.trim_start_matches('/');
-                                    format!("{}/{}", root.trim_end_matches('/'), stripped)
-                                }
-                            } else {
-                                path.clone()
-                            }
-                        } else {
-                            if let Some(wd) = &working_dir {
-                                format!("{}/{}", wd, path)
-                            } else {
-                                path.clone()
+                        history.push_str(&format!("\nSystem: {}\n", resolver.scrub(&out)));
+                    }
+                    crate::domain::types::AgentAction::Outline(path) => {
+                        let label = resolver.display(&path);
+                        {
+                            let mut feed = self.feed.lock().await;
+                            feed.add_activity(format!("Outlining {}", label));
+                            let _ = feed.update_feed(chat).await;
+                        }
+
+                        let resolved_path = match resolver.resolve(&path) {
+                            Ok(p) => p,
+                            Err(e) => {
+                                self.reject_path(chat, &mut history, format!("Outline {}", label), &e).await;
+                                continue;
                             }
                         };
 
-                        let result = client.write_file(&resolved_path, &content).await;
+                        let client = self.tools.lock().await;
+                        let result = client.outline(&resolved_path.to_string_lossy(), &label).await;
+                        drop(client);
                         let (out, success) = match result {
-                            Ok(_) => ("File written successfully".to_string(), true),
-                            Err(e) => (format!("Error writing file: {}", e), false),
+                            Ok(o) => (o, true),
+                            Err(e) => (resolver.scrub(&format!("Error outlining: {}", e)), false),
                         };
-
                         {
                             let mut feed = self.feed.lock().await;
-                            if success {
-                                feed.replace_last_activity(
-                                    format!("Wrote {}", sanitized),
-                                    true,
-                                );
-                            } else {
-                                feed.replace_last_activity(
-                                    format!("Write {}", sanitized),
-                                    false,
-                                );
-                                // Keep error details for failure
+                            feed.replace_last_activity(format!("Outlined {}", label), success);
+                            if !success {
                                 feed.update_last_entry(out.clone(), false);
                             }
                             let _ = feed.update_feed(chat).await;
                         }
-                        history.push_str(&format!("\nOutput: {}\n", out));
+                        history.push_str(&format!("\nOutput:\n{}\n", out));
                     }
-                    crate::domain::types::AgentAction::ReadFile(path) => {
+                    crate::domain::types::AgentAction::FindSymbol(name) => {
                         {
                             let mut feed = self.feed.lock().await;
-                            feed.add_activity(format!("Reading file {}", path));
+                            feed.add_activity(format!("Looking up {}", name));
                             let _ = feed.update_feed(chat).await;
                         }
-                        let client = self.tools.lock().await;
-
-                        let projects_root = {
-                            let f = self.feed.lock().await;
-                            f.projects_root()
-                        };
 
-                        let resolved_path = if Path::new(&path).is_absolute() {
-                            if let Some(root) = projects_root.as_deref() {
-                                if path.starts_with(root) {
-                                    path.clone()
-                                } else {
-                                    let stripped = path.trim_start_matches('/');
-                                    format!("{}/{}", root.trim_end_matches('/'), stripped)
+                        let mut symbols = self.symbols.lock().await;
+                        if symbols.is_none() {
+                            match self.tools.lock().await.index_symbols(resolver.root()) {
+                                Ok(index) => *symbols = Some(index),
+                                Err(e) => {
+                                    let out = resolver.scrub(&format!("Error indexing symbols: {}", e));
+                                    let mut feed = self.feed.lock().await;
+                                    feed.replace_last_activity(format!("Look up {}", name), false);
+                                    feed.update_last_entry(out.clone(), false);
+                                    let _ = feed.update_feed(chat).await;
+                                    history.push_str(&format!("\nSystem: {}\n", out));
+                                    continue;
                                 }
-                            } else {
-                                path.clone()
                             }
+                        }
+                        let matches = symbols
+                            .as_ref()
+                            .map(|index| index.lookup(&name))
+                            .unwrap_or_default();
+                        let out = if matches.is_empty() {
+                            format!(
+                                "No definition of `{}` found. Try a shorter name, or ```search``` for it.",
+                                name
+                            )
                         } else {
-                            if let Some(wd) = &working_dir {
-                                format!("{}/{}", wd, path)
-                            } else {
-                                path.clone()
+                            let mut out = format!("Definitions matching `{}`:\n", name);
+                            for (file, symbol) in &matches {
+                                out.push_str(&format!(
+                                    "{}:{}: {} {}\n",
+                                    file,
+                                    symbol.line,
+                                    symbol.kind,
+                                    symbol.qualified()
+                                ));
                             }
+                            let (file, symbol) = matches[0];
+                            out.push_str(&format!(
+                                "Read one with ```read {} around {}```.",
+                                file, symbol.line
+                            ));
+                            out
                         };
+                        let found = matches.len();
+                        drop(symbols);
 
-                        let result = client.read_file(&resolved_path).await;
-                        let (out, success) = match result {
-                            Ok(c) => (c, true),
-                            Err(e) => (format!("Error reading file: {}", e), false),
-                        };
                         {
                             let mut feed = self.feed.lock().await;
-                            
-                            // Sanitize for display
-                            // Prefer working_dir (current project root) to sanitize redundant project prefixes
-                            let root_to_use = working_dir.as_deref().or(projects_root.as_deref());
-                            let sanitized = crate::application::utils::sanitize_path(
-                                &path, 
-                                root_to_use
-                            );
-
-                            if success {
-                                feed.replace_last_activity(format!("Read {}", sanitized), true);
-                                // Don't show full content in feed, maybe irrelevant
-                                // Don't show full content or byte count in feed
-
-                            } else {
-                                feed.replace_last_activity(format!("Read {}", sanitized), false);
-                                feed.update_last_entry(out.clone(), false);
-                            }
+                            feed.replace_last_activity(format!("Looked up {} ({} found)", name, found), true);
                             let _ = feed.update_feed(chat).await;
                         }
-                        history.push_str(&format!("\nOutput:\n{}\n", out));
+                        history.push_str(&format!("\nSystem: {}\n", out));
                     }
-                    crate::domain::types::AgentAction::ShellCommand(cmd) => {
+                    crate::domain::types::AgentAction::WriteFile(path, content) => {
                         // SAFETY CHECK: Enforce Planning constraints
-                        // ABSOLUTELY NO COMMANDS in Planning/New Project phase.
-                        if task_phase == crate::application::state::TaskPhase::Planning
-                            || task_phase == crate::application::state::TaskPhase::NewProject
+                        // If in Planning phase, ONLY allow .md (or .txt/yaml/json?) files.
+                        // Strictly forbid .rs, .py, etc.
+                        if (task_phase == crate::application::state::TaskPhase::Planning
+                            || task_phase == crate::application::state::TaskPhase::NewProject)
+                            && !path.ends_with(".md")
+                            && !path.ends_with(".txt")
+                            && !path.ends_with(".yaml")
+                            && !path.ends_with(".json")
                         {
                             let err_msg = format!(
-                                "PERMISSION DENIED: You are in the PLANNING phase. You cannot run commands (`{}`) yet. You are strictly limited to documentation. Output `NO_MORE_STEPS` if you are done.",
-                                cmd
+                                "PERMISSION DENIED: You are in the PLANNING phase. You cannot write code files (`{}`) yet. You can only write documentation (.md, .txt, .yaml, .json). If you have finished the plan, output `NO_MORE_STEPS`.",
+                                path
                             );
                             history.push_str(&format!("\nSystem: {}\n", err_msg));
 
-                            // Silent Rejection in Feed
+                            // Update feed to show the rejection?
+                            {
+                                let mut feed = self.feed.lock().await;
+                                feed.add_activity(format!(
+                                    "⚠️ Blocked write to {} (Planning Only)",
+                                    path
+                                ));
+                                let _ = feed.update_feed(chat).await;
+                            }
                             continue;
                         }
 
-                        // Update Feed (Only if safe/allowed phase)
+                        let label = resolver.display(&path);
+                        if let Some(scope) = &self.delegation
+                            && !scope.allows(&label)
                         {
+                            history.push_str(&format!(
+                                "\nSystem: PERMISSION DENIED: `{}` is outside your sub-task scope ({}).\n",
+                                label,
+                                scope.files.join(", ")
+                            ));
                             let mut feed = self.feed.lock().await;
-                            feed.process_action(&crate::domain::types::AgentAction::ShellCommand(
-                                cmd.clone(),
-                            ))
-                            .await;
+                            feed.add_activity(format!("⚠️ Blocked write to {} (outside sub-task scope)", label));
+                            let _ = feed.update_feed(chat).await;
+                            continue;
+                        }
+                        if let Some(failure) =
+                            self.run_hooks(chat, &hooks.pre, HookEvent::Write, Some(&label), &resolver, true).await
+                        {
+                            history.push_str(&format!(
+                                "\nSystem: HOOK BLOCKED: `{}` was not written: {}\n",
+                                label,
+                                failure.to_history()
+                            ));
+                            continue;
+                        }
+                        {
+                            let mut feed = self.feed.lock().await;
+                            feed.add_activity(format!("Writing {}", label));
                             let _ = feed.update_feed(chat).await;
                         }
 
-                        // Safety Check
+                        let resolved_path = match resolver.resolve(&path) {
+                            Ok(p) => p,
+                            Err(e) => {
+                                self.reject_path(chat, &mut history, format!("Write {}", label), &e).await;
+                                continue;
+                            }
+                        };
+
+                        let checklist_before = self.checklist_before(&resolved_path, checklist_path.as_deref()).await;
+                        let client = self.tools.lock().await;
+                        // Project changes of the Developer go into the walkthrough (task documents do not)
+                        let previous = if task_phase == crate::application::state::TaskPhase::Execution
+                            && !label.starts_with("tasks/")
+                        {
+                            Some(client.read_file(&resolved_path.to_string_lossy()).await.ok())
+                        } else {
+                            None
+                        };
+                        let result = client.write_file(&resolved_path.to_string_lossy(), &content).await;
+                        drop(client);
+                        let (mut out, mut success) = match result {
+                            Ok(_) => ("File written successfully".to_string(), true),
+                            Err(e) => (resolver.scrub(&format!("Error writing file: {}", e)), false),
+                        };
+                        if success
+                            && let Some(before) = checklist_before
+                            && let Some(rejection) = self.enforce_checklist(&resolved_path, &before).await
+                        {
+                            (out, success) = (rejection, false);
+                        }
+                        if success {
+                            wrote_files = true;
+                            // Post hooks (e.g. a formatter) run before the index picks up the file
+                            if let Some(failure) =
+                                self.run_hooks(chat, &hooks.post, HookEvent::Write, Some(&label), &resolver, false).await
+                            {
+                                out.push_str(&format!("\nWarning: {}", failure.to_history()));
+                            }
+                            self.reindex(&resolver, &resolved_path).await;
+                        }
+                        if success && task_phase == crate::application::state::TaskPhase::Execution {
+                            unverified_writes = true;
+                        }
+                        if success && let Some(previous) = previous {
+                            let (added, removed) = crate::infrastructure::tools::diff::line_stats(
+                                previous.as_deref().unwrap_or(""),
+                                &content,
+                            );
+                            self.action_log.lock().await.record(LoggedAction::File {
+                                path: label.clone(),
+                                added,
+                                removed,
+                                created: previous.is_none(),
+                            });
+                        }
+
+                        {
+                            let mut feed = self.feed.lock().await;
+                            if success {
+                                feed.replace_last_activity(format!("Wrote {}", label), true);
+                            } else {
+                                feed.replace_last_activity(format!("Write {}", label), false);
+                                // Keep error details for failure
+                                feed.update_last_entry(out.clone(), false);
+                            }
+                            let _ = feed.update_feed(chat).await;
+                        }
+                        history.push_str(&format!("\nOutput: {}\n", out));
+                    }
+                    crate::domain::types::AgentAction::EditFile(path, patch) => {
+                        // Same Planning constraints as WriteFile: documentation only
+                        if (task_phase == crate::application::state::TaskPhase::Planning
+                            || task_phase == crate::application::state::TaskPhase::NewProject)
+                            && !path.ends_with(".md")
+                            && !path.ends_with(".txt")
+                            && !path.ends_with(".yaml")
+                            && !path.ends_with(".json")
+                        {
+                            let err_msg = format!(
+                                "PERMISSION DENIED: You are in the PLANNING phase. You cannot edit code files (`{}`) yet. You can only edit documentation (.md, .txt, .yaml, .json).",
+                                path
+                            );
+                            history.push_str(&format!("\nSystem: {}\n", err_msg));
+                            {
+                                let mut feed = self.feed.lock().await;
+                                feed.add_activity(format!("⚠️ Blocked edit to {} (Planning Only)", path));
+                                let _ = feed.update_feed(chat).await;
+                            }
+                            continue;
+                        }
+
+                        let label = resolver.display(&path);
+                        if let Some(scope) = &self.delegation
+                            && !scope.allows(&label)
+                        {
+                            history.push_str(&format!(
+                                "\nSystem: PERMISSION DENIED: `{}` is outside your sub-task scope ({}).\n",
+                                label,
+                                scope.files.join(", ")
+                            ));
+                            let mut feed = self.feed.lock().await;
+                            feed.add_activity(format!("⚠️ Blocked edit to {} (outside sub-task scope)", label));
+                            let _ = feed.update_feed(chat).await;
+                            continue;
+                        }
+                        if let Some(failure) =
+                            self.run_hooks(chat, &hooks.pre, HookEvent::Write, Some(&label), &resolver, true).await
+                        {
+                            history.push_str(&format!(
+                                "\nSystem: HOOK BLOCKED: `{}` was not edited: {}\n",
+                                label,
+                                failure.to_history()
+                            ));
+                            continue;
+                        }
+                        {
+                            let mut feed = self.feed.lock().await;
+                            feed.add_activity(format!("Editing {}", label));
+                            let _ = feed.update_feed(chat).await;
+                        }
+
+                        let resolved_path = match resolver.resolve(&path) {
+                            Ok(p) => p,
+                            Err(e) => {
+                                self.reject_path(chat, &mut history, format!("Edit {}", label), &e).await;
+                                continue;
+                            }
+                        };
+
+                        let checklist_before = self.checklist_before(&resolved_path, checklist_path.as_deref()).await;
+                        let client = self.tools.lock().await;
+                        let result = client.edit_file(&resolved_path.to_string_lossy(), &patch).await;
+                        drop(client);
+                        let (mut out, mut success) = match &result {
+                            Ok(o) => (
+                                format!("Edit applied to {} (+{}/-{})", label, o.added, o.removed),
+                                true,
+                            ),
+                            Err(e) => (resolver.scrub(&format!("Error editing file: {}", e)), false),
+                        };
+                        if success
+                            && let Some(before) = checklist_before
+                            && let Some(rejection) = self.enforce_checklist(&resolved_path, &before).await
+                        {
+                            (out, success) = (rejection, false);
+                        }
+                        if success {
+                            wrote_files = true;
+                            // Post hooks (e.g. a formatter) run before the index picks up the file
+                            if let Some(failure) =
+                                self.run_hooks(chat, &hooks.post, HookEvent::Write, Some(&label), &resolver, false).await
+                            {
+                                out.push_str(&format!("\nWarning: {}", failure.to_history()));
+                            }
+                            self.reindex(&resolver, &resolved_path).await;
+                        }
+                        if success && task_phase == crate::application::state::TaskPhase::Execution {
+                            unverified_writes = true;
+                            if let Ok(o) = &result
+                                && !label.starts_with("tasks/")
+                            {
+                                self.action_log.lock().await.record(LoggedAction::File {
+                                    path: label.clone(),
+                                    added: o.added,
+                                    removed: o.removed,
+                                    created: false,
+                                });
+                            }
+                        }
+
+                        {
+                            let mut feed = self.feed.lock().await;
+                            match &result {
+                                Ok(o) if success => feed.replace_last_activity(
+                                    format!("Edited {} (+{}/-{})", label, o.added, o.removed),
+                                    true,
+                                ),
+                                _ => {
+                                    feed.replace_last_activity(format!("Edit {}", label), false);
+                                    feed.update_last_entry(out.clone(), false);
+                                }
+                            }
+                            let _ = feed.update_feed(chat).await;
+                        }
+                        history.push_str(&format!("\nOutput: {}\n", out));
+                    }
+                    crate::domain::types::AgentAction::ReadFile(path, range) => {
+                        let label = resolver.display(&path);
+                        {
+                            let mut feed = self.feed.lock().await;
+                            feed.add_activity(format!("Reading file {}", label));
+                            let _ = feed.update_feed(chat).await;
+                        }
+
+                        let resolved_path = match resolver.resolve(&path) {
+                            Ok(p) => p,
+                            Err(e) => {
+                                self.reject_path(chat, &mut history, format!("Read {}", label), &e).await;
+                                continue;
+                            }
+                        };
+
+                        let client = self.tools.lock().await;
+                        let result = client
+                            .read_range(&resolved_path.to_string_lossy(), &label, range.as_ref())
+                            .await;
+                        let (out, success) = match result {
+                            Ok(c) => (c, true),
+                            Err(e) => (resolver.scrub(&format!("Error reading file: {}", e)), false),
+                        };
+                        {
+                            let mut feed = self.feed.lock().await;
+                            if success {
+                                // Don't show full content or byte count in feed
+                                let lines = match &range {
+                                    Some(crate::domain::types::LineRange::Span(start, Some(end))) => {
+                                        format!(" (lines {}-{})", start, end)
+                                    }
+                                    Some(crate::domain::types::LineRange::Span(start, None)) => {
+                                        format!(" (from line {})", start)
+                                    }
+                                    Some(crate::domain::types::LineRange::Around(line)) => {
+                                        format!(" (around line {})", line)
+                                    }
+                                    None => String::new(),
+                                };
+                                feed.replace_last_activity(format!("Read {}{}", label, lines), true);
+                            } else {
+                                feed.replace_last_activity(format!("Read {}", label), false);
+                                feed.update_last_entry(out.clone(), false);
+                            }
+                            let _ = feed.update_feed(chat).await;
+                        }
+                        history.push_str(&format!("\nOutput:\n{}\n", out));
+                    }
+                    crate::domain::types::AgentAction::ShellCommand(cmd) => {
+                        // SAFETY CHECK: Enforce Planning constraints
+                        // ABSOLUTELY NO COMMANDS in Planning/New Project phase.
+                        if task_phase == crate::application::state::TaskPhase::Planning
+                            || task_phase == crate::application::state::TaskPhase::NewProject
+                        {
+                            let err_msg = format!(
+                                "PERMISSION DENIED: You are in the PLANNING phase. You cannot run commands (`{}`) yet. You are strictly limited to documentation. Output `NO_MORE_STEPS` if you are done.",
+                                cmd
+                            );
+                            history.push_str(&format!("\nSystem: {}\n", err_msg));
+
+                            // Silent Rejection in Feed
+                            continue;
+                        }
+
+                        if let Some(failure) =
+                            self.run_hooks(chat, &hooks.pre, HookEvent::Command, Some(&cmd), &resolver, true).await
+                        {
+                            history.push_str(&format!(
+                                "\nSystem: HOOK BLOCKED: `{}` was not run: {}\n",
+                                cmd,
+                                failure.to_history()
+                            ));
+                            continue;
+                        }
+
+                        // Update Feed (Only if safe/allowed phase)
+                        {
+                            let mut feed = self.feed.lock().await;
+                            feed.process_action(&crate::domain::types::AgentAction::ShellCommand(
+                                cmd.clone(),
+                            ))
+                            .await;
+                            let _ = feed.update_feed(chat).await;
+                        }
+
+                        // Safety Check
                         let projects_root = {
                             let f = self.feed.lock().await;
                             f.projects_root()
                         };
 
+                        // Commands the project allows skip the check
+                        let allowed = self._config.project.as_ref().is_some_and(|p| {
+                            crate::infrastructure::tools::executor::command_allowed(&cmd, &p.commands.allowed)
+                        });
+
                         // If checking safety fails, ask for permission
-                        if !crate::application::utils::check_command_safety(
-                            &cmd,
-                            projects_root.as_deref(),
-                        ) {
+                        if !allowed
+                            && !crate::application::utils::check_command_safety(
+                                &cmd,
+                                projects_root.as_deref(),
+                            )
+                        {
                             let (tx, rx) = tokio::sync::oneshot::channel();
 
                             {
@@ -839,6 +1515,13 @@ impl ExecutionEngine {
 
                             let _ = chat.send_notification(&format!("⚠️ **Security Alert**: Command `{}` uses absolute path outside project root.\nReply `.approve` to allow, `.deny` to skip.", cmd)).await;
 
+                            // Record the pending approval so a restart does not silently lose it
+                            checkpoint.phase = task_phase.clone();
+                            checkpoint.steps = steps;
+                            checkpoint.history = history.clone();
+                            checkpoint.pending_approval = Some(cmd.clone());
+                            self.persist_checkpoint(&chat.room_id(), &mut checkpoint).await;
+
                             // Wait for approval
                             match rx.await {
                                 Ok(true) => {
@@ -867,7 +1550,7 @@ impl ExecutionEngine {
                         // We use a simplified direct execution for now, assuming ToolExecutor handles safety/timeouts logic
                         let client = self.tools.lock().await;
                         let output = client
-                            .execute_command(&cmd, Path::new(working_dir.as_deref().unwrap_or(".")))
+                            .execute_command(&cmd, resolver.root())
                             .await;
 
                         let (out_str, success) = match output {
@@ -899,7 +1582,102 @@ impl ExecutionEngine {
                             let _ = feed.update_feed(chat).await;
                         }
 
-                        history.push_str(&format!("\nOutput:\n{}\n", out_str));
+                        if task_phase == crate::application::state::TaskPhase::Execution {
+                            self.action_log.lock().await.record(LoggedAction::Command {
+                                command: resolver.scrub(&cmd),
+                                success: refined_success,
+                            });
+                        }
+
+                        let mut out_str = out_str;
+                        if let Some(failure) =
+                            self.run_hooks(chat, &hooks.post, HookEvent::Command, Some(&cmd), &resolver, false).await
+                        {
+                            out_str.push_str(&format!("\nWarning: {}", failure.to_history()));
+                        }
+
+                        // Commands can create, move or delete files: rebuild the index and map lazily
+                        *self.symbols.lock().await = None;
+                        *self.repo_map.lock().await = None;
+
+                        history.push_str(&format!("\nOutput:\n{}\n", resolver.scrub(&out_str)));
+                    }
+                    crate::domain::types::AgentAction::Delegate(goal, files, budget) => {
+                        let depth = self.delegation.as_ref().map(|d| d.depth).unwrap_or(0);
+                        if task_phase != crate::application::state::TaskPhase::Execution {
+                            history.push_str("\nSystem: Delegation is only available in the EXECUTION phase.\n");
+                            continue;
+                        }
+                        if depth >= crate::application::delegation::MAX_DEPTH {
+                            history.push_str("\nSystem: Sub-agents cannot delegate any further. Do the work yourself.\n");
+                            continue;
+                        }
+
+                        let title = goal.lines().next().unwrap_or(&goal).to_string();
+                        let budget = match budget {
+                            0 => budgets.delegate_steps.unwrap_or(0),
+                            n => n,
+                        };
+                        let scope = DelegationScope::new(depth + 1, &goal, files, budget);
+                        {
+                            let mut feed = self.feed.lock().await;
+                            feed.add_activity(format!("Delegating: {}", title));
+                            let _ = feed.update_feed(chat).await;
+                            feed.set_nesting(depth + 1);
+                        }
+
+                        let result = self
+                            .run_child(chat, scope, agent_name, working_dir.clone())
+                            .await;
+
+                        {
+                            let mut feed = self.feed.lock().await;
+                            feed.set_nesting(depth);
+                        }
+
+                        // A stopped child returns early; the stop check before the next action ends this run too
+                        let success = result.is_ok();
+                        let summary = match result {
+                            Ok(Some(summary)) if !summary.is_empty() => summary,
+                            Ok(_) => "(Sub-agent finished without a summary)".to_string(),
+                            Err(e) => format!("Sub-agent failed: {}", e),
+                        };
+                        // The child may have written code
+                        unverified_writes = true;
+
+                        {
+                            let mut feed = self.feed.lock().await;
+                            feed.add_finished_activity(format!("Sub-agent done: {}", title), success);
+                            let _ = feed.update_feed(chat).await;
+                        }
+                        history.push_str(&format!(
+                            "\nSystem: Sub-agent result for \"{}\":\n{}\n",
+                            goal, summary
+                        ));
+                    }
+                    crate::domain::types::AgentAction::AskUser(question, options) => {
+                        // Persist first: waiting for a human can take a long time
+                        checkpoint.phase = task_phase.clone();
+                        checkpoint.steps = steps;
+                        checkpoint.history = history.clone();
+                        self.persist_checkpoint(&chat.room_id(), &mut checkpoint).await;
+
+                        // Guidance sent before the question is guidance, not the answer
+                        for guidance in self.drain_input().await {
+                            history.push_str(&format!("\nUser: {}\n", guidance));
+                        }
+
+                        match self.ask_user(chat, &question, &options).await {
+                            Some(answer) => {
+                                history.push_str(&format!("\nUser: {}\n", answer));
+                            }
+                            None => {
+                                history.push_str(&format!(
+                                    "\nSystem: No answer within {} minutes. Make the most reasonable decision yourself and note it in the walkthrough.\n",
+                                    ANSWER_TIMEOUT_MINUTES
+                                ));
+                            }
+                        }
                     }
                     crate::domain::types::AgentAction::SwitchMode(phase) => {
                         tracing::info!(
@@ -977,11 +1755,679 @@ impl ExecutionEngine {
                         break;
                     }
                 }
+
+                // Loop detection: same action, same result as an earlier step
+                let outcome = &history[history_mark..];
+                match stagnation.observe(&observed, outcome) {
+                    Stagnation::Progress => {}
+                    Stagnation::Repeated => {
+                        history.push_str(&format!(
+                            "\nSystem: LOOP DETECTED: You already did `{}` and got the same result. Repeating it will not help. Use the information you already have, or try a different approach.\n",
+                            crate::application::stagnation::describe(&observed)
+                        ));
+                        let mut feed = self.feed.lock().await;
+                        feed.add_activity(format!(
+                            "⚠️ Repeated action: {}",
+                            crate::application::stagnation::describe(&observed)
+                        ));
+                        let _ = feed.update_feed(chat).await;
+                    }
+                    Stagnation::Stuck => {
+                        if !self.ask_to_continue(chat, &observed).await {
+                            self.clear_checkpoint(&chat.room_id(), working_dir.as_deref()).await;
+                            return Ok(None);
+                        }
+                        stagnation.reset();
+                        history.push_str("\nSystem: The user reviewed the repeated actions and asked you to continue. Change your approach.\n");
+                    }
+                }
+            }
+
+            if wrote_files
+                && let Some(failure) =
+                    self.run_hooks(chat, &hooks.post, HookEvent::Batch, None, &resolver, false).await
+            {
+                history.push_str(&format!("\nSystem: Post-step {}\n", failure.to_history()));
+            }
+
+            // Verify the batch of writes from this step
+            if unverified_writes
+                && let Some(report) = self.run_verification(chat, working_dir.as_deref()).await
+            {
+                history.push_str(&format!("\nSystem: {}\n", report.to_history()));
             }
+
+            // Walkthrough section for every checklist item ticked in this step (before the commit picks it up)
+            if !is_child && task_phase == crate::application::state::TaskPhase::Execution {
+                self.document_checked_items(&chat.room_id(), working_dir.as_deref(), &task_title, &mut documented_items)
+                    .await;
+            }
+
+            // Commit on the task branch for every checklist item ticked in this step
+            if !is_child {
+                self.commit_checked_items(chat, working_dir.as_deref(), &mut committed_items)
+                    .await;
+            }
+
+            // Step finished: persist a crash-safe checkpoint
+            checkpoint.phase = task_phase.clone();
+            checkpoint.steps = steps;
+            checkpoint.history = history.clone();
+            checkpoint.pending_approval = None;
+            self.persist_checkpoint(&chat.room_id(), &mut checkpoint).await;
         }
 
         Ok(None) // Loop finished
     }
+
+    /// Runs the hooks of one action stage and shows each in the feed.
+    /// Returns the first failing (or rejecting) hook.
+    async fn run_hooks(
+        &self,
+        chat: &impl ChatProvider,
+        hooks: &[Hook],
+        event: HookEvent,
+        subject: Option<&str>,
+        resolver: &PathResolver,
+        pre: bool,
+    ) -> Option<crate::application::hooks::HookResult> {
+        if !hooks.iter().any(|h| h.applies(event, subject)) {
+            return None;
+        }
+        let results =
+            crate::application::hooks::run(&self.tools, hooks, event, subject, resolver.root(), pre).await;
+        {
+            let mut feed = self.feed.lock().await;
+            for r in &results {
+                let label = if r.command == "reject" {
+                    format!("Hook rejected {}", subject.unwrap_or_default())
+                } else {
+                    format!("Hook: {}", r.command)
+                };
+                feed.add_finished_activity(label, r.success);
+                if !r.success {
+                    feed.update_last_entry(resolver.scrub(&r.output), false);
+                }
+            }
+            let _ = feed.update_feed(chat).await;
+        }
+        results.into_iter().find(|r| !r.success).map(|mut r| {
+            r.output = resolver.scrub(&r.output);
+            r
+        })
+    }
+
+    /// Runs the project's verification pipeline, shows each command in the feed
+    /// and records the outcome on the room. Returns `None` if no pipeline is configured.
+    async fn run_verification(
+        &self,
+        chat: &impl ChatProvider,
+        working_dir: Option<&str>,
+    ) -> Option<crate::application::verification::VerificationReport> {
+        let wd = working_dir?;
+        let pipeline = self._config.verification.pipeline_for(wd);
+        if pipeline.is_empty() {
+            return None;
+        }
+
+        {
+            let mut feed = self.feed.lock().await;
+            feed.add_activity(format!("Verifying: {}", pipeline.join(" && ")));
+            let _ = feed.update_feed(chat).await;
+        }
+
+        let report =
+            crate::application::verification::run_pipeline(&self.tools, &pipeline, Path::new(wd)).await;
+
+        {
+            let mut guard = self.state.lock().await;
+            let room = guard.get_room_state(&chat.room_id());
+            room.verification_failing = !report.is_green();
+        }
+        self.action_log.lock().await.record(LoggedAction::Verification {
+            summary: report.summary(),
+            success: report.is_green(),
+        });
+
+        {
+            let mut feed = self.feed.lock().await;
+            if report.is_green() {
+                feed.replace_last_activity(format!("Verified ({})", report.summary()), true);
+            } else {
+                let failed = report
+                    .results
+                    .iter()
+                    .find(|r| !r.success)
+                    .map(|r| r.command.clone())
+                    .unwrap_or_default();
+                feed.replace_last_activity(format!("Verification: {} ({})", failed, report.summary()), false);
+                if let Some(r) = report.results.iter().find(|r| !r.success) {
+                    feed.update_last_entry(r.output.clone(), false);
+                }
+            }
+            let _ = feed.update_feed(chat).await;
+        }
+
+        Some(report)
+    }
+
+    /// Writes the checkpoint into the active task folder (skipped for `.ask` conversations).
+    async fn persist_checkpoint(&self, room_id: &str, checkpoint: &mut TaskCheckpoint) {
+        // Sub-agents run inside the parent's step; the parent's checkpoint covers them
+        if self.delegation.is_some()
+            || checkpoint.phase == crate::application::state::TaskPhase::Assistant
+        {
+            return;
+        }
+        let task_rel = {
+            let guard = self.state.lock().await;
+            guard.rooms.get(room_id).and_then(|r| r.active_task.clone())
+        };
+        let (Some(wd), Some(task_rel)) = (checkpoint.working_dir.clone(), task_rel) else {
+            return;
+        };
+        // A dry run leaves the disk alone; its checkpoint only lives in memory
+        if self.tools.lock().await.in_dry_run(Path::new(&wd)) {
+            return;
+        }
+
+        checkpoint.feed = Some(self.feed.lock().await.snapshot());
+        checkpoint.action_log = self.action_log.lock().await.clone();
+        checkpoint.updated_at = chrono::Utc::now().timestamp();
+        if let Err(e) = checkpoint.save(&wd, &task_rel) {
+            tracing::warn!("Failed to write task checkpoint: {}", e);
+        }
+    }
+
+    /// Removes the checkpoint once the run ends normally.
+    async fn clear_checkpoint(&self, room_id: &str, working_dir: Option<&str>) {
+        if self.delegation.is_some() {
+            return;
+        }
+        let task_rel = {
+            let guard = self.state.lock().await;
+            guard.rooms.get(room_id).and_then(|r| r.active_task.clone())
+        };
+        // A dry run never wrote one, and must not remove one left by a real run
+        if let (Some(wd), Some(task_rel)) = (working_dir, task_rel)
+            && !self.tools.lock().await.in_dry_run(Path::new(wd))
+        {
+            TaskCheckpoint::clear(wd, &task_rel);
+        }
+    }
+
+    /// Current content of the active task's tasks.md if `path` is that file, so that
+    /// `enforce_checklist` can compare the agent's change against it.
+    async fn checklist_before(&self, path: &Path, checklist_path: Option<&Path>) -> Option<String> {
+        if checklist_path != Some(path) {
+            return None;
+        }
+        self.tools.lock().await.read_file(&path.to_string_lossy()).await.ok()
+    }
+
+    /// Restores tasks.md if the agent's change dropped or silently unticked items.
+    /// Returns the rejection to report back, or `None` if the change is fine.
+    async fn enforce_checklist(&self, path: &Path, before: &str) -> Option<String> {
+        let client = self.tools.lock().await;
+        let after = client.read_file(&path.to_string_lossy()).await.ok()?;
+        let violations = Checklist::parse(before).violations(&Checklist::parse(&after));
+        if violations.is_empty() {
+            return None;
+        }
+        if let Err(e) = client.write_file(&path.to_string_lossy(), before).await {
+            tracing::warn!("Failed to restore tasks.md: {}", e);
+        }
+        let list: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+        Some(format!(
+            "CHECKLIST CHANGE REJECTED: the change {}. tasks.md was restored. Keep every item: mark items you will not do as `[-]`, and append `{}: reason)` when unticking an item.",
+            list.join(", "),
+            crate::domain::checklist::REOPEN_MARKER
+        ))
+    }
+
+    /// Items of the active task's tasks.md that are still open.
+    async fn open_items(&self, room_id: &str, working_dir: Option<&str>) -> Vec<String> {
+        let task_rel = {
+            let guard = self.state.lock().await;
+            guard.rooms.get(room_id).and_then(|r| r.active_task.clone())
+        };
+        let (Some(wd), Some(task_rel)) = (working_dir, task_rel) else {
+            return Vec::new();
+        };
+        let Ok(content) = self
+            .tools
+            .lock()
+            .await
+            .read_file(&format!("{}/{}/tasks.md", wd, task_rel))
+            .await
+        else {
+            return Vec::new();
+        };
+        Checklist::parse(&content)
+            .open_items()
+            .into_iter()
+            .map(|i| i.text.clone())
+            .collect()
+    }
+
+    /// Ticked items of the active task's tasks.md.
+    async fn checked_items(&self, room_id: &str, working_dir: Option<&str>) -> Vec<String> {
+        let task_rel = {
+            let guard = self.state.lock().await;
+            guard.rooms.get(room_id).and_then(|r| r.active_task.clone())
+        };
+        let (Some(wd), Some(task_rel)) = (working_dir, task_rel) else {
+            return Vec::new();
+        };
+        let content = self
+            .tools
+            .lock()
+            .await
+            .read_file(&format!("{}/{}/tasks.md", wd, task_rel))
+            .await
+            .unwrap_or_default();
+        crate::infrastructure::tools::git::checked_items(&content)
+    }
+
+    /// Adds a walkthrough.md section, built from the action log, for checklist items ticked
+    /// since the last call.
+    async fn document_checked_items(
+        &self,
+        room_id: &str,
+        working_dir: Option<&str>,
+        title: &str,
+        documented: &mut Vec<String>,
+    ) {
+        let new_items: Vec<String> = self
+            .checked_items(room_id, working_dir)
+            .await
+            .into_iter()
+            .filter(|item| !documented.contains(item))
+            .collect();
+        let Some(section) = self.action_log.lock().await.complete(&new_items) else {
+            return;
+        };
+        documented.extend(new_items);
+
+        let task_rel = {
+            let guard = self.state.lock().await;
+            guard.rooms.get(room_id).and_then(|r| r.active_task.clone())
+        };
+        let (Some(wd), Some(task_rel)) = (working_dir, task_rel) else {
+            return;
+        };
+        let path = format!("{}/{}/walkthrough.md", wd, task_rel);
+        let client = self.tools.lock().await;
+        let doc = client
+            .read_file(&path)
+            .await
+            .unwrap_or_else(|_| crate::strings::templates::WALKTHROUGH_TEMPLATE.to_string());
+        let updated = crate::application::action_log::add_to_walkthrough(&doc, title, &section);
+        if let Err(e) = client.write_file(&path, &updated).await {
+            tracing::warn!("Failed to update walkthrough.md: {}", e);
+        }
+    }
+
+    /// Appends the task's entry to progress.md and marks its walkthrough as completed.
+    async fn log_progress(&self, room_id: &str, working_dir: Option<&str>, title: &str, outcome: &str) {
+        let task_rel = {
+            let guard = self.state.lock().await;
+            guard.rooms.get(room_id).and_then(|r| r.active_task.clone())
+        };
+        let (Some(wd), Some(task_rel)) = (working_dir, task_rel) else {
+            return;
+        };
+        let date = chrono::Local::now().format("%Y-%m-%d %H:%M").to_string();
+        let entry = self.action_log.lock().await.progress_entry(&date, title, &task_rel, outcome);
+
+        let client = self.tools.lock().await;
+        let progress_path = crate::domain::paths::progress_path(wd);
+        let mut progress = client
+            .read_file(&progress_path)
+            .await
+            .unwrap_or_else(|_| "# Progress History\n".to_string());
+        progress = format!("{}\n\n{}", progress.trim_end(), entry);
+        if let Err(e) = client.write_file(&progress_path, &progress).await {
+            tracing::warn!("Failed to update progress.md: {}", e);
+        }
+
+        let walkthrough_path = format!("{}/{}/walkthrough.md", wd, task_rel);
+        if let Ok(doc) = client.read_file(&walkthrough_path).await {
+            let completed = crate::application::action_log::complete_walkthrough(&doc);
+            if completed != doc
+                && let Err(e) = client.write_file(&walkthrough_path, &completed).await
+            {
+                tracing::warn!("Failed to update walkthrough.md: {}", e);
+            }
+        }
+    }
+
+    /// Commits the work of newly ticked checklist items, using their text as the message.
+    /// Only applies to tasks that run on their own branch, and never during a dry run.
+    async fn commit_checked_items(
+        &self,
+        chat: &impl ChatProvider,
+        working_dir: Option<&str>,
+        committed: &mut Vec<String>,
+    ) {
+        let Some(wd) = working_dir else {
+            return;
+        };
+        let has_branch = {
+            let guard = self.state.lock().await;
+            guard.rooms.get(&chat.room_id()).is_some_and(|r| r.task_base.is_some())
+        };
+        if !has_branch || self.tools.lock().await.dry_run_overlay(Path::new(wd)).is_some() {
+            return;
+        }
+
+        let new_items: Vec<String> = self
+            .checked_items(&chat.room_id(), Some(wd))
+            .await
+            .into_iter()
+            .filter(|item| !committed.contains(item))
+            .collect();
+        if new_items.is_empty() {
+            return;
+        }
+
+        // Several items ticked in one step share a commit: the first is the subject, the rest the body
+        let mut message = new_items[0].clone();
+        if new_items.len() > 1 {
+            message.push('\n');
+            for item in &new_items[1..] {
+                message.push_str(&format!("\n- {}", item));
+            }
+        }
+
+        let repo = crate::infrastructure::tools::git::GitRepo::new(wd);
+        match repo.commit_all(&message).await {
+            Ok(Some(hash)) => {
+                let mut feed = self.feed.lock().await;
+                let label = format!("Committed {}: {}", hash, new_items[0]);
+                feed.add_finished_activity(label, true);
+                let _ = feed.update_feed(chat).await;
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Failed to commit checklist item: {}", e),
+        }
+        committed.extend(new_items);
+    }
+
+    async fn resolver_for(&self, working_dir: Option<&str>) -> PathResolver {
+        let projects_root = self.feed.lock().await.projects_root();
+        PathResolver::new(working_dir.or(projects_root.as_deref()).unwrap_or("."))
+    }
+
+    /// Posts the cumulative diff of an active dry run: a summary in the feed, the full diff as a message.
+    async fn report_dry_run(&self, chat: &impl ChatProvider, resolver: &PathResolver) {
+        let Some(overlay) = self.tools.lock().await.dry_run_overlay(resolver.root()) else {
+            return;
+        };
+        let (files, added, removed) = overlay.stats();
+        {
+            let mut feed = self.feed.lock().await;
+            feed.add_checkpoint(
+                "Dry run".to_string(),
+                format!(
+                    "{} file(s) would change (+{}/-{}), {} command(s) skipped",
+                    files,
+                    added,
+                    removed,
+                    overlay.commands.len()
+                ),
+            );
+            let _ = feed.update_feed(chat).await;
+        }
+        let _ = chat
+            .send_message(&crate::strings::messages::dry_run_diff(
+                &overlay.diff(resolver.root()),
+                &overlay.commands,
+            ))
+            .await;
+    }
+
+    /// Parks the run while the room's pause switch is on, showing the paused state in the feed.
+    async fn wait_if_paused(&self, chat: &impl ChatProvider) {
+        let rx = self.pause_rx.lock().await.clone();
+        let Some(mut rx) = rx else {
+            return;
+        };
+        if !*rx.borrow() {
+            return;
+        }
+
+        {
+            let mut feed = self.feed.lock().await;
+            feed.paused = true;
+            let _ = feed.update_feed(chat).await;
+        }
+        let _ = chat
+            .send_notification(crate::strings::messages::TASK_PAUSED)
+            .await;
+
+        // Sender dropped means the room state was reset; carry on rather than hang
+        let _ = rx.wait_for(|paused| !*paused).await;
+
+        let mut feed = self.feed.lock().await;
+        feed.paused = false;
+        let _ = feed.update_feed(chat).await;
+    }
+
+    /// Takes all pending user input without waiting.
+    async fn drain_input(&self) -> Vec<String> {
+        let mut rx = self.input_rx.lock().await;
+        let mut pending = Vec::new();
+        if let Some(rx) = rx.as_mut() {
+            while let Ok(msg) = rx.try_recv() {
+                pending.push(msg);
+            }
+        }
+        pending
+    }
+
+    /// Shows a question in the feed and waits for the user's reply on the room's answer channel
+    /// (`.say` guidance stays on the input channel). A numeric reply picks the matching option.
+    /// Returns None if nobody answers within `ANSWER_TIMEOUT_MINUTES`.
+    async fn ask_user(
+        &self,
+        chat: &impl ChatProvider,
+        question: &str,
+        options: &[String],
+    ) -> Option<String> {
+        let (answer_tx, mut answer_rx) = tokio::sync::mpsc::channel(1);
+        {
+            let mut guard = self.state.lock().await;
+            let room = guard.get_room_state(&chat.room_id());
+            room.awaiting_answer = true;
+            room.answer_tx = Some(answer_tx);
+        }
+        {
+            let mut feed = self.feed.lock().await;
+            feed.add_activity(format!("Waiting for answer: {}", question.lines().next().unwrap_or(question)));
+            feed.set_question(question, options);
+            let _ = feed.update_feed(chat).await;
+        }
+        let _ = chat
+            .send_notification(&crate::strings::messages::agent_question(ANSWER_TIMEOUT_MINUTES))
+            .await;
+
+        let timeout = std::time::Duration::from_secs(ANSWER_TIMEOUT_MINUTES * 60);
+        let reply = tokio::time::timeout(timeout, answer_rx.recv())
+            .await
+            .ok()
+            .flatten();
+
+        {
+            let mut guard = self.state.lock().await;
+            let room = guard.get_room_state(&chat.room_id());
+            room.awaiting_answer = false;
+            room.answer_tx = None;
+        }
+
+        let answer = reply.map(|r| {
+            let r = r.trim().to_string();
+            match r.parse::<usize>() {
+                Ok(n) if n >= 1 && n <= options.len() => options[n - 1].clone(),
+                _ => r,
+            }
+        });
+
+        let mut feed = self.feed.lock().await;
+        feed.clear_question();
+        match &answer {
+            Some(a) => feed.replace_last_activity(format!("Answered: {}", a), true),
+            None => feed.replace_last_activity("No answer".to_string(), false),
+        }
+        let _ = feed.update_feed(chat).await;
+        answer
+    }
+
+    /// Pauses the run and asks the user whether a looping agent should carry on.
+    /// Returns false if the user stopped it.
+    async fn ask_to_continue(
+        &self,
+        chat: &impl ChatProvider,
+        action: &crate::domain::types::AgentAction,
+    ) -> bool {
+        let description = crate::application::stagnation::describe(action);
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        {
+            let mut guard = self.state.lock().await;
+            let room = guard.get_room_state(&chat.room_id());
+            room.pending_approval_tx = Some(Arc::new(Mutex::new(Some(tx))));
+        }
+        {
+            let mut feed = self.feed.lock().await;
+            feed.add_activity(format!("Stuck repeating: {}", description));
+            let _ = feed.update_feed(chat).await;
+        }
+        let _ = chat
+            .send_notification(&crate::strings::messages::agent_stuck(&description))
+            .await;
+
+        let carry_on = matches!(rx.await, Ok(true));
+        let mut feed = self.feed.lock().await;
+        if carry_on {
+            feed.replace_last_activity(format!("Continuing after loop: {}", description), true);
+        } else {
+            feed.replace_last_activity(format!("Stopped in a loop: {}", description), false);
+        }
+        let _ = feed.update_feed(chat).await;
+        carry_on
+    }
+
+    /// Reports a path the resolver refused (outside the project) to the model and the feed.
+    async fn reject_path(
+        &self,
+        chat: &impl ChatProvider,
+        history: &mut String,
+        label: String,
+        error: &PathError,
+    ) {
+        history.push_str(&format!("\nSystem: {}\n", error));
+        let mut feed = self.feed.lock().await;
+        feed.replace_last_activity(label, false);
+        feed.update_last_entry(error.to_string(), false);
+        let _ = feed.update_feed(chat).await;
+    }
+
+    /// Refreshes a written file in the symbol index, if one has been built, and marks the
+    /// repository map for a rebuild.
+    async fn reindex(&self, resolver: &PathResolver, path: &Path) {
+        *self.repo_map.lock().await = None;
+        let mut symbols = self.symbols.lock().await;
+        let Some(index) = symbols.as_mut() else {
+            return;
+        };
+        let content = self.tools.lock().await.read_file(&path.to_string_lossy()).await.ok();
+        index.update(&resolver.to_agent(path), content.as_deref());
+    }
+
+    /// The repository map, rebuilt (along with the symbol index if needed) when files changed.
+    async fn repo_map(&self, resolver: &PathResolver, query: &str) -> String {
+        let mut cached = self.repo_map.lock().await;
+        if let Some(map) = cached.as_ref() {
+            return map.clone();
+        }
+        let mut symbols = self.symbols.lock().await;
+        if symbols.is_none() {
+            match self.tools.lock().await.index_symbols(resolver.root()) {
+                Ok(index) => *symbols = Some(index),
+                Err(e) => return format!("(Repository map unavailable: {})", resolver.scrub(&e.to_string())),
+            }
+        }
+        let Some(index) = symbols.as_ref() else {
+            return "(Repository map unavailable)".to_string();
+        };
+        let files = crate::infrastructure::tools::repo_map::list_files(resolver.root());
+        let map = crate::infrastructure::tools::repo_map::render(
+            &files,
+            index,
+            query,
+            crate::infrastructure::tools::repo_map::MAP_BUDGET,
+        );
+        *cached = Some(map.clone());
+        map
+    }
+
+    /// Top matches for `query` from the project's retrieval index, as cited snippets.
+    /// The index picks up changed files (including the agent's writes) by modification time.
+    async fn retrieve(&self, resolver: &PathResolver, query: &str) -> String {
+        use crate::infrastructure::tools::retrieval;
+
+        let dry_run = self.tools.lock().await.in_dry_run(resolver.root());
+        let mut guard = self.retrieval.lock().await;
+        let index_dir = Path::new(retrieval::INDEX_DIR);
+        let index = guard.get_or_insert_with(|| RetrievalIndex::load(index_dir, resolver.root()));
+        if index.refresh() {
+            tracing::info!("Retrieval index updated: {} files", index.file_count());
+            // During a dry run the refreshed index stays in memory
+            if !dry_run && let Err(e) = index.save(index_dir) {
+                tracing::warn!("Failed to save retrieval index: {}", e);
+            }
+        }
+
+        let hits = index.search(query, retrieval::TOP_K);
+        drop(guard);
+        if hits.is_empty() {
+            return "(No matching code)".to_string();
+        }
+
+        let client = self.tools.lock().await;
+        let mut out = String::new();
+        for hit in &hits {
+            let path = resolver.root().join(&hit.file);
+            if let Ok(content) = client.read_file(&path.to_string_lossy()).await {
+                out.push_str(&retrieval::excerpt(&content, hit, query));
+            }
+        }
+        out
+    }
+}
+
+/// Removes the action blocks from a response, keeping the prose around them.
+fn strip_actions(
+    response: &str,
+    actions: &[(crate::domain::types::AgentAction, usize, usize)],
+) -> String {
+    let mut sorted_actions = actions.to_vec();
+    // Correct Tuple: (Action, start_index, end_index)
+    sorted_actions.sort_by_key(|(_, start, _)| *start);
+
+    let mut clean_msg = String::new();
+    let mut last_idx = 0;
+
+    for (_, start, end) in sorted_actions {
+        if start > last_idx {
+            clean_msg.push_str(&response[last_idx..start]);
+        }
+        last_idx = end;
+    }
+    if last_idx < response.len() {
+        clean_msg.push_str(&response[last_idx..]);
+    }
+    clean_msg
 }
 
 fn clean_agent_thought(text: &str) -> String {
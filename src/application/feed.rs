@@ -34,6 +34,9 @@ pub(crate) struct FeedEntry {
     pub(crate) label: String, // Was action_type
     pub(crate) content: String,
     output: Option<String>,
+    /// Sub-agent nesting level (0 = top-level run)
+    #[serde(default)]
+    depth: usize,
 }
 
 impl FeedEntry {
@@ -44,9 +47,15 @@ impl FeedEntry {
             label,
             content,
             output: None,
+            depth: 0,
         }
     }
 
+    /// Indentation marker for entries produced by sub-agents.
+    fn nesting_prefix(&self) -> String {
+        "↳ ".repeat(self.depth)
+    }
+
     pub(crate) fn format_active(&self, is_last: bool) -> String {
         let (icon, bold) = match self.kind {
             FeedEntryKind::Checkpoint => {
@@ -63,7 +72,7 @@ impl FeedEntry {
             FeedEntryKind::Activity => ("🔄", true), 
         };
 
-        let mut result = String::new();
+        let mut result = self.nesting_prefix();
 
         if self.kind == FeedEntryKind::Activity {
             // Special handling for Activity bullets
@@ -98,23 +107,23 @@ impl FeedEntry {
         }
         
         result.push('\n');
-        
-        if let Some(output) = &self.output {
-            if !output.is_empty() {
-                 let truncated = if output.len() > 300 {
-                    format!("{}...", &output[..300])
-                } else {
-                    output.clone()
-                };
-                result.push_str(&format!("> {}\n", truncated.replace('\n', " ")));
-            }
+
+        if let Some(output) = &self.output
+            && !output.is_empty()
+        {
+            let truncated = if output.len() > 300 {
+                format!("{}...", &output[..300])
+            } else {
+                output.clone()
+            };
+            result.push_str(&format!("> {}\n", truncated.replace('\n', " ")));
         }
 
         result
     }
 
     pub(crate) fn format_squashed(&self) -> String {
-        let mut result = String::new();
+        let mut result = self.nesting_prefix();
         match self.kind {
             FeedEntryKind::Activity => {
                 // Force checkmark if it was a bullet
@@ -142,6 +151,22 @@ impl FeedEntry {
     }
 }
 
+/// Serializable copy of the visible feed state, stored in task checkpoints.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedSnapshot {
+    entries: Vec<FeedEntry>,
+    mode: FeedMode,
+    current_task: Option<String>,
+    #[serde(default)]
+    feed_event_id: Option<String>,
+    #[serde(default)]
+    recent_activities: Vec<String>,
+    #[serde(default)]
+    agent_name: Option<String>,
+    #[serde(default)]
+    last_agent_thought: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct FeedManager {
     pub(crate) entries: Vec<FeedEntry>,
@@ -160,6 +185,14 @@ pub struct FeedManager {
     pub title: String,
     pub auto_start_timestamp: Option<i64>,
     pub agent_name: Option<String>,
+    /// Run is parked by `.pause`
+    pub paused: bool,
+    /// Question the agent is waiting on (rendered with numbered choices)
+    pub pending_question: Option<String>,
+    /// Nesting level applied to new activities (set while a sub-agent runs)
+    nesting: usize,
+    /// Checklist progress of the active task (`7/12 items, Phase 2`), shown in the header
+    pub progress: Option<String>,
 }
 
 impl FeedManager {
@@ -185,6 +218,10 @@ impl FeedManager {
             title: "Construct".to_string(),
             auto_start_timestamp: None,
             agent_name: None,
+            pending_question: None,
+            paused: false,
+            nesting: 0,
+            progress: None,
         }
     }
 
@@ -205,6 +242,9 @@ impl FeedManager {
         self.completion_message = None;
         self.last_agent_thought = None;
         self.auto_start_timestamp = None;
+        self.pending_question = None;
+        self.paused = false;
+        self.progress = None;
         // self.agent_name = None; // Don't clear agent name, as it might be set before initialize or we want it persistent for the session? 
         // Actually engine sets it after initialize usually? No wait, engine calls runs task.
         // Let's safe-guard by not clearing it here, relying on engine to update it if it changes.
@@ -226,6 +266,40 @@ impl FeedManager {
         self.add_activity(label);
     }
 
+    /// Captures the feed state for a task checkpoint.
+    pub fn snapshot(&self) -> FeedSnapshot {
+        FeedSnapshot {
+            entries: self.entries.clone(),
+            mode: self.mode,
+            current_task: self.current_task.clone(),
+            feed_event_id: self.feed_event_id.clone(),
+            recent_activities: self.recent_activities.clone(),
+            agent_name: self.agent_name.clone(),
+            last_agent_thought: self.last_agent_thought.clone(),
+        }
+    }
+
+    /// Rebuilds the feed from a checkpoint snapshot (e.g. after a restart).
+    /// Keeps the current event ID if the snapshot has none, so the sticky message is reused.
+    pub fn restore(&mut self, snapshot: FeedSnapshot) {
+        self.entries = snapshot.entries;
+        self.mode = snapshot.mode;
+        self.current_task = snapshot.current_task;
+        if snapshot.feed_event_id.is_some() {
+            self.feed_event_id = snapshot.feed_event_id;
+        }
+        self.recent_activities = snapshot.recent_activities;
+        self.agent_name = snapshot.agent_name;
+        self.last_agent_thought = snapshot.last_agent_thought;
+        self.completion_message = None;
+        self.auto_start_timestamp = None;
+    }
+
+    /// Sets the nesting level for subsequent activities (sub-agent runs).
+    pub fn set_nesting(&mut self, depth: usize) {
+        self.nesting = depth;
+    }
+
     pub fn set_completion(&mut self, message: String) {
         self.completion_message = Some(message);
     }
@@ -258,6 +332,7 @@ impl FeedManager {
         if let Some(last) = self.recent_activities.last() {
             // Strip known prefixes
             let clean_last = last
+                .trim_start_matches("↳ ")
                 .trim_start_matches("✅ ")
                 .trim_start_matches("❌ ")
                 .trim_start_matches("• ")
@@ -273,22 +348,21 @@ impl FeedManager {
             }
         }
 
-        self.recent_activities.push(activity_log.clone());
+        self.recent_activities
+            .push(format!("{}{}", "↳ ".repeat(self.nesting), activity_log));
         if self.recent_activities.len() > 15 {
             self.recent_activities.remove(0);
         }
-        self.entries.push(FeedEntry::new(
-            FeedEntryKind::Activity,
-            "System".to_string(),
-            activity_log,
-        ));
+        let mut entry = FeedEntry::new(FeedEntryKind::Activity, "System".to_string(), activity_log);
+        entry.depth = self.nesting;
+        self.entries.push(entry);
     }
 
     pub fn replace_last_activity(&mut self, new_content: String, success: bool) {
         // Update recent log
         if let Some(last) = self.recent_activities.last_mut() {
             let icon = if success { "✅" } else { "❌" };
-            *last = format!("{} {}", icon, new_content);
+            *last = format!("{}{} {}", "↳ ".repeat(self.nesting), icon, new_content);
         }
         // Update entry
         if let Some(entry) = self.entries.last_mut()
@@ -311,6 +385,21 @@ impl FeedManager {
     }
     // Prefer strict methods now.
 
+    /// Adds an activity that is already finished, as `add_activity` followed by
+    /// `replace_last_activity` would leave it.
+    pub fn add_finished_activity(&mut self, content: String, success: bool) {
+        let icon = if success { "✅" } else { "❌" };
+        self.recent_activities
+            .push(format!("{}{} {}", "↳ ".repeat(self.nesting), icon, content));
+        if self.recent_activities.len() > 15 {
+            self.recent_activities.remove(0);
+        }
+        let label = if success { "Completed" } else { "Failed" };
+        let mut entry = FeedEntry::new(FeedEntryKind::Checkpoint, label.to_string(), content);
+        entry.depth = self.nesting;
+        self.entries.push(entry);
+    }
+
     pub fn update_last_entry(&mut self, output: String, _success: bool) {
         if let Some(entry) = self.entries.last_mut() {
             // entry.status update removed.
@@ -350,6 +439,19 @@ impl FeedManager {
         }
     }
 
+    /// Shows a question with numbered choices under the activity list.
+    pub fn set_question(&mut self, question: &str, options: &[String]) {
+        let mut text = question.to_string();
+        for (i, option) in options.iter().enumerate() {
+            text.push_str(&format!("\n{}. {}", i + 1, option));
+        }
+        self.pending_question = Some(text);
+    }
+
+    pub fn clear_question(&mut self) {
+        self.pending_question = None;
+    }
+
     pub fn add_completion_message(&mut self, msg: String) {
         self.completion_message = Some(msg);
     }
@@ -372,12 +474,24 @@ impl FeedManager {
             AgentAction::WriteFile(path, _) => {
                 self.add_activity(format!("Writing: {}", path));
             }
+            AgentAction::EditFile(path, _) => {
+                self.add_activity(format!("Editing: {}", path));
+            }
             AgentAction::Find(path, pattern) => {
                 let sanitized =
                     crate::application::utils::sanitize_path(path, self.projects_root.as_deref());
                 self.add_activity(format!("Finding: {} {}", sanitized, pattern));
             }
-            AgentAction::ReadFile(path) => {
+            AgentAction::Search(query) => {
+                self.add_activity(format!("Searching: {} in {}", query.pattern, query.path));
+            }
+            AgentAction::Outline(path) => {
+                self.add_activity(format!("Outlining: {}", path));
+            }
+            AgentAction::FindSymbol(name) => {
+                self.add_activity(format!("Looking up: {}", name));
+            }
+            AgentAction::ReadFile(path, _) => {
                 self.add_activity(format!("Reading: {}", path));
             }
             AgentAction::ListDir(path) => {
@@ -386,6 +500,12 @@ impl FeedManager {
             AgentAction::SwitchMode(phase) => {
                 self.add_activity(format!("Switching to mode: {}", phase));
             }
+            AgentAction::Delegate(goal, _, _) => {
+                self.add_activity(format!("Delegating: {}", goal));
+            }
+            AgentAction::AskUser(question, _) => {
+                self.add_activity(format!("Asking: {}", question));
+            }
         }
     }
 
@@ -5,12 +5,17 @@ pub struct FeedFormatter;
 
 impl FeedFormatter {
     pub fn format_active(manager: &FeedManager) -> String {
-        let header = if let Some(agent) = &manager.agent_name {
+        let header = if manager.paused {
+            String::from("**⏸️ Paused** (`.resume` to continue)\n")
+        } else if let Some(agent) = &manager.agent_name {
             format!("**🚀 [{}] Thinking & doing...**\n", agent)
         } else {
              String::from("**🚀 Thinking & doing...**\n")
         };
         let mut content = header;
+        if let Some(progress) = &manager.progress {
+            content.push_str(&format!("📋 {}\n", progress));
+        }
         if let Some(task) = &manager.current_task {
             // Only show first line, no label
             let summary = task.lines().next().unwrap_or(task);
@@ -67,6 +72,13 @@ impl FeedFormatter {
              }
         }
 
+        if let Some(question) = &manager.pending_question {
+            content.push_str(&format!(
+                "\n**❓ Question**: {}\n_Reply with a number or your own answer._\n",
+                question
+            ));
+        }
+
         if let Some(ts) = manager.auto_start_timestamp {
              let now = Local::now().timestamp();
              let remaining = ts - now;
@@ -0,0 +1,128 @@
+//! # Action Hooks
+//!
+//! Runs the configured pre/post hooks (`hooks` in config.yaml plus `tasks/specs/hooks.yaml`)
+//! around agent writes, commands and step batches. Hook commands go through
+//! `ToolExecutor::execute_command`, so they share the agent's sandbox, timeouts and dry run.
+//! The agent cannot write `tasks/specs/hooks.yaml` (the executor protects it), so every hook
+//! it triggers was written by a person.
+
+use crate::domain::config::{Hook, HookEvent};
+use crate::infrastructure::tools::executor::SharedToolExecutor;
+use std::path::Path;
+
+/// Maximum characters of hook output reported back to the model.
+const OUTPUT_TAIL_CHARS: usize = 2000;
+
+#[derive(Debug, Clone)]
+pub struct HookResult {
+    /// The command that ran, or `reject` for a rejecting hook
+    pub command: String,
+    pub success: bool,
+    pub output: String,
+}
+
+impl HookResult {
+    /// Line for the engine history describing a failed hook.
+    pub fn to_history(&self) -> String {
+        if self.command == "reject" {
+            return format!("rejected by hook: {}", self.output);
+        }
+        format!(
+            "hook `{}` failed:\n{}",
+            self.command,
+            crate::application::verification::tail(&self.output, OUTPUT_TAIL_CHARS)
+        )
+    }
+}
+
+/// Runs the hooks that apply to `event` on `subject` in order. With `stop_on_failure` (pre
+/// hooks) the first failing or rejecting hook ends the run.
+pub async fn run(
+    tools: &SharedToolExecutor,
+    hooks: &[Hook],
+    event: HookEvent,
+    subject: Option<&str>,
+    cwd: &Path,
+    stop_on_failure: bool,
+) -> Vec<HookResult> {
+    let mut results = Vec::new();
+    for hook in hooks.iter().filter(|h| h.applies(event, subject)) {
+        let result = if let Some(reason) = &hook.reject {
+            HookResult {
+                command: "reject".to_string(),
+                success: false,
+                output: reason.clone(),
+            }
+        } else if let Some(command) = &hook.run {
+            let command = expand(command, subject.filter(|_| event == HookEvent::Write));
+            let output = tools.lock().await.execute_command(&command, cwd).await;
+            let (output, success) = match output {
+                Ok(o) => {
+                    let ok = !o.contains("[Exit Code:");
+                    (o, ok)
+                }
+                Err(e) => (format!("Error: {}", e), false),
+            };
+            HookResult {
+                command,
+                success,
+                output,
+            }
+        } else {
+            continue;
+        };
+        let failed = !result.success;
+        results.push(result);
+        if failed && stop_on_failure {
+            break;
+        }
+    }
+    results
+}
+
+/// Substitutes `{file}` in a hook command, single-quoted so the agent-chosen path
+/// (`src/$(curl x|sh).rs`) stays one literal argument.
+fn expand(command: &str, file: Option<&str>) -> String {
+    match file {
+        Some(file) => command.replace("{file}", &format!("'{}'", file.replace('\'', "'\\''"))),
+        None => command.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::config::HooksConfig;
+
+    const HOOKS: &str = "pre:\n  - on: write\n    matches: Cargo.lock\n    reject: managed by cargo\npost:\n  - on: write\n    matches: \"*.rs\"\n    run: rustfmt {file}\n  - on: batch\n    run: cargo check\n  - on: command\n    matches: \"cargo *\"\n    run: echo done\n";
+
+    #[test]
+    fn test_hook_matching() {
+        let hooks: HooksConfig = serde_yaml::from_str(HOOKS).unwrap();
+        assert!(hooks.pre[0].applies(HookEvent::Write, Some("Cargo.lock")));
+        assert!(!hooks.pre[0].applies(HookEvent::Write, Some("Cargo.toml")));
+        assert!(!hooks.pre[0].applies(HookEvent::Command, Some("Cargo.lock")));
+        assert!(hooks.post[0].applies(HookEvent::Write, Some("src/deep/main.rs")));
+        assert!(!hooks.post[0].applies(HookEvent::Write, Some("README.md")));
+        assert!(hooks.post[1].applies(HookEvent::Batch, None));
+        assert!(hooks.post[2].applies(HookEvent::Command, Some("cargo test --all")));
+        assert!(!hooks.post[2].applies(HookEvent::Command, Some("ls")));
+        assert_eq!(expand("rustfmt {file}", Some("src/a.rs")), "rustfmt 'src/a.rs'");
+        assert_eq!(expand("cat {file}", Some("it's $(x).rs")), "cat 'it'\\''s $(x).rs'");
+    }
+
+    #[test]
+    fn test_project_hooks_extend_global() {
+        let dir = tempfile::tempdir().unwrap();
+        let specs = dir.path().join(crate::domain::paths::SPECS_DIR);
+        std::fs::create_dir_all(&specs).unwrap();
+        std::fs::write(specs.join(crate::domain::paths::HOOKS_FILE), HOOKS).unwrap();
+
+        let global: HooksConfig = serde_yaml::from_str("post:\n  - on: batch\n    run: make lint\n").unwrap();
+        let merged = global.for_project(&dir.path().to_string_lossy());
+        assert_eq!(merged.pre.len(), 1);
+        assert_eq!(merged.post.len(), 4);
+        assert_eq!(merged.post[0].run.as_deref(), Some("make lint"));
+        assert!(global.for_project("/nonexistent").pre.is_empty());
+    }
+}
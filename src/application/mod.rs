@@ -3,12 +3,18 @@
 //! Contains the core business logic and orchestration of the bot.
 //! This includes the execution engine, command routing, state management, and feed system.
 
+pub mod action_log;
+pub mod checkpoint;
+pub mod delegation;
 pub mod engine;
 pub mod feed;
 pub mod feed_formatter;
+pub mod hooks;
 pub mod logging;
 pub mod parsing;
 pub mod project;
 pub mod router;
+pub mod stagnation;
 pub mod state;
 pub mod utils;
+pub mod verification;
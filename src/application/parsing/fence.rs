@@ -0,0 +1,257 @@
+//! # Fenced Block Tokenizer
+//!
+//! Splits an LLM response into prose and fenced code blocks the way a Markdown renderer would,
+//! with one extension for nesting: inside a block, a fence at least as long as the opening one
+//! that carries a language tag opens an inner block, and the next bare fence closes that inner
+//! block instead of the outer one. A ```` ```write ```` of a Markdown file therefore keeps its
+//! ```` ```rust ```` examples even when the model forgets to use four backticks.
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment<'a> {
+    /// Text outside any fenced block, starting at byte `start` of the response
+    Prose { text: &'a str, start: usize },
+    Block(Block<'a>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Block<'a> {
+    /// Info string after the opening fence (`write src/main.rs`), trimmed
+    pub info: &'a str,
+    /// Everything between the fence lines, including the final newline
+    pub body: &'a str,
+    /// Byte range of the whole block, fences included
+    pub start: usize,
+    pub end: usize,
+    /// 1-based line of the opening fence
+    pub line: usize,
+    /// False when the response ended before the closing fence
+    pub closed: bool,
+}
+
+/// An inline code span (`` `read src/main.rs` ``) inside prose.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Span<'a> {
+    pub ticks: usize,
+    pub content: &'a str,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// A fence line: its character, run length and the rest of the line (trimmed).
+fn fence(line: &str) -> Option<(char, usize, &str)> {
+    let trimmed = line.trim();
+    let c = trimmed.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let run = trimmed.chars().take_while(|x| *x == c).count();
+    if run < 3 {
+        return None;
+    }
+    Some((c, run, trimmed[run..].trim()))
+}
+
+/// A fence that can open a block: backtick info strings may not contain backticks
+/// (```` ```read x``` ```` on one line is an inline code span).
+fn opening_fence(line: &str) -> Option<(char, usize, &str)> {
+    fence(line).filter(|(c, _, info)| *c == '~' || !info.contains('`'))
+}
+
+/// Byte offset of a closing fence glued to the end of a content line (`cargo build```).
+/// Only a single run of exactly `len` fence characters counts: two runs are an inline span.
+fn trailing_fence(line: &str, c: char, len: usize) -> Option<usize> {
+    let content = line.trim_end();
+    let run = content.chars().rev().take_while(|x| *x == c).count();
+    let at = content.len() - run;
+    let before = &content[..at];
+    let glued = run == len && !before.trim().is_empty();
+    (glued && !before.contains(&c.to_string().repeat(len))).then_some(at)
+}
+
+struct Open<'a> {
+    c: char,
+    len: usize,
+    info: &'a str,
+    start: usize,
+    body_start: usize,
+    line: usize,
+    depth: usize,
+}
+
+/// Splits `text` into prose and fenced blocks, in document order.
+pub fn tokenize(text: &str) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    let mut prose_start = 0;
+    let mut open: Option<Open> = None;
+    let mut offset = 0;
+
+    for (number, line) in text.split_inclusive('\n').enumerate() {
+        let line_start = offset;
+        offset += line.len();
+        let content = line.trim_end_matches(['\n', '\r']);
+
+        let Some(block) = open.as_mut() else {
+            if let Some((c, len, info)) = opening_fence(content) {
+                if line_start > prose_start {
+                    segments.push(Segment::Prose {
+                        text: &text[prose_start..line_start],
+                        start: prose_start,
+                    });
+                }
+                open = Some(Open {
+                    c,
+                    len,
+                    info,
+                    start: line_start + content.len() - content.trim_start().len(),
+                    body_start: offset,
+                    line: number + 1,
+                    depth: 0,
+                });
+            }
+            continue;
+        };
+
+        let close_at = match fence(content) {
+            Some((c, len, rest)) if c == block.c && len >= block.len => {
+                if !rest.is_empty() {
+                    // A tagged fence inside the block opens a nested one
+                    if c == '~' || !rest.contains('`') {
+                        block.depth += 1;
+                    }
+                    None
+                } else if block.depth > 0 {
+                    block.depth -= 1;
+                    None
+                } else {
+                    let at = content.len() - content.trim_start().len();
+                    Some((line_start, line_start + at + content.trim().len()))
+                }
+            }
+            Some(_) => None,
+            None if block.depth == 0 => trailing_fence(content, block.c, block.len)
+                .map(|at| (line_start + at, line_start + content.trim_end().len())),
+            None => None,
+        };
+
+        if let Some((body_end, end)) = close_at {
+            segments.push(Segment::Block(Block {
+                info: block.info,
+                body: &text[block.body_start.min(body_end)..body_end],
+                start: block.start,
+                end,
+                line: block.line,
+                closed: true,
+            }));
+            open = None;
+            prose_start = end;
+        }
+    }
+
+    match open {
+        Some(block) => segments.push(Segment::Block(Block {
+            info: block.info,
+            body: &text[block.body_start.min(text.len())..],
+            start: block.start,
+            end: text.len(),
+            line: block.line,
+            closed: false,
+        })),
+        None if prose_start < text.len() => segments.push(Segment::Prose {
+            text: &text[prose_start..],
+            start: prose_start,
+        }),
+        None => {}
+    }
+    segments
+}
+
+/// Inline code spans of `text` (prose starting at byte `base` of the response).
+/// A span opens and closes with backtick runs of the same length on the same line.
+pub fn code_spans(text: &str, base: usize) -> Vec<Span<'_>> {
+    let bytes = text.as_bytes();
+    let run_at = |i: usize| bytes[i..].iter().take_while(|b| **b == b'`').count();
+    let mut spans = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'`' {
+            i += 1;
+            continue;
+        }
+        let ticks = run_at(i);
+        let mut j = i + ticks;
+        let mut close = None;
+        while j < bytes.len() && bytes[j] != b'\n' {
+            if bytes[j] == b'`' {
+                let run = run_at(j);
+                if run == ticks {
+                    close = Some(j);
+                    break;
+                }
+                j += run;
+            } else {
+                j += 1;
+            }
+        }
+        match close {
+            Some(j) => {
+                spans.push(Span {
+                    ticks,
+                    content: text[i + ticks..j].trim(),
+                    start: base + i,
+                    end: base + j + ticks,
+                });
+                i = j + ticks;
+            }
+            None => i += ticks,
+        }
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blocks(text: &str) -> Vec<Block<'_>> {
+        tokenize(text)
+            .into_iter()
+            .filter_map(|s| match s {
+                Segment::Block(b) => Some(b),
+                Segment::Prose { .. } => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_nested_tagged_fences() {
+        let text = "Intro\n```write README.md\n# Demo\n```bash\ncargo run\n```\nDone.\n```\nAfter\n";
+        let found = blocks(text);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].info, "write README.md");
+        assert_eq!(found[0].body, "# Demo\n```bash\ncargo run\n```\nDone.\n");
+        assert_eq!(&text[found[0].start..found[0].end], &text[6..text.len() - 7]);
+        assert!(found[0].closed);
+
+        // Shorter fences are plain content of a longer block
+        let quad = blocks("````write a.md\n```\nx\n````\n");
+        assert_eq!(quad[0].body, "```\nx\n");
+    }
+
+    #[test]
+    fn test_unclosed_and_glued_fences() {
+        let open = blocks("```edit src/lib.rs\n<<<<<<< SEARCH\n");
+        assert!(!open[0].closed);
+        assert_eq!(open[0].line, 1);
+
+        let glued = blocks("```bash\ncargo build```\n");
+        assert_eq!(glued[0].body, "cargo build");
+        assert!(glued[0].closed);
+        // Two runs on the line are an inline span, not a closing fence
+        assert!(!blocks("```write a.md\nRun ```cargo test```\n")[0].closed);
+    }
+
+    #[test]
+    fn test_code_spans() {
+        let spans = code_spans("Use `read a.rs` or ```symbol Foo``` and `` a`b ``, not `open", 10);
+        let contents: Vec<(&str, usize)> = spans.iter().map(|s| (s.content, s.ticks)).collect();
+        assert_eq!(contents, vec![("read a.rs", 1), ("symbol Foo", 3), ("a`b", 2)]);
+        assert_eq!(spans[0].start, 14);
+    }
+}
@@ -0,0 +1,570 @@
+//! # Parsing Utils
+//!
+//! Extracts the agent's actions (```` ```write ````, ```` ```run_command ````, `NO_MORE_STEPS`, ...)
+//! from the raw LLM response. The response is split into prose and fenced blocks by
+//! [`fence::tokenize`]; a block becomes an action only when its language tag names a tool, so
+//! code inside a `write` never runs. Prose is searched for the inline forms and the done signal.
+//! Tool blocks that cannot be read are reported as [`ParseIssue`]s instead of being dropped.
+
+mod fence;
+
+use crate::domain::types::AgentAction;
+use fence::Segment;
+use regex::Regex;
+
+/// Tools that also work as inline code (`` `read src/main.rs` ``) or as one line in a bare fence
+const INLINE_TOOLS: &[&str] = &["read", "list", "find", "outline", "symbol", "switch_mode", "search"];
+/// Language tags of command blocks
+const SHELL_TAGS: &[&str] = &["bash", "sh", "run_command"];
+/// Ends the task wherever it appears in prose; a bare `DONE` only counts on a line of its own
+const DONE_SIGNAL: &str = "NO_MORE_STEPS";
+
+/// A tool block that was recognised but could not be turned into an action.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseIssue {
+    /// 1-based line of the block's opening fence
+    pub line: usize,
+    pub tag: String,
+    pub reason: String,
+}
+
+impl std::fmt::Display for ParseIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "`{}` block on line {} {}", self.tag, self.line, self.reason)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ParsedResponse {
+    /// Actions in document order, with their byte range in the response
+    pub actions: Vec<(AgentAction, usize, usize)>,
+    pub issues: Vec<ParseIssue>,
+}
+
+pub fn parse_response(response: &str) -> ParsedResponse {
+    let mut parsed = ParsedResponse::default();
+    let mut done: Option<(usize, usize)> = None;
+    // Conversational fallback: **Action**: Read `path`
+    let read_fallback = Regex::new(r"(?i)\*\*Action\*\*:\s*Read\s+`([^`]+)`").unwrap();
+
+    for segment in fence::tokenize(response) {
+        match segment {
+            Segment::Block(block) => {
+                let (tag, args) = split_tag(block.info);
+                let tag = tag.to_ascii_lowercase();
+                let result = if tag.is_empty() {
+                    // ```\nread src/lib.rs\n```
+                    bare_block(block.body).map(Ok)
+                } else {
+                    tool_block(&tag, args, block.body)
+                };
+                let issue = |reason: &str| ParseIssue {
+                    line: block.line,
+                    tag: tag.clone(),
+                    reason: reason.to_string(),
+                };
+                match result {
+                    None => {}
+                    // A half-written block (a truncated `write` especially) must not run
+                    Some(_) if !block.closed && !tag.is_empty() => {
+                        parsed.issues.push(issue("is not closed (the closing fence is missing)"));
+                    }
+                    Some(_) if !block.closed => {}
+                    Some(Ok(action)) => parsed.actions.push((action, block.start, block.end)),
+                    Some(Err(reason)) => parsed.issues.push(issue(&reason)),
+                }
+            }
+            Segment::Prose { text, start } => {
+                for span in fence::code_spans(text, start) {
+                    if let Some(action) = inline_action(span.content, span.ticks) {
+                        parsed.actions.push((action, span.start, span.end));
+                    }
+                }
+                for caps in read_fallback.captures_iter(text) {
+                    if let (Some(match_node), Some(path)) = (caps.get(0), caps.get(1)) {
+                        tracing::warn!("Parsed fallback action format: Read {}", path.as_str());
+                        parsed.actions.push((
+                            read_action(path.as_str()),
+                            start + match_node.start(),
+                            start + match_node.end(),
+                        ));
+                    }
+                }
+                if done.is_none() {
+                    done = done_signal(text).map(|(s, e)| (start + s, start + e));
+                }
+            }
+        }
+    }
+
+    if let Some((start, end)) = done {
+        parsed.actions.push((AgentAction::Done, start, end));
+    }
+    // Sort matches by start index to preserve document order
+    parsed.actions.sort_by_key(|a| a.1);
+
+    if parsed.actions.is_empty() && parsed.issues.is_empty() && response.contains("Action:") {
+        tracing::warn!("Potential unparsed action in response: {}", response);
+    }
+    parsed
+}
+
+/// Splits an info string or span into its tag and the trimmed rest (`write src/a.rs`).
+fn split_tag(info: &str) -> (&str, &str) {
+    let info = info.trim();
+    match info.split_once(char::is_whitespace) {
+        Some((tag, rest)) => (tag, rest.trim()),
+        None => (info, ""),
+    }
+}
+
+/// Action of a fenced block with a language tag. `None` for ordinary code (```` ```rust ````).
+fn tool_block(tag: &str, args: &str, body: &str) -> Option<Result<AgentAction, String>> {
+    let action = match tag {
+        "write" => file_path(args).map(|path| AgentAction::WriteFile(path, body.to_string())),
+        "edit" => file_path(args).and_then(|path| {
+            if body.trim().is_empty() {
+                Err("is empty (put the SEARCH/REPLACE blocks inside it)".to_string())
+            } else {
+                Ok(AgentAction::EditFile(path, body.to_string()))
+            }
+        }),
+        t if SHELL_TAGS.contains(&t) => {
+            let command = format!("{}\n{}", args, body).trim().to_string();
+            if command.is_empty() {
+                Err("has no command".to_string())
+            } else {
+                Ok(AgentAction::ShellCommand(command))
+            }
+        }
+        "delegate" => {
+            let (goal, files, steps) = crate::application::delegation::parse_request(body);
+            if goal.is_empty() {
+                Err("has no `goal:`".to_string())
+            } else {
+                Ok(AgentAction::Delegate(goal, files, steps))
+            }
+        }
+        "ask" => ask_action(body),
+        // ```read src/main.rs``` or the arguments on the next line
+        t if INLINE_TOOLS.contains(&t) => {
+            let args = if args.is_empty() && t != "search" { body.trim() } else { args };
+            inline_tool(t, args, body)
+        }
+        _ => return None,
+    };
+    Some(action)
+}
+
+/// A bare fence around a single tool line. Anything else is ordinary code.
+fn bare_block(body: &str) -> Option<AgentAction> {
+    let line = body.trim();
+    if line.contains('\n') {
+        return None;
+    }
+    inline_action(line, 1)
+}
+
+/// Action of inline code in prose. Shell and search need the triple-backtick form
+/// (```` ```bash ls``` ````) so that prose like `` `sh` `` never runs anything.
+fn inline_action(content: &str, ticks: usize) -> Option<AgentAction> {
+    let (tag, args) = split_tag(content);
+    let tag = tag.to_ascii_lowercase();
+    if args.is_empty() {
+        return None;
+    }
+    if SHELL_TAGS.contains(&tag.as_str()) {
+        return (ticks >= 3).then(|| AgentAction::ShellCommand(args.to_string()));
+    }
+    if tag == "search" && ticks < 3 {
+        return None;
+    }
+    INLINE_TOOLS
+        .contains(&tag.as_str())
+        .then(|| inline_tool(&tag, args, "").ok())
+        .flatten()
+}
+
+/// Actions that take their arguments on one line.
+fn inline_tool(tag: &str, args: &str, body: &str) -> Result<AgentAction, String> {
+    let words: Vec<&str> = args.split_whitespace().collect();
+    match tag {
+        "search" => {
+            let query = crate::infrastructure::tools::search::parse_query(args, body);
+            if query.pattern.is_empty() {
+                Err("has no `pattern:`".to_string())
+            } else {
+                Ok(AgentAction::Search(query))
+            }
+        }
+        _ if args.is_empty() => Err("has no arguments".to_string()),
+        "read" => Ok(read_action(args)),
+        "list" => Ok(AgentAction::ListDir(args.to_string())),
+        "outline" => Ok(AgentAction::Outline(args.to_string())),
+        "find" => match words.as_slice() {
+            [path, pattern] => Ok(AgentAction::Find(path.to_string(), pattern.to_string())),
+            _ => Err("needs a path and a pattern (```find src *.rs```)".to_string()),
+        },
+        "symbol" => match words.as_slice() {
+            [name] => Ok(AgentAction::FindSymbol(name.to_string())),
+            _ => Err("needs one name (```symbol Type::name```)".to_string()),
+        },
+        "switch_mode" => match words.as_slice() {
+            [phase] if phase.chars().all(|c| c.is_ascii_alphabetic() || c == '_') => {
+                Ok(AgentAction::SwitchMode(phase.to_string()))
+            }
+            _ => Err("needs a phase name".to_string()),
+        },
+        _ => Err("is not a tool".to_string()),
+    }
+}
+
+fn file_path(args: &str) -> Result<String, String> {
+    if args.is_empty() {
+        Err("has no file path (```write path/to/file```)".to_string())
+    } else {
+        Ok(args.to_string())
+    }
+}
+
+/// ```` ```ask ```` body: question lines, then optional `1.` / `-` options.
+fn ask_action(body: &str) -> Result<AgentAction, String> {
+    let option_regex = Regex::new(r"^(?:[-*]|\d+[.)])\s+(.+)$").unwrap();
+    let mut question = Vec::new();
+    let mut options = Vec::new();
+    for line in body.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        match option_regex.captures(line) {
+            Some(opt) => options.push(opt[1].trim().to_string()),
+            None => question.push(line),
+        }
+    }
+    if question.is_empty() {
+        return Err("has no question".to_string());
+    }
+    Ok(AgentAction::AskUser(question.join("\n"), options))
+}
+
+/// Byte range of the done signal in a prose segment: `NO_MORE_STEPS` as a whole word anywhere,
+/// or `DONE` alone on a line (bold, code or with a full stop is fine).
+fn done_signal(text: &str) -> Option<(usize, usize)> {
+    let word = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let signal = text.match_indices(DONE_SIGNAL).find(|(idx, _)| {
+        let before = text[..*idx].chars().next_back();
+        let after = text[idx + DONE_SIGNAL.len()..].chars().next();
+        !before.is_some_and(word) && !after.is_some_and(word)
+    });
+    if let Some((idx, _)) = signal {
+        return Some((idx, idx + DONE_SIGNAL.len()));
+    }
+
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let bare = line.trim().trim_matches(|c: char| matches!(c, '*' | '_' | '`' | '#' | '.' | '!' | ' '));
+        if bare == "DONE" {
+            let idx = offset + line.find("DONE")?;
+            return Some((idx, idx + "DONE".len()));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// `read` target with an optional line range (`src/main.rs 120-180`, `src/main.rs around 150`).
+fn read_action(target: &str) -> AgentAction {
+    let (path, range) = crate::infrastructure::tools::reader::parse_target(target);
+    AgentAction::ReadFile(path, range)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::types::{AgentAction, LineRange};
+    use proptest::prelude::*;
+
+    fn parse_actions(response: &str) -> Vec<(AgentAction, usize, usize)> {
+        parse_response(response).actions
+    }
+
+    #[test]
+    fn test_parse_standard_read() {
+        let input = "Here is a file:\n```read src/main.rs```\n";
+        let actions = parse_actions(input);
+        assert_eq!(actions.len(), 1);
+        if let AgentAction::ReadFile(path, None) = &actions[0].0 {
+            assert_eq!(path, "src/main.rs");
+        } else {
+            panic!("Expected ReadFile");
+        }
+    }
+
+    #[test]
+    fn test_parse_loose_read() {
+        // This was the failure case: ```\nread path\n```
+        let input = "Check this:\n```\nread src/lib.rs\n```\n";
+        let actions = parse_actions(input);
+        assert_eq!(actions.len(), 1);
+        if let AgentAction::ReadFile(path, None) = &actions[0].0 {
+            assert_eq!(path, "src/lib.rs");
+        } else {
+            panic!("Expected ReadFile");
+        }
+    }
+    
+    #[test]
+    fn test_parse_inline_read_with_newline() {
+        // Log case: ```read path/to/file\n```
+        let input = "I will read:\n```read tasks/001-init/request.md\n```";
+        let actions = parse_actions(input);
+        assert_eq!(actions.len(), 1);
+        if let AgentAction::ReadFile(path, None) = &actions[0].0 {
+            assert_eq!(path, "tasks/001-init/request.md");
+        } else {
+            panic!("Expected ReadFile, got {:?}", actions[0].0);
+        }
+    }
+
+    #[test]
+    fn test_parse_read_range() {
+        let actions = parse_actions("```read src/main.rs 120-180```\n```read src/lib.rs around 40```");
+        assert_eq!(
+            actions[0].0,
+            AgentAction::ReadFile("src/main.rs".into(), Some(LineRange::Span(120, Some(180))))
+        );
+        assert_eq!(
+            actions[1].0,
+            AgentAction::ReadFile("src/lib.rs".into(), Some(LineRange::Around(40)))
+        );
+    }
+
+    #[test]
+    fn test_parse_outline_and_symbol() {
+        let actions = parse_actions("```outline src/application```\n```symbol FeedManager::update_feed```");
+        assert_eq!(actions[0].0, AgentAction::Outline("src/application".into()));
+        assert_eq!(actions[1].0, AgentAction::FindSymbol("FeedManager::update_feed".into()));
+    }
+
+    #[test]
+    fn test_parse_loose_find() {
+        let input = "Looking for files:\n```\nfind src *.rs\n```";
+        let actions = parse_actions(input);
+        assert_eq!(actions.len(), 1);
+        if let AgentAction::Find(path, pattern) = &actions[0].0 {
+            assert_eq!(path, "src");
+            assert_eq!(pattern, "*.rs");
+        } else {
+            panic!("Expected Find");
+        }
+    }
+
+    #[test]
+    fn test_parse_search_block() {
+        let input = "```search\npattern: fn main\npath: src\nmode: literal\n```\nthen ```search TODO```";
+        let actions = parse_actions(input);
+        assert_eq!(actions.len(), 2);
+        match (&actions[0].0, &actions[1].0) {
+            (AgentAction::Search(block), AgentAction::Search(inline)) => {
+                assert_eq!((block.pattern.as_str(), block.path.as_str()), ("fn main", "src"));
+                assert!(block.literal);
+                assert_eq!((inline.pattern.as_str(), inline.path.as_str()), ("TODO", "."));
+            }
+            other => panic!("Expected two searches, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_edit_block() {
+        let input = "```edit src/lib.rs\n<<<<<<< SEARCH\nfoo\n=======\nbar\n>>>>>>> REPLACE\n```";
+        let actions = parse_actions(input);
+        assert_eq!(actions.len(), 1);
+        if let AgentAction::EditFile(path, patch) = &actions[0].0 {
+            assert_eq!(path, "src/lib.rs");
+            assert!(patch.contains("<<<<<<< SEARCH"));
+        } else {
+            panic!("Expected EditFile");
+        }
+    }
+
+    #[test]
+    fn test_parse_delegate_block() {
+        let input = "Splitting this up.\n```delegate\ngoal: Write the lexer\nfiles: src/lexer.rs\nsteps: 5\n```";
+        let actions = parse_actions(input);
+        assert_eq!(actions.len(), 1);
+        assert_eq!(
+            actions[0].0,
+            AgentAction::Delegate("Write the lexer".into(), vec!["src/lexer.rs".into()], 5)
+        );
+    }
+
+    #[test]
+    fn test_parse_ask_block() {
+        let input = "```ask\nWhich database should I use?\n1. SQLite\n- Postgres\n```";
+        let actions = parse_actions(input);
+        assert_eq!(actions.len(), 1);
+        assert_eq!(
+            actions[0].0,
+            AgentAction::AskUser(
+                "Which database should I use?".into(),
+                vec!["SQLite".into(), "Postgres".into()]
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_standard_write() {
+        let input = "```write test.txt\nHello World\n```";
+        let actions = parse_actions(input);
+        assert_eq!(actions.len(), 1);
+        if let AgentAction::WriteFile(path, content) = &actions[0].0 {
+            assert_eq!(path, "test.txt");
+            assert_eq!(content, "Hello World\n");
+        } else {
+            panic!("Expected WriteFile");
+        }
+    }
+
+    /// Short form of an action for the corpus expectations.
+    fn summary(action: &AgentAction) -> String {
+        match action {
+            AgentAction::WriteFile(path, _) => format!("write {}", path),
+            AgentAction::EditFile(path, _) => format!("edit {}", path),
+            AgentAction::ShellCommand(cmd) => format!("run {}", cmd),
+            AgentAction::ReadFile(path, _) => format!("read {}", path),
+            AgentAction::ListDir(path) => format!("list {}", path),
+            AgentAction::Find(path, pattern) => format!("find {} {}", path, pattern),
+            AgentAction::Done => "done".to_string(),
+            other => format!("{:?}", other),
+        }
+    }
+
+    /// Responses from real runs (`corpus/`), with the actions and issues they must produce.
+    const CORPUS: &[(&str, &[&str], &[&str])] = &[
+        (
+            "architect_nested_markdown.md",
+            &["write tasks/specs/architecture.md", "write tasks/specs/roadmap.md", "done"],
+            &[],
+        ),
+        ("quad_tick_readme.md", &["write README.md"], &[]),
+        ("done_in_prose.md", &["run cargo test cli::"], &[]),
+        ("checklist_write_with_done.md", &["write tasks/003-add-cli-flags/notes.md", "run cargo build"], &[]),
+        (
+            "loose_forms.md",
+            &["read src/main.rs", "list src", "find src *.rs", "read Cargo.toml"],
+            &[],
+        ),
+        ("unclosed_edit.md", &[], &["`edit` block on line 3 is not closed (the closing fence is missing)"]),
+        ("missing_write_path.md", &["read src/config.rs"], &["`write` block on line 3 has no file path (```write path/to/file```)"]),
+        ("glued_closing_fence.md", &["run cargo build 2>&1 | tail -20"], &[]),
+        ("edit_then_done.md", &["edit src/cli.rs", "run cargo test", "done"], &[]),
+        ("assistant_explanation.md", &[], &[]),
+    ];
+
+    #[test]
+    fn test_regression_corpus() {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src/application/parsing/corpus");
+        for (name, actions, issues) in CORPUS {
+            let response = std::fs::read_to_string(dir.join(name)).unwrap();
+            let parsed = parse_response(&response);
+            let found: Vec<String> = parsed.actions.iter().map(|(a, ..)| summary(a)).collect();
+            assert_eq!(found, *actions, "actions of {}", name);
+            let reported: Vec<String> = parsed.issues.iter().map(|i| i.to_string()).collect();
+            assert_eq!(reported, *issues, "issues of {}", name);
+        }
+    }
+
+    #[test]
+    fn test_shell_fence_inside_write_does_not_run() {
+        let input = "```write docs/setup.md\n# Setup\n```bash\nrm -rf target\n```\n```";
+        let actions = parse_actions(input);
+        assert_eq!(actions.len(), 1);
+        assert_eq!(
+            actions[0].0,
+            AgentAction::WriteFile("docs/setup.md".into(), "# Setup\n```bash\nrm -rf target\n```\n".into())
+        );
+        assert_eq!((actions[0].1, actions[0].2), (0, input.len()));
+    }
+
+    #[test]
+    fn test_done_signal() {
+        assert!(parse_actions("The parser is DONE, now the tests.").is_empty());
+        assert!(parse_actions("SHUTDOWN_DONE\nNO_MORE_STEPS_LATER").is_empty());
+        assert_eq!(parse_actions("All items ticked.\n`NO_MORE_STEPS`")[0].0, AgentAction::Done);
+        let done = parse_actions("Finished.\n\nDONE.\n");
+        assert_eq!(done[0].0, AgentAction::Done);
+        assert_eq!((done[0].1, done[0].2), (11, 15));
+        // Only one Done, at the first signal
+        assert_eq!(parse_actions("NO_MORE_STEPS\nDONE\nNO_MORE_STEPS").len(), 1);
+    }
+
+    #[test]
+    fn test_malformed_tool_blocks_are_reported() {
+        let parsed = parse_response("```find src```\n```symbol\n```\n```bash\n```\n```ask\n1. a\n```\n```python\nprint(1)\n```");
+        assert!(parsed.actions.is_empty());
+        let tags: Vec<&str> = parsed.issues.iter().map(|i| i.tag.as_str()).collect();
+        assert_eq!(tags, vec!["symbol", "bash", "ask"]);
+        assert_eq!(parsed.issues[0].line, 2);
+    }
+
+    /// Lines that stress the tokenizer: fences of every length, tags, spans and signals.
+    fn fuzz_line() -> impl Strategy<Value = String> {
+        prop_oneof![
+            Just("```".to_string()),
+            Just("````".to_string()),
+            Just("~~~".to_string()),
+            Just("```write a.txt".to_string()),
+            Just("````write b.md".to_string()),
+            Just("```edit a.rs".to_string()),
+            Just("```bash".to_string()),
+            Just("```rust".to_string()),
+            Just("```read x.rs```".to_string()),
+            Just("`list src`".to_string()),
+            Just("ls -la```".to_string()),
+            Just("DONE".to_string()),
+            Just("NO_MORE_STEPS".to_string()),
+            "[a-z`* ]{0,12}",
+            ".{0,12}",
+        ]
+    }
+
+    /// Content lines of a file written by the agent, including fences shorter than four ticks.
+    fn content_line() -> impl Strategy<Value = String> {
+        prop_oneof![
+            4 => "[a-zA-Z0-9 (){};=.-]{0,24}",
+            1 => Just("```rust".to_string()),
+            1 => Just("```bash".to_string()),
+            1 => Just("```".to_string()),
+            1 => Just("DONE".to_string()),
+            1 => Just("`read secrets.txt`".to_string()),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn prop_ranges_are_ordered_and_in_bounds(lines in prop::collection::vec(fuzz_line(), 0..16)) {
+            let response = lines.join("\n");
+            let parsed = parse_response(&response);
+            let mut last = 0;
+            for (_, start, end) in &parsed.actions {
+                prop_assert!(*start >= last && start <= end && *end <= response.len());
+                prop_assert!(response.is_char_boundary(*start) && response.is_char_boundary(*end));
+                last = *start;
+            }
+            let done = parsed.actions.iter().filter(|(a, ..)| *a == AgentAction::Done).count();
+            prop_assert!(done <= 1);
+        }
+
+        #[test]
+        fn prop_quad_tick_write_roundtrips(lines in prop::collection::vec(content_line(), 0..12), path in "[a-z]{1,8}/[a-z]{1,8}\\.md") {
+            let content: String = lines.iter().map(|l| format!("{}\n", l)).collect();
+            let response = format!("Writing it.\n````write {}\n{}````\n", path, content);
+            let actions = parse_actions(&response);
+            prop_assert_eq!(actions.len(), 1);
+            prop_assert_eq!(&actions[0].0, &AgentAction::WriteFile(path, content));
+        }
+
+        #[test]
+        fn prop_prose_never_signals_done(lines in prop::collection::vec(prop::collection::vec(prop_oneof!["[a-z]{1,8}", Just("DONE".to_string())], 2..6), 0..6)) {
+            let response = lines.iter().map(|words| words.join(" ")).collect::<Vec<_>>().join("\n");
+            prop_assert!(parse_actions(&response).is_empty());
+        }
+    }
+}
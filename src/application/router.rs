@@ -48,18 +48,18 @@ impl CommandRouter {
         // Cancel Auto-Continue Timer on ANY user message (unless it's the bot itself, handled by caller check usually)
         if sender != chat.room_id() { // Simple check, though main.rs already checks sender != own_user_id
              let mut guard = self.state.lock().await;
-             if let Some(room) = guard.rooms.get_mut(&chat.room_id()) {
-                 if room.task_completion_time.is_some() {
-                     room.task_completion_time = None;
-                     // access feed via room
-                     if let Some(feed_mutex) = &room.feed_manager {
-                         let mut feed = feed_mutex.lock().await;
-                         feed.auto_start_timestamp = None;
-                         // Force update to remove countdown? Or wait for next sticky?
-                         // Ideally we force update if it was currently displaying the countdown.
-                         // But router doesn't easily async update feed here without holding locks too long.
-                         // We'll let the interaction trigger the next update naturally or rely on the fact the user is typing.
-                     }
+             if let Some(room) = guard.rooms.get_mut(&chat.room_id())
+                 && room.task_completion_time.is_some()
+             {
+                 room.task_completion_time = None;
+                 // access feed via room
+                 if let Some(feed_mutex) = &room.feed_manager {
+                     let mut feed = feed_mutex.lock().await;
+                     feed.auto_start_timestamp = None;
+                     // Force update to remove countdown? Or wait for next sticky?
+                     // Ideally we force update if it was currently displaying the countdown.
+                     // But router doesn't easily async update feed here without holding locks too long.
+                     // We'll let the interaction trigger the next update naturally or rely on the fact the user is typing.
                  }
              }
         }
@@ -159,6 +159,18 @@ impl CommandRouter {
         }
 
         if !msg.starts_with('.') && !msg.starts_with(',') {
+            // A running task asked a question: plain replies are the answer
+            let answer_tx = {
+                let guard = self.state.lock().await;
+                guard
+                    .rooms
+                    .get(&chat.room_id())
+                    .filter(|r| r.awaiting_answer)
+                    .and_then(|r| r.answer_tx.clone())
+            };
+            if let Some(tx) = answer_tx {
+                let _ = tx.send(msg.to_string()).await;
+            }
             return Ok(());
         }
 
@@ -278,13 +290,9 @@ impl CommandRouter {
                         // But we can't easily check existence.
                         // We'll trust checking if we can "read metadata" or similar?
                         // Using read_file might be heavy if big? typically small.
-                        match t
-                            .read_file(&conversation_path.to_string_lossy().to_string())
+                        t.read_file(conversation_path.to_string_lossy().as_ref())
                             .await
-                        {
-                            Ok(_) => true,
-                            Err(_) => false,
-                        }
+                            .is_ok()
                     };
 
                     if conversation_active {
@@ -310,10 +318,7 @@ impl CommandRouter {
 
                     let plan_exists = {
                         let t = self.tools.lock().await;
-                        match t.read_file(&plan_path.to_string_lossy().to_string()).await {
-                            Ok(_) => true,
-                            Err(_) => false,
-                        }
+                        t.read_file(plan_path.to_string_lossy().as_ref()).await.is_ok()
                     };
 
                     if plan_exists {
@@ -376,6 +381,23 @@ impl CommandRouter {
                     )
                     .await?;
                 } else {
+                    // One task at a time per room: later ones wait in the queue
+                    let busy = {
+                        let guard = self.state.lock().await;
+                        guard
+                            .rooms
+                            .get(&chat.room_id())
+                            .is_some_and(|r| r.is_task_running())
+                    };
+                    if busy {
+                        let position =
+                            commands::queue::enqueue_task(&self.state, &chat.room_id(), args).await;
+                        let _ = chat
+                            .send_notification(&crate::strings::messages::task_queued(position))
+                            .await;
+                        return Ok(());
+                    }
+
                     // Initialize Engine
 
                     // Determine working directory AND existing feed AND stored_id
@@ -468,6 +490,50 @@ impl CommandRouter {
             ".status" => {
                 commands::misc::handle_status(&self.config, &self.state, chat).await?;
             }
+            ".dryrun" => {
+                commands::dryrun::handle_dryrun(
+                    &self.config,
+                    &self.state,
+                    self.tools.clone(),
+                    chat,
+                    args,
+                )
+                .await?;
+            }
+            ".changes" => {
+                commands::git::handle_changes(&self.state, chat).await?;
+            }
+            ".commit" => {
+                commands::git::handle_commit(&self.state, chat, args).await?;
+            }
+            ".discard" => {
+                commands::git::handle_discard(&self.state, chat).await?;
+            }
+            ".undo" => {
+                commands::undo::handle_undo(&self.state, self.tools.clone(), chat).await?;
+            }
+            ".rewind" => {
+                commands::undo::handle_rewind(&self.state, self.tools.clone(), chat, args).await?;
+            }
+            ".say" => {
+                commands::say::handle_say(&self.state, chat, args).await?;
+            }
+            ".queue" => {
+                commands::queue::handle_queue(&self.state, chat, args).await?;
+            }
+            ".roadmap" => {
+                commands::roadmap::handle_roadmap(&self.state, chat, args).await?;
+            }
+            ".verify" => {
+                commands::verify::handle_verify(
+                    &self.config,
+                    &self.state,
+                    self.tools.clone(),
+                    chat,
+                    args,
+                )
+                .await?;
+            }
             ".ask" => {
                 commands::misc::handle_ask(
                     &self.config,
@@ -518,26 +584,90 @@ impl CommandRouter {
                 // Use handle_start for Execution Phase
                 commands::start::handle_start(&self.config, &self.state, &engine, chat, workdir).await?;
             }
+            ".pause" => {
+                commands::resume::handle_pause(&self.state, chat).await?;
+            }
+            ".resume" => {
+                let (workdir, feed) = {
+                    let mut guard = self.state.lock().await;
+                    let room = guard.get_room_state(&chat.room_id());
+                    room.ensure_feed_manager(self.tools.clone(), self.config.system.projects_dir.clone());
+                    (room.current_working_dir.clone(), room.feed_manager.clone())
+                };
+                let feed = feed.unwrap_or_else(|| {
+                    Arc::new(Mutex::new(FeedManager::new(
+                        workdir,
+                        self.config.system.projects_dir.clone(),
+                        self.tools.clone(),
+                        None,
+                    )))
+                });
+
+                let engine = ExecutionEngine::new(
+                    self.config.clone(),
+                    self.llm.clone(),
+                    self.tools.clone(),
+                    feed,
+                    self.state.clone(),
+                );
+
+                commands::resume::handle_resume(&self.config, &self.state, &engine, chat).await?;
+            }
             ".stop" => {
-                let mut guard = self.state.lock().await;
-                let room = guard.get_room_state(&chat.room_id());
-                room.stop_requested = true;
-
-                // Instant Abort
-                if let Some(handle_lock) = &room.task_handle {
-                    let mut handle = handle_lock.lock().await;
-                    if let Some(h) = handle.take() {
-                        h.abort();
-                        let _ = chat
-                            .send_message("🛑 **Task Stopped Instantly (Aborted)**")
-                            .await;
+                let aborted = {
+                    let mut guard = self.state.lock().await;
+                    let room = guard.get_room_state(&chat.room_id());
+                    room.stop_requested = true;
+                    room.awaiting_answer = false;
+                    room.answer_tx = None;
+                    // A stopped task is abandoned, so it should not be offered for `.resume`
+                    room.clear_checkpoint();
+
+                    // Instant Abort
+                    if let Some(handle_lock) = &room.task_handle {
+                        let mut handle = handle_lock.lock().await;
+                        if let Some(h) = handle.take() {
+                            h.abort();
+                            let _ = chat
+                                .send_message("🛑 **Task Stopped Instantly (Aborted)**")
+                                .await;
+                            true
+                        } else {
+                            let _ = chat
+                                .send_message("🛑 Stop requested (Flag set, no active handle).")
+                                .await;
+                            false
+                        }
                     } else {
-                        let _ = chat
-                            .send_message("🛑 Stop requested (Flag set, no active handle).")
-                            .await;
+                        let _ = chat.send_message("🛑 Stop requested (Flag set).").await;
+                        false
                     }
-                } else {
-                    let _ = chat.send_message("🛑 Stop requested (Flag set).").await;
+                };
+
+                // The aborted run never gets to start the next queued task itself
+                if aborted {
+                    let (workdir, feed) = {
+                        let mut guard = self.state.lock().await;
+                        let room = guard.get_room_state(&chat.room_id());
+                        room.ensure_feed_manager(self.tools.clone(), self.config.system.projects_dir.clone());
+                        (room.current_working_dir.clone(), room.feed_manager.clone())
+                    };
+                    let feed = feed.unwrap_or_else(|| {
+                        Arc::new(Mutex::new(FeedManager::new(
+                            workdir,
+                            self.config.system.projects_dir.clone(),
+                            self.tools.clone(),
+                            None,
+                        )))
+                    });
+                    let engine = ExecutionEngine::new(
+                        self.config.clone(),
+                        self.llm.clone(),
+                        self.tools.clone(),
+                        feed,
+                        self.state.clone(),
+                    );
+                    commands::queue::start_next(&self.config, &self.state, &engine, chat, false).await;
                 }
             }
             ".deny" | ".no" | ".cancel" => {
@@ -0,0 +1,115 @@
+//! # Stagnation Detection
+//!
+//! Tracks what the agent did and what it got back, across the steps of a `run_task` loop.
+//! Repeating an action that already produced the same result (re-reading an unchanged file,
+//! re-running a failing command, rewriting identical content) is a sign the run is stuck.
+
+use crate::domain::types::AgentAction;
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Stagnation {
+    /// Nothing suspicious.
+    Progress,
+    /// First repetition: tell the model to change approach.
+    Repeated,
+    /// Repeated again after the warning: ask the user.
+    Stuck,
+}
+
+#[derive(Debug, Default)]
+pub struct StagnationDetector {
+    /// action fingerprint -> (result hash, times seen with that result)
+    seen: HashMap<u64, (u64, usize)>,
+}
+
+impl StagnationDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records an action and its result, and reports whether this is a repetition.
+    pub fn observe(&mut self, action: &AgentAction, result: &str) -> Stagnation {
+        // Completion and mode switches are not work that can loop; questions get fresh answers
+        if matches!(
+            action,
+            AgentAction::Done | AgentAction::SwitchMode(_) | AgentAction::AskUser(_, _)
+        ) {
+            return Stagnation::Progress;
+        }
+
+        let result_hash = hash_of(&result.trim());
+        let entry = self.seen.entry(hash_of(action)).or_insert((result_hash, 0));
+        if entry.0 != result_hash {
+            // Same action, different outcome (file changed, command now passes): not a loop
+            *entry = (result_hash, 0);
+        }
+        entry.1 += 1;
+
+        match entry.1 {
+            1 => Stagnation::Progress,
+            2 => Stagnation::Repeated,
+            _ => Stagnation::Stuck,
+        }
+    }
+
+    /// Forgets everything (e.g. after the user told the agent to carry on).
+    pub fn reset(&mut self) {
+        self.seen.clear();
+    }
+}
+
+/// Short human-readable description of an action for warnings.
+pub fn describe(action: &AgentAction) -> String {
+    match action {
+        AgentAction::ShellCommand(cmd) => format!("run `{}`", cmd.lines().next().unwrap_or(cmd)),
+        AgentAction::WriteFile(path, _) => format!("write `{}`", path),
+        AgentAction::EditFile(path, _) => format!("edit `{}`", path),
+        AgentAction::ReadFile(path, _) => format!("read `{}`", path),
+        AgentAction::Outline(path) => format!("outline `{}`", path),
+        AgentAction::FindSymbol(name) => format!("look up `{}`", name),
+        AgentAction::ListDir(path) => format!("list `{}`", path),
+        AgentAction::Find(path, pattern) => format!("find `{}` in `{}`", pattern, path),
+        AgentAction::Search(query) => format!("search `{}` in `{}`", query.pattern, query.path),
+        AgentAction::Delegate(goal, _, _) => {
+            format!("delegate `{}`", goal.lines().next().unwrap_or(goal))
+        }
+        AgentAction::AskUser(question, _) => format!("ask `{}`", question),
+        AgentAction::SwitchMode(phase) => format!("switch to {}", phase),
+        AgentAction::Done => "finish".to_string(),
+    }
+}
+
+fn hash_of(value: &impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repetition_escalates() {
+        let mut detector = StagnationDetector::new();
+        let read = AgentAction::ReadFile("src/main.rs".into(), None);
+        assert_eq!(detector.observe(&read, "fn main() {}"), Stagnation::Progress);
+        assert_eq!(detector.observe(&read, "fn main() {}"), Stagnation::Repeated);
+        assert_eq!(detector.observe(&read, "fn main() {}"), Stagnation::Stuck);
+
+        detector.reset();
+        assert_eq!(detector.observe(&read, "fn main() {}"), Stagnation::Progress);
+    }
+
+    #[test]
+    fn test_changed_result_is_progress() {
+        let mut detector = StagnationDetector::new();
+        let test = AgentAction::ShellCommand("cargo test".into());
+        assert_eq!(detector.observe(&test, "1 failed"), Stagnation::Progress);
+        assert_eq!(detector.observe(&test, "ok"), Stagnation::Progress);
+        assert_eq!(detector.observe(&test, "ok"), Stagnation::Repeated);
+    }
+}
@@ -4,7 +4,9 @@
 //! This includes active tasks, agent configurations, wizard status, and conversation history context.
 //! It handles serialization and deserialization to/from JSON.
 
+use crate::application::checkpoint::TaskCheckpoint;
 use crate::application::feed::FeedManager;
+use crate::infrastructure::tools::snapshot::SnapshotStore;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
@@ -67,8 +69,9 @@ pub struct RoomState {
 
     #[serde(default)]
     pub is_task_completed: bool,
+    /// Pause switch for the running task (`.pause` / `.resume`)
     #[serde(skip)]
-    pub _abort_handle: Option<Arc<watch::Sender<bool>>>,
+    pub pause_tx: Option<Arc<watch::Sender<bool>>>,
     #[serde(default)]
     pub wizard: WizardState,
     #[serde(default)]
@@ -88,14 +91,42 @@ pub struct RoomState {
     pub feed_event_id: Option<String>,
     #[serde(skip)]
     pub feed_manager: Option<Arc<Mutex<FeedManager>>>,
+    /// User input for the running task (answers to `ask` blocks)
     #[serde(skip)]
-    pub _input_tx: Option<tokio::sync::mpsc::Sender<String>>,
+    pub input_tx: Option<tokio::sync::mpsc::Sender<String>>,
+    /// The running task asked a question and is waiting for a plain reply
+    #[serde(skip)]
+    pub awaiting_answer: bool,
+    /// Plain replies while `awaiting_answer` go here (kept apart from `.say` guidance on `input_tx`)
+    #[serde(skip)]
+    pub answer_tx: Option<tokio::sync::mpsc::Sender<String>>,
     #[serde(skip)]
     pub pending_approval_tx: Option<Arc<Mutex<Option<tokio::sync::oneshot::Sender<bool>>>>>,
     #[serde(skip)]
     pub task_handle: Option<Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>>,
     #[serde(default)]
     pub task_completion_time: Option<i64>,
+    /// Last verification run failed; blocks task completion until green
+    #[serde(default)]
+    pub verification_failing: bool,
+    /// User accepted completion despite failing verification (`.verify override`)
+    #[serde(default)]
+    pub verification_override: bool,
+    /// Checkpoint handed to the next `run_task` call by `.resume`
+    #[serde(skip)]
+    pub resume_checkpoint: Option<TaskCheckpoint>,
+    /// Tasks simulate writes and commands instead of performing them (`.dryrun`)
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Commit the current task branch started from (`.discard` resets to it)
+    #[serde(default)]
+    pub task_base: Option<String>,
+    /// Tasks waiting for the current one to finish (FIFO)
+    #[serde(default)]
+    pub task_queue: Vec<String>,
+    /// The current task was started by the queue, so its plan is executed without waiting
+    #[serde(default)]
+    pub queue_running: bool,
 }
 
 impl RoomState {
@@ -114,6 +145,57 @@ impl RoomState {
             self.feed_manager = Some(Arc::new(Mutex::new(mgr)));
         }
     }
+
+    /// True while a `run_task` loop is alive for this room.
+    pub fn is_task_running(&self) -> bool {
+        match &self.task_handle {
+            Some(lock) => match lock.try_lock() {
+                Ok(handle) => handle.as_ref().is_some_and(|h| !h.is_finished()),
+                // Someone is holding the handle (e.g. `.stop` in progress)
+                Err(_) => true,
+            },
+            None => false,
+        }
+    }
+
+    /// True while the running task is parked by `.pause`.
+    pub fn is_paused(&self) -> bool {
+        self.pause_tx.as_ref().is_some_and(|tx| *tx.borrow()) && self.is_task_running()
+    }
+
+    /// Loads the checkpoint of the active task, if the room was interrupted mid-task.
+    pub fn load_checkpoint(&self) -> Option<TaskCheckpoint> {
+        let wd = self
+            .current_working_dir
+            .as_deref()
+            .or(self.current_project_path.as_deref())?;
+        let task_rel = self.active_task.as_deref()?;
+        TaskCheckpoint::load(wd, task_rel)
+    }
+
+    /// Undo history of the active task (`{wd}/{task}/snapshots`).
+    pub fn snapshot_store(&self) -> Option<SnapshotStore> {
+        let wd = self
+            .current_working_dir
+            .as_deref()
+            .or(self.current_project_path.as_deref())?;
+        let task_rel = self.active_task.as_deref()?;
+        let dir = std::path::Path::new(wd)
+            .join(task_rel)
+            .join(crate::domain::paths::SNAPSHOTS_DIR);
+        Some(SnapshotStore::new(wd, dir))
+    }
+
+    /// Removes the checkpoint of the active task (task finished or stopped).
+    pub fn clear_checkpoint(&self) {
+        let wd = self
+            .current_working_dir
+            .as_ref()
+            .or(self.current_project_path.as_ref());
+        if let (Some(wd), Some(task_rel)) = (wd, &self.active_task) {
+            TaskCheckpoint::clear(wd, task_rel);
+        }
+    }
 }
 
 /// Persistent state of the bot, mapping Room IDs to their respective room states.
@@ -129,21 +211,21 @@ impl BotState {
     pub fn get_room_state(&mut self, room_id: &str) -> &mut RoomState {
         self.rooms
             .entry(room_id.to_string())
-            .or_insert_with(RoomState::default)
+            .or_default()
     }
     /// Loads the state from `data/state.json` or returns default.
     pub fn load() -> Self {
-        if let Ok(content) = fs::read_to_string("data/state.json") {
-            if let Ok(mut state) = serde_json::from_str::<Self>(&content) {
-                // Sanitize: Reset wizards on load
-                for room in state.rooms.values_mut() {
-                    room.wizard.active = false;
-                    room.wizard.step = None;
-                    room.wizard.data.clear();
-                    room.wizard.buffer.clear();
-                }
-                return state;
+        if let Ok(content) = fs::read_to_string("data/state.json")
+            && let Ok(mut state) = serde_json::from_str::<Self>(&content)
+        {
+            // Sanitize: Reset wizards on load
+            for room in state.rooms.values_mut() {
+                room.wizard.active = false;
+                room.wizard.step = None;
+                room.wizard.data.clear();
+                room.wizard.buffer.clear();
             }
+            return state;
         }
         Self::default()
     }
@@ -4,14 +4,9 @@
 pub fn sanitize_path(path: &str, projects_dir: Option<&str>) -> String {
     if let Some(root) = projects_dir {
         // Normalize root by stripping trailing slash for consistent comparison
-        let root = if root.ends_with('/') {
-            &root[..root.len() - 1]
-        } else {
-            root
-        };
+        let root = root.strip_suffix('/').unwrap_or(root);
 
-        if path.starts_with(root) {
-            let relative = &path[root.len()..];
+        if let Some(relative) = path.strip_prefix(root) {
             if relative.is_empty() {
                 return "/".to_string();
             }
@@ -44,11 +39,7 @@ pub fn check_command_safety(command: &str, projects_root: Option<&str>) -> bool
         // 2. Check Absolute Paths
         if clean_token.starts_with('/') {
             if let Some(root) = projects_root {
-                let normalized_root = if root.ends_with('/') {
-                    &root[..root.len() - 1]
-                } else {
-                    root
-                };
+                let normalized_root = root.strip_suffix('/').unwrap_or(root);
                 // Must start with root to be safe
                 if !clean_token.starts_with(normalized_root) {
                     return false; // Accessing /etc, /var, etc.
@@ -0,0 +1,129 @@
+//! # Verification Pipeline
+//!
+//! Runs the configured verification commands (e.g. `cargo check`, `cargo test`) after the agent writes code,
+//! and turns their output into structured results for the engine history and the feed.
+
+use crate::infrastructure::tools::executor::SharedToolExecutor;
+use std::path::Path;
+
+/// Maximum characters of command output kept in history per failing command.
+const OUTPUT_TAIL_CHARS: usize = 3000;
+
+#[derive(Debug, Clone)]
+pub struct VerificationResult {
+    pub command: String,
+    pub success: bool,
+    pub output: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct VerificationReport {
+    pub results: Vec<VerificationResult>,
+}
+
+impl VerificationReport {
+    pub fn is_green(&self) -> bool {
+        self.results.iter().all(|r| r.success)
+    }
+
+    pub fn passed(&self) -> usize {
+        self.results.iter().filter(|r| r.success).count()
+    }
+
+    /// Short one-line summary for the feed (e.g. "2/3 passed").
+    pub fn summary(&self) -> String {
+        format!("{}/{} passed", self.passed(), self.results.len())
+    }
+
+    /// Structured block appended to the engine history so the model sees exactly what failed.
+    pub fn to_history(&self) -> String {
+        let mut out = format!("Verification results ({}):\n", self.summary());
+        for r in &self.results {
+            let status = if r.success { "PASS" } else { "FAIL" };
+            out.push_str(&format!("[{}] {}\n", status, r.command));
+        }
+        for r in self.results.iter().filter(|r| !r.success) {
+            out.push_str(&format!("\n--- {} ---\n{}\n", r.command, tail(&r.output, OUTPUT_TAIL_CHARS)));
+        }
+        out
+    }
+}
+
+/// Runs the pipeline in order, stopping at the first failing command.
+pub async fn run_pipeline(
+    tools: &SharedToolExecutor,
+    commands: &[String],
+    cwd: &Path,
+) -> VerificationReport {
+    let mut report = VerificationReport::default();
+    for command in commands {
+        let output = {
+            let client = tools.lock().await;
+            client.execute_command(command, cwd).await
+        };
+        let (output, success) = match output {
+            Ok(o) => {
+                let ok = !o.contains("[Exit Code:");
+                (o, ok)
+            }
+            Err(e) => (format!("Error: {}", e), false),
+        };
+        report.results.push(VerificationResult {
+            command: command.clone(),
+            success,
+            output,
+        });
+        if !success {
+            break;
+        }
+    }
+    report
+}
+
+/// Keeps the end of long outputs, where compilers put the summary.
+pub(crate) fn tail(text: &str, max_chars: usize) -> &str {
+    if text.len() <= max_chars {
+        return text;
+    }
+    let mut start = text.len() - max_chars;
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    &text[start..]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_history_lists_failures() {
+        let report = VerificationReport {
+            results: vec![
+                VerificationResult {
+                    command: "cargo check".into(),
+                    success: true,
+                    output: String::new(),
+                },
+                VerificationResult {
+                    command: "cargo test".into(),
+                    success: false,
+                    output: "test foo ... FAILED".into(),
+                },
+            ],
+        };
+        assert!(!report.is_green());
+        assert_eq!(report.summary(), "1/2 passed");
+        let history = report.to_history();
+        assert!(history.contains("[PASS] cargo check"));
+        assert!(history.contains("[FAIL] cargo test"));
+        assert!(history.contains("test foo ... FAILED"));
+    }
+
+    #[test]
+    fn test_tail_respects_char_boundaries() {
+        let text = "ééééé";
+        assert_eq!(tail(text, 3), "é");
+        assert_eq!(tail("short", 10), "short");
+    }
+}
@@ -0,0 +1,278 @@
+//! # Task Checklist
+//!
+//! Structured view of a task's `tasks.md`: phases made of checkbox items with their states.
+//! A phase is either a top-level checkbox with nested items (`- [ ] **Phase 1: Build**`) or a
+//! heading followed by items. Used for progress reporting, for rejecting agent edits that drop
+//! or silently reopen items, and for gating task completion.
+
+/// Suffix that makes reopening a ticked item explicit: `- [ ] Add lexer (reopened: tests fail)`.
+pub const REOPEN_MARKER: &str = "(reopened";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemState {
+    Open,
+    Done,
+    /// `[-]` or `[~]`: deliberately not done (counts as resolved)
+    Skipped,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChecklistItem {
+    pub text: String,
+    pub state: ItemState,
+    /// 1-based line in the file
+    pub line: usize,
+}
+
+impl ChecklistItem {
+    pub fn is_resolved(&self) -> bool {
+        self.state != ItemState::Open
+    }
+
+    /// Text used to match an item across edits: no markdown emphasis, no reopen note.
+    fn key(&self) -> String {
+        let text = match self.text.find(REOPEN_MARKER) {
+            Some(i) => &self.text[..i],
+            None => &self.text,
+        };
+        text.replace("**", "").replace('`', "").trim().to_lowercase()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Phase {
+    pub title: String,
+    /// State of the phase's own checkbox, if it has one
+    pub state: Option<ItemState>,
+    pub items: Vec<ChecklistItem>,
+}
+
+impl Phase {
+    pub fn is_complete(&self) -> bool {
+        if self.items.is_empty() {
+            return self.state.is_some_and(|s| s != ItemState::Open);
+        }
+        self.items.iter().all(|i| i.is_resolved())
+    }
+}
+
+/// A change to the checklist that the agent is not allowed to make.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation {
+    Removed(String),
+    Unchecked(String),
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Violation::Removed(item) => write!(f, "removed `{}`", item),
+            Violation::Unchecked(item) => write!(f, "unchecked `{}`", item),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Checklist {
+    pub phases: Vec<Phase>,
+}
+
+impl Checklist {
+    pub fn parse(text: &str) -> Self {
+        let lines: Vec<&str> = text.lines().collect();
+        let boxes: Vec<Option<(usize, ItemState, String)>> = lines.iter().map(|l| checkbox(l)).collect();
+        let top_indent = boxes.iter().flatten().map(|(indent, _, _)| *indent).min().unwrap_or(0);
+
+        let mut phases: Vec<Phase> = Vec::new();
+        let mut heading: Option<String> = None;
+        // Whether the last phase is a checkbox group still taking nested items
+        let mut in_group = false;
+
+        for (i, line) in lines.iter().enumerate() {
+            let trimmed = line.trim();
+            if let Some(title) = trimmed.strip_prefix('#') {
+                heading = Some(title.trim_start_matches('#').trim().to_string());
+                in_group = false;
+                continue;
+            }
+            let Some((indent, state, text)) = &boxes[i] else {
+                continue;
+            };
+            if text.is_empty() {
+                continue;
+            }
+            let has_children = boxes[i + 1..]
+                .iter()
+                .flatten()
+                .next()
+                .is_some_and(|(next, _, _)| next > indent);
+
+            if *indent == top_indent && has_children {
+                phases.push(Phase {
+                    title: text.replace("**", "").trim().to_string(),
+                    state: Some(*state),
+                    items: Vec::new(),
+                });
+                in_group = true;
+                continue;
+            }
+            if *indent == top_indent {
+                in_group = false;
+            }
+            if !in_group {
+                // A flat item: it belongs to the phase of the heading above it
+                let title = heading.take();
+                if title.is_some() || phases.is_empty() {
+                    phases.push(Phase {
+                        title: title.unwrap_or_default(),
+                        state: None,
+                        items: Vec::new(),
+                    });
+                }
+            }
+            if let Some(phase) = phases.last_mut() {
+                phase.items.push(ChecklistItem {
+                    text: text.clone(),
+                    state: *state,
+                    line: i + 1,
+                });
+            }
+        }
+        Self { phases }
+    }
+
+    pub fn items(&self) -> impl Iterator<Item = &ChecklistItem> {
+        self.phases.iter().flat_map(|p| p.items.iter())
+    }
+
+    pub fn total(&self) -> usize {
+        self.items().count()
+    }
+
+    pub fn resolved(&self) -> usize {
+        self.items().filter(|i| i.is_resolved()).count()
+    }
+
+    /// Texts of the ticked items.
+    pub fn done_items(&self) -> Vec<String> {
+        self.items()
+            .filter(|i| i.state == ItemState::Done)
+            .map(|i| i.text.clone())
+            .collect()
+    }
+
+    pub fn open_items(&self) -> Vec<&ChecklistItem> {
+        self.items().filter(|i| !i.is_resolved()).collect()
+    }
+
+    /// First phase with open items (1-based index).
+    pub fn current_phase(&self) -> Option<(usize, &Phase)> {
+        self.phases
+            .iter()
+            .enumerate()
+            .find(|(_, p)| !p.is_complete())
+            .map(|(i, p)| (i + 1, p))
+    }
+
+    pub fn next_item(&self) -> Option<&ChecklistItem> {
+        self.items().find(|i| !i.is_resolved())
+    }
+
+    /// `7/12 items, Phase 2` (the phase is left out for single-phase lists), or `None` if
+    /// there are no items.
+    pub fn progress(&self) -> Option<String> {
+        let total = self.total();
+        if total == 0 {
+            return None;
+        }
+        let mut label = format!("{}/{} items", self.resolved(), total);
+        if self.phases.len() > 1
+            && let Some((n, _)) = self.current_phase()
+        {
+            label.push_str(&format!(", Phase {}", n));
+        }
+        Some(label)
+    }
+
+    /// Items of `self` that `updated` deletes or unticks. Reopening is allowed when the item
+    /// says why (`REOPEN_MARKER`); adding items is always allowed.
+    pub fn violations(&self, updated: &Checklist) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        for item in self.items() {
+            let key = item.key();
+            match updated.items().find(|i| i.key() == key) {
+                None => violations.push(Violation::Removed(item.text.clone())),
+                Some(new)
+                    if item.is_resolved()
+                        && !new.is_resolved()
+                        && !new.text.contains(REOPEN_MARKER) =>
+                {
+                    violations.push(Violation::Unchecked(item.text.clone()))
+                }
+                Some(_) => {}
+            }
+        }
+        violations
+    }
+}
+
+/// `(indent, state, text)` of a `- [ ]` / `* [x]` line.
+pub(crate) fn checkbox(line: &str) -> Option<(usize, ItemState, String)> {
+    let trimmed = line.trim_start();
+    let indent = line.len() - trimmed.len();
+    let rest = trimmed
+        .strip_prefix("- [")
+        .or_else(|| trimmed.strip_prefix("* ["))?;
+    let mut chars = rest.chars();
+    let state = match chars.next()? {
+        ' ' => ItemState::Open,
+        'x' | 'X' => ItemState::Done,
+        '-' | '~' => ItemState::Skipped,
+        _ => return None,
+    };
+    let text = chars.as_str().strip_prefix(']')?.trim().to_string();
+    Some((indent, state, text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEMPLATE: &str = "# Milestone\n\n## Task List\n- [x] **Phase 1: Implementation**\n  - [x] Implement Structs\n  - [x] Implement Logic\n- [ ] **Phase 2: Verification**\n  - [x] Add Unit Tests\n  - [ ] Run Integration Tests\n- [ ] **Phase 3: Documentation**\n  - [-] Update README\n  - [ ] Add Docstrings\n\n## Notes\n- Ensure tests pass.\n";
+
+    #[test]
+    fn test_parse_phases_and_progress() {
+        let list = Checklist::parse(TEMPLATE);
+        assert_eq!(list.phases.len(), 3);
+        assert_eq!(list.phases[1].title, "Phase 2: Verification");
+        assert_eq!(list.total(), 6);
+        assert_eq!(list.progress().as_deref(), Some("4/6 items, Phase 2"));
+        assert_eq!(list.next_item().map(|i| i.line), Some(9));
+        assert_eq!(list.done_items().len(), 3);
+
+        let flat = Checklist::parse("# Tasks\n- [x] One\n- [ ] Two\n");
+        assert_eq!(flat.progress().as_deref(), Some("1/2 items"));
+
+        let headed = Checklist::parse("## Phase A\n- [x] One\n## Phase B\n- [ ] Two\n");
+        assert_eq!(headed.phases.len(), 2);
+        assert_eq!(headed.progress().as_deref(), Some("1/2 items, Phase 2"));
+    }
+
+    #[test]
+    fn test_violations() {
+        let before = Checklist::parse(TEMPLATE);
+        let ticked = Checklist::parse(&TEMPLATE.replace("- [ ] Run Integration", "- [x] Run Integration"));
+        assert!(before.violations(&ticked).is_empty());
+
+        let dropped = Checklist::parse(&TEMPLATE.replace("  - [ ] Add Docstrings\n", ""));
+        assert_eq!(before.violations(&dropped), vec![Violation::Removed("Add Docstrings".into())]);
+
+        let unticked = TEMPLATE.replace("- [x] Add Unit Tests", "- [ ] Add Unit Tests");
+        assert_eq!(
+            before.violations(&Checklist::parse(&unticked)),
+            vec![Violation::Unchecked("Add Unit Tests".into())]
+        );
+        let reopened = TEMPLATE.replace("- [x] Add Unit Tests", "- [ ] Add Unit Tests (reopened: flaky)");
+        assert!(before.violations(&Checklist::parse(&reopened)).is_empty());
+    }
+}
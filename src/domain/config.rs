@@ -28,6 +28,42 @@ pub struct AppConfig {
     /// TUI Configuration
     #[serde(default)]
     pub tui: TuiConfig,
+    /// Verification pipelines run after code writes
+    #[serde(default)]
+    pub verification: VerificationConfig,
+    /// Commands run before or after agent actions (extended per project by `tasks/specs/hooks.yaml`)
+    #[serde(default)]
+    pub hooks: HooksConfig,
+    /// Settings of the project the config was scoped to with `for_project` (never in config.yaml)
+    #[serde(skip)]
+    pub project: Option<ProjectConfig>,
+}
+
+impl AppConfig {
+    /// The global config with the project's `tasks/specs/construct.yaml` merged over it.
+    /// Its command rules are not merged into `commands`: they stay in `project` and reach the
+    /// executor as its `ProjectPolicy`. An unreadable file is logged and ignored.
+    pub fn for_project(&self, workdir: &str) -> AppConfig {
+        let project = match ProjectConfig::load(workdir) {
+            Ok(Some(project)) => project,
+            Ok(None) => return self.clone(),
+            Err(e) => {
+                tracing::warn!("Ignoring project config in {}: {}", workdir, e);
+                return self.clone();
+            }
+        };
+
+        let mut config = self.clone();
+        if let Some(pipeline) = &project.verification {
+            let name = std::path::Path::new(workdir)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            config.verification.projects.insert(name, pipeline.clone());
+        }
+        config.project = Some(project);
+        config
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -91,6 +127,192 @@ pub struct SystemConfig {
     pub auto_start_delay_minutes: Option<u64>,
 }
 
+/// Verification commands the engine runs automatically after code writes in the Execution phase.
+#[derive(Debug, Default, Deserialize, Clone)]
+pub struct VerificationConfig {
+    /// Pipeline used for every project without its own entry (e.g. `cargo check`)
+    #[serde(default)]
+    pub commands: Vec<String>,
+    /// Per-project pipelines, keyed by project folder name
+    #[serde(default)]
+    pub projects: HashMap<String, Vec<String>>,
+}
+
+impl VerificationConfig {
+    /// Returns the pipeline for the project at `workdir` (project entry first, then the default).
+    pub fn pipeline_for(&self, workdir: &str) -> Vec<String> {
+        let name = std::path::Path::new(workdir)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        self.projects
+            .get(&name)
+            .cloned()
+            .unwrap_or_else(|| self.commands.clone())
+    }
+}
+
+/// Action a hook is attached to.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HookEvent {
+    /// A file write or edit (`matches` is tested against the project-relative path)
+    Write,
+    /// A shell command (`matches` is tested against the command line)
+    Command,
+    /// The end of a step that wrote files (post hooks only)
+    Batch,
+}
+
+/// A command run around an action, or a rule rejecting it.
+#[derive(Debug, Deserialize, Clone)]
+pub struct Hook {
+    pub on: HookEvent,
+    /// Glob the file path or command must match (all when unset), e.g. `*.rs` or `Cargo.lock`
+    #[serde(default)]
+    pub matches: Option<String>,
+    /// Command to run in the project root; `{file}` is replaced with the written file, already shell-quoted
+    #[serde(default)]
+    pub run: Option<String>,
+    /// Reject the action with this reason instead of running a command (pre hooks)
+    #[serde(default)]
+    pub reject: Option<String>,
+}
+
+impl Hook {
+    pub fn applies(&self, event: HookEvent, subject: Option<&str>) -> bool {
+        if self.on != event {
+            return false;
+        }
+        match (&self.matches, subject) {
+            (None, _) => true,
+            (Some(_), None) => false,
+            (Some(pattern), Some(subject)) => match glob::Pattern::new(pattern) {
+                Ok(p) => {
+                    p.matches(subject)
+                        || subject.rsplit('/').next().is_some_and(|name| p.matches(name))
+                }
+                Err(_) => pattern == subject,
+            },
+        }
+    }
+}
+
+/// Hooks run before (`pre`) and after (`post`) agent actions. A failing or rejecting pre hook
+/// blocks the action; post hook failures are reported to the model.
+#[derive(Debug, Default, Deserialize, Clone)]
+pub struct HooksConfig {
+    #[serde(default)]
+    pub pre: Vec<Hook>,
+    #[serde(default)]
+    pub post: Vec<Hook>,
+}
+
+impl HooksConfig {
+    /// The global hooks followed by the ones in the project's `tasks/specs/hooks.yaml`.
+    pub fn for_project(&self, workdir: &str) -> HooksConfig {
+        let mut hooks = self.clone();
+        let path = std::path::Path::new(workdir)
+            .join(crate::domain::paths::SPECS_DIR)
+            .join(crate::domain::paths::HOOKS_FILE);
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            return hooks;
+        };
+        match serde_yaml::from_str::<HooksConfig>(&content) {
+            Ok(project) => {
+                hooks.pre.extend(project.pre);
+                hooks.post.extend(project.post);
+            }
+            Err(e) => tracing::warn!("Ignoring invalid {:?}: {}", path, e),
+        }
+        hooks
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pre.is_empty() && self.post.is_empty()
+    }
+}
+
+/// Agent (and optionally model) used for one task phase.
+#[derive(Debug, Default, Deserialize, Clone, PartialEq)]
+pub struct PhaseAgent {
+    #[serde(default)]
+    pub agent: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+/// Command rules of one project, applied by the executor on top of the global ones.
+#[derive(Debug, Default, Deserialize, Clone)]
+pub struct ProjectCommands {
+    /// Commands (or command prefixes) that run without the out-of-project path approval
+    #[serde(default)]
+    pub allowed: Vec<String>,
+    /// Commands (or command prefixes) that never run
+    #[serde(default)]
+    pub blocked: Vec<String>,
+    /// Extra binaries that get the long timeout
+    #[serde(default)]
+    pub long_commands: Vec<String>,
+    #[serde(default)]
+    pub timeout: Option<u64>,
+    #[serde(default)]
+    pub long_timeout: Option<u64>,
+}
+
+/// Step limits of a project's tasks.
+#[derive(Debug, Default, Deserialize, Clone)]
+pub struct BudgetConfig {
+    /// Steps a task may take (20 by default)
+    #[serde(default)]
+    pub max_steps: Option<usize>,
+    /// Steps a sub-agent gets when the delegation does not name a budget
+    #[serde(default)]
+    pub delegate_steps: Option<usize>,
+}
+
+/// Project-level settings from `tasks/specs/construct.yaml`, merged over the global config by
+/// `AppConfig::for_project` whenever a room works inside the project.
+#[derive(Debug, Default, Deserialize, Clone)]
+pub struct ProjectConfig {
+    /// Agent/model per phase, keyed `planning`, `execution` or `assistant`
+    #[serde(default)]
+    pub agents: HashMap<String, PhaseAgent>,
+    /// Verification pipeline replacing the global one
+    #[serde(default)]
+    pub verification: Option<Vec<String>>,
+    #[serde(default)]
+    pub commands: ProjectCommands,
+    /// Globs (relative to the project) the agent may not write or edit, e.g. `Cargo.lock`
+    #[serde(default)]
+    pub protected_paths: Vec<String>,
+    /// Environment variables set for every command run in the project
+    #[serde(default)]
+    pub env: std::collections::BTreeMap<String, String>,
+    #[serde(default)]
+    pub budgets: BudgetConfig,
+}
+
+impl ProjectConfig {
+    /// Reads the project's config file; `Ok(None)` when it has none.
+    pub fn load(workdir: &str) -> anyhow::Result<Option<Self>> {
+        let path = std::path::Path::new(workdir)
+            .join(crate::domain::paths::SPECS_DIR)
+            .join(crate::domain::paths::PROJECT_CONFIG_FILE);
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            return Ok(None);
+        };
+        let config = serde_yaml::from_str(&content)
+            .map_err(|e| anyhow::anyhow!("{}: {}", crate::domain::paths::PROJECT_CONFIG_FILE, e))?;
+        Ok(Some(config))
+    }
+
+    /// Agent override for a phase (`planning`, `execution`, `assistant`).
+    pub fn phase_agent(&self, phase: &str) -> Option<&PhaseAgent> {
+        self.agents.get(phase)
+    }
+}
+
 /// Represents a specific bridge entry connecting a service to a channel.
 #[derive(Debug, Deserialize, Clone)]
 pub struct BridgeEntry {
@@ -110,7 +332,7 @@ pub struct ServicesConfig {
 
 pub type AgentsConfig = HashMap<String, AgentConfig>;
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Default, Deserialize, Clone)]
 pub struct AgentConfig {
     #[serde(default)]
     pub provider: String,
@@ -139,25 +361,6 @@ pub struct AgentConfig {
     pub extra_params: std::collections::HashMap<String, serde_json::Value>,
 }
 
-impl Default for AgentConfig {
-    fn default() -> Self {
-        Self {
-            provider: String::new(),
-            command: None,
-            model: String::new(),
-            endpoint: None,
-            api_key: None,
-            api_key_env: None,
-            model_order: None,
-            model_fallbacks: None,
-            fallback_agent: None,
-            requests_per_minute: None,
-            timeout: None,
-            extra_params: std::collections::HashMap::new(),
-        }
-    }
-}
-
 /// Specific configuration for the Matrix service.
 #[derive(Debug, Deserialize, Clone)]
 pub struct MatrixConfig {
@@ -186,3 +389,38 @@ impl Default for TuiConfig {
         Self { enabled: true }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_config_merges_over_global() {
+        let global: AppConfig = serde_yaml::from_str(
+            "services:\n  matrix:\n    username: bot\n    password: x\n    homeserver: https://example.org\ncommands:\n  blocked: [rm]\n",
+        )
+        .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let wd = dir.path().to_string_lossy().to_string();
+        assert!(global.for_project(&wd).project.is_none());
+
+        let specs = dir.path().join(crate::domain::paths::SPECS_DIR);
+        std::fs::create_dir_all(&specs).unwrap();
+        std::fs::write(
+            specs.join(crate::domain::paths::PROJECT_CONFIG_FILE),
+            "agents:\n  execution:\n    model: small-model\nverification:\n  - make check\ncommands:\n  blocked: [git push]\n  long_commands: [bazel]\n  timeout: 5\nbudgets:\n  max_steps: 40\n",
+        )
+        .unwrap();
+
+        let scoped = global.for_project(&wd);
+        let project = scoped.project.as_ref().unwrap();
+        assert_eq!(project.phase_agent("execution").unwrap().model.as_deref(), Some("small-model"));
+        assert!(project.phase_agent("planning").is_none());
+        assert_eq!(project.budgets.max_steps, Some(40));
+        assert_eq!(scoped.verification.pipeline_for(&wd), vec!["make check".to_string()]);
+        assert_eq!(project.commands.blocked, vec!["git push".to_string()]);
+        assert_eq!(project.commands.timeout, Some(5));
+        assert_eq!(scoped.commands.blocked, vec!["rm".to_string()]);
+        assert_eq!(scoped.commands.timeouts.default, global.commands.timeouts.default);
+    }
+}
@@ -3,7 +3,9 @@
 //! Core definitions, types, and traits that define the business domain of the application.
 //! Independent of specific frameworks (mostly), serving as the contract for other layers.
 
+pub mod checklist;
 pub mod config;
 pub mod paths;
+pub mod roadmap;
 pub mod traits;
 pub mod types;
@@ -8,6 +8,10 @@ pub const ROADMAP_FILE: &str = "roadmap.md";
 pub const ARCHITECTURE_FILE: &str = "architecture.md";
 pub const PROGRESS_FILE: &str = "progress.md";
 pub const GUIDELINES_FILE: &str = "guidelines.md";
+pub const HOOKS_FILE: &str = "hooks.yaml";
+pub const PROJECT_CONFIG_FILE: &str = "construct.yaml";
+pub const CHECKPOINT_FILE: &str = "checkpoint.json";
+pub const SNAPSHOTS_DIR: &str = "snapshots";
 
 /// Returns the relative path to the roadmap file (e.g. "tasks/specs/roadmap.md")
 pub fn roadmap_rel() -> String {
@@ -48,3 +52,15 @@ pub fn progress_path(root: &str) -> String {
 pub fn guidelines_path(root: &str) -> String {
     format!("{}/{}", root, guidelines_rel())
 }
+
+/// Folder-name form of a task description (e.g. "Add CLI flags" -> "add-cli-flags"):
+/// alphanumerics and dashes, at most 100 characters
+pub fn task_slug(task: &str) -> String {
+    let slug: String = task
+        .chars()
+        .filter(|c| c.is_alphanumeric() || *c == ' ')
+        .map(|c| if c == ' ' { '-' } else { c.to_ascii_lowercase() })
+        .take(100)
+        .collect();
+    slug.trim_matches('-').to_string()
+}
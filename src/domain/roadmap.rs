@@ -0,0 +1,490 @@
+//! # Roadmap
+//!
+//! Structured view of `tasks/specs/roadmap.md`: milestones with their items, nesting, status,
+//! dependencies and the task folder working on them.
+//!
+//! A milestone is a `##`/`###` heading with checkbox items below it, or, in a list without
+//! such headings, a top-level checkbox (with its nested checkboxes as items). Dependencies are
+//! written as a `depends:` line in the milestone body or inline in the title:
+//! `## Milestone 3: Testing (depends: 2)`. A reference is a milestone number ("Milestone 2"),
+//! a position in the roadmap, or the start of a title.
+
+use crate::domain::checklist::{ChecklistItem, ItemState, checkbox};
+
+/// Marker appended to headings of milestones skipped with `.roadmap skip`.
+const SKIPPED_MARKER: &str = "(skipped)";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MilestoneStatus {
+    Pending,
+    InProgress,
+    Done,
+    Skipped,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Milestone {
+    /// Title without emphasis and annotations
+    pub title: String,
+    /// Nesting level (0 for top-level milestones)
+    pub depth: usize,
+    /// First and last line of the milestone's block (1-based, inclusive)
+    pub line: usize,
+    pub end: usize,
+    /// State of the milestone's own checkbox (list-style milestones)
+    pub state: Option<ItemState>,
+    pub items: Vec<ChecklistItem>,
+    pub depends: Vec<String>,
+    skipped: bool,
+    heading: bool,
+}
+
+impl Milestone {
+    pub fn status(&self) -> MilestoneStatus {
+        let resolved = self.items.iter().filter(|i| i.is_resolved()).count();
+        let all_skipped =
+            !self.items.is_empty() && self.items.iter().all(|i| i.state == ItemState::Skipped);
+        if self.skipped || self.state == Some(ItemState::Skipped) || all_skipped {
+            MilestoneStatus::Skipped
+        } else if self.state == Some(ItemState::Done)
+            || (!self.items.is_empty() && resolved == self.items.len())
+        {
+            MilestoneStatus::Done
+        } else if resolved > 0 {
+            MilestoneStatus::InProgress
+        } else {
+            MilestoneStatus::Pending
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        matches!(self.status(), MilestoneStatus::Done | MilestoneStatus::Skipped)
+    }
+
+    /// Resolved and total items.
+    pub fn progress(&self) -> (usize, usize) {
+        let resolved = self.items.iter().filter(|i| i.is_resolved()).count();
+        (resolved, self.items.len())
+    }
+
+    /// Task description for the engine: the title and the open items.
+    pub fn task_description(&self) -> String {
+        let open: Vec<String> = self
+            .items
+            .iter()
+            .filter(|i| !i.is_resolved())
+            .map(|i| format!("- {}", i.text))
+            .collect();
+        if open.is_empty() {
+            self.title.clone()
+        } else {
+            format!("{}\n\n{}", self.title, open.join("\n"))
+        }
+    }
+
+    /// The most recent task folder (`NNN-slug`) created for this milestone.
+    pub fn linked_task<'a>(&self, folders: &'a [String]) -> Option<&'a str> {
+        let slug = crate::domain::paths::task_slug(&self.title);
+        if slug.is_empty() {
+            return None;
+        }
+        folders
+            .iter()
+            .filter(|f| f.split_once('-').is_some_and(|(_, rest)| rest.starts_with(&slug)))
+            .max()
+            .map(|f| f.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Roadmap {
+    pub milestones: Vec<Milestone>,
+}
+
+impl Roadmap {
+    pub fn parse(text: &str) -> Self {
+        let lines: Vec<&str> = text.lines().collect();
+        let mut milestones = Vec::new();
+
+        // Sections between headings (the part before the first heading has none)
+        let mut starts: Vec<(usize, Option<usize>)> = vec![(0, None)];
+        for (i, line) in lines.iter().enumerate() {
+            if let Some(level) = heading_level(line) {
+                starts.push((i, Some(level)));
+            }
+        }
+
+        for (s, &(start, level)) in starts.iter().enumerate() {
+            let end = starts.get(s + 1).map(|(next, _)| *next).unwrap_or(lines.len());
+            let body_start = if level.is_some() { start + 1 } else { start };
+            let boxes: Vec<(usize, usize, ItemState, String)> = (body_start..end)
+                .filter_map(|i| checkbox(lines[i]).map(|(indent, state, text)| (i, indent, state, text)))
+                .filter(|(_, _, _, text)| !text.is_empty())
+                .collect();
+            let top = boxes.iter().map(|(_, indent, _, _)| *indent).min().unwrap_or(0);
+            let has_groups = boxes.iter().enumerate().any(|(b, (_, indent, _, _))| {
+                *indent == top && boxes.get(b + 1).is_some_and(|next| next.1 > top)
+            });
+
+            match level {
+                // A heading with flat items is one milestone (the `#` document title is not)
+                Some(level) if level >= 2 && !has_groups => {
+                    let raw = lines[start].trim_start_matches('#').trim();
+                    if boxes.is_empty() && !raw.to_lowercase().contains("milestone") {
+                        continue;
+                    }
+                    let block_end = starts[s + 1..]
+                        .iter()
+                        .find(|(_, l)| l.is_some_and(|l| l <= level))
+                        .map(|(next, _)| *next)
+                        .unwrap_or(lines.len());
+                    let mut depends = inline_depends(raw);
+                    depends.extend((body_start..end).filter_map(|i| depends_line(lines[i])).flatten());
+                    milestones.push(Milestone {
+                        title: clean_title(raw),
+                        depth: level.saturating_sub(2),
+                        line: start + 1,
+                        end: trim_end(&lines, start, block_end),
+                        state: None,
+                        items: boxes
+                            .iter()
+                            .map(|(i, _, state, text)| ChecklistItem {
+                                text: text.clone(),
+                                state: *state,
+                                line: i + 1,
+                            })
+                            .collect(),
+                        depends,
+                        skipped: raw.contains(SKIPPED_MARKER),
+                        heading: true,
+                    });
+                }
+                // Otherwise every top-level checkbox is a milestone with its nested boxes as items
+                _ => {
+                    let depth = level.map(|l| l.saturating_sub(2)).unwrap_or(0);
+                    for (b, (i, indent, state, text)) in boxes.iter().enumerate() {
+                        if *indent != top {
+                            continue;
+                        }
+                        let children: Vec<&(usize, usize, ItemState, String)> =
+                            boxes[b + 1..].iter().take_while(|next| next.1 > top).collect();
+                        let block_end = (i + 1..end)
+                            .find(|&j| {
+                                let line = lines[j];
+                                !line.trim().is_empty() && line.len() - line.trim_start().len() <= top
+                            })
+                            .unwrap_or(end);
+                        milestones.push(Milestone {
+                            title: clean_title(text),
+                            depth,
+                            line: i + 1,
+                            end: trim_end(&lines, *i, block_end),
+                            state: Some(*state),
+                            items: children
+                                .iter()
+                                .map(|(j, _, state, text)| ChecklistItem {
+                                    text: text.clone(),
+                                    state: *state,
+                                    line: j + 1,
+                                })
+                                .collect(),
+                            depends: inline_depends(text),
+                            skipped: false,
+                            heading: false,
+                        });
+                    }
+                }
+            }
+        }
+        Self { milestones }
+    }
+
+    /// Whether milestone `index` only groups nested milestones.
+    pub fn is_container(&self, index: usize) -> bool {
+        let m = &self.milestones[index];
+        m.items.is_empty()
+            && self
+                .milestones
+                .get(index + 1)
+                .is_some_and(|next| next.depth > m.depth)
+    }
+
+    /// Index of the milestone a dependency reference points at.
+    pub fn resolve(&self, reference: &str) -> Option<usize> {
+        let reference = reference.trim().trim_start_matches('#');
+        if let Ok(n) = reference.parse::<usize>() {
+            let named = format!("milestone {}", n);
+            return self
+                .milestones
+                .iter()
+                .position(|m| {
+                    let title = m.title.to_lowercase();
+                    title == named
+                        || title.starts_with(&format!("{}:", named))
+                        || title.starts_with(&format!("{} ", named))
+                })
+                .or_else(|| (n >= 1 && n <= self.milestones.len()).then(|| n - 1));
+        }
+        let reference = reference.to_lowercase();
+        self.milestones
+            .iter()
+            .position(|m| m.title.to_lowercase().starts_with(&reference))
+            .or_else(|| {
+                self.milestones
+                    .iter()
+                    .position(|m| m.title.to_lowercase().contains(&reference))
+            })
+    }
+
+    /// Titles of the unfinished milestones that `index` waits for.
+    pub fn blocked_by(&self, index: usize) -> Vec<String> {
+        self.milestones[index]
+            .depends
+            .iter()
+            .filter_map(|r| self.resolve(r))
+            .filter(|&d| d != index && !self.milestones[d].is_finished())
+            .map(|d| self.milestones[d].title.clone())
+            .collect()
+    }
+
+    /// The first unfinished milestone whose dependencies are all finished.
+    pub fn next_ready(&self) -> Option<usize> {
+        (0..self.milestones.len()).find(|&i| {
+            !self.milestones[i].is_finished() && !self.is_container(i) && self.blocked_by(i).is_empty()
+        })
+    }
+
+    /// Whether any milestone is left to do.
+    pub fn has_unfinished(&self) -> bool {
+        (0..self.milestones.len()).any(|i| !self.milestones[i].is_finished() && !self.is_container(i))
+    }
+}
+
+/// Moves milestone `index` in front of the next milestone that would run, so it is picked up
+/// first. Both must be at the same level.
+pub fn prioritize(text: &str, index: usize) -> Result<String, String> {
+    let roadmap = Roadmap::parse(text);
+    let target = roadmap
+        .milestones
+        .get(index)
+        .ok_or_else(|| format!("There is no milestone {}.", index + 1))?;
+    if target.is_finished() {
+        return Err(format!("`{}` is already finished.", target.title));
+    }
+    // `.start` only picks ready milestones: moving a blocked one would change nothing
+    let waiting = roadmap.blocked_by(index);
+    if !waiting.is_empty() {
+        return Err(format!(
+            "`{}` depends on unfinished milestones: {}. Finish or skip those first.",
+            target.title,
+            waiting.join(", ")
+        ));
+    }
+    let Some(next) = (0..roadmap.milestones.len())
+        .find(|&i| !roadmap.milestones[i].is_finished() && !roadmap.is_container(i))
+    else {
+        return Err("Nothing is left to do.".to_string());
+    };
+    let first = &roadmap.milestones[next];
+    if next == index {
+        return Ok(text.to_string());
+    }
+    if next > index || first.depth != target.depth || first.heading != target.heading {
+        return Err(format!(
+            "`{}` cannot be moved in front of `{}`: they are at different levels.",
+            target.title, first.title
+        ));
+    }
+
+    let mut lines: Vec<&str> = text.lines().collect();
+    let mut start = target.line - 1;
+    let mut end = target.end;
+    let mut block: Vec<&str> = lines[start..end].to_vec();
+    if target.heading {
+        // Take the blank lines separating the section along with it
+        while end < lines.len() && lines[end].trim().is_empty() {
+            end += 1;
+        }
+        if end == lines.len() {
+            while start > 0 && lines[start - 1].trim().is_empty() {
+                start -= 1;
+            }
+        }
+        block.push("");
+    }
+    lines.drain(start..end);
+    let insert_at = first.line - 1;
+    lines.splice(insert_at..insert_at, block);
+    Ok(join(text, &lines))
+}
+
+/// Marks milestone `index` as skipped: its open items become `[-]`; a heading without items
+/// gets a `(skipped)` marker.
+pub fn skip(text: &str, index: usize) -> Result<String, String> {
+    let roadmap = Roadmap::parse(text);
+    let target = roadmap
+        .milestones
+        .get(index)
+        .ok_or_else(|| format!("There is no milestone {}.", index + 1))?;
+    if target.is_finished() {
+        return Err(format!("`{}` is already finished.", target.title));
+    }
+
+    let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+    let mut to_mark: Vec<usize> = target
+        .items
+        .iter()
+        .filter(|i| !i.is_resolved())
+        .map(|i| i.line)
+        .collect();
+    if !target.heading {
+        to_mark.push(target.line);
+    } else if target.items.is_empty() {
+        let line = &mut lines[target.line - 1];
+        line.push(' ');
+        line.push_str(SKIPPED_MARKER);
+    }
+    for n in to_mark {
+        let line = &mut lines[n - 1];
+        if let Some(pos) = line.find("- [ ]").or_else(|| line.find("* [ ]")) {
+            line.replace_range(pos + 3..pos + 4, "-");
+        }
+    }
+    let refs: Vec<&str> = lines.iter().map(|l| l.as_str()).collect();
+    Ok(join(text, &refs))
+}
+
+fn join(original: &str, lines: &[&str]) -> String {
+    let mut out = lines.join("\n");
+    if original.ends_with('\n') {
+        out.push('\n');
+    }
+    out
+}
+
+/// Last non-blank line of a block starting at `start` (0-based) and ending before `end`, 1-based.
+fn trim_end(lines: &[&str], start: usize, end: usize) -> usize {
+    (start..end)
+        .rev()
+        .find(|&i| !lines[i].trim().is_empty())
+        .unwrap_or(start)
+        + 1
+}
+
+fn heading_level(line: &str) -> Option<usize> {
+    let hashes = line.chars().take_while(|c| *c == '#').count();
+    (hashes > 0 && line[hashes..].starts_with(' ')).then_some(hashes)
+}
+
+/// References from a `depends: a, b` body line (also `- **Depends**: a`).
+fn depends_line(line: &str) -> Option<Vec<String>> {
+    let cleaned = line.trim().trim_start_matches(['-', '*']).replace("**", "");
+    let (key, value) = cleaned.split_once(':')?;
+    let key = key.trim().to_lowercase();
+    if key != "depends" && key != "depends on" {
+        return None;
+    }
+    Some(split_refs(value))
+}
+
+/// References from an inline `(depends: a, b)` annotation.
+fn inline_depends(title: &str) -> Vec<String> {
+    let lower = title.to_lowercase();
+    let Some(start) = lower.find("(depends") else {
+        return Vec::new();
+    };
+    let rest = &title[start..];
+    let Some(close) = rest.find(')') else {
+        return Vec::new();
+    };
+    match rest[..close].split_once(':') {
+        Some((_, value)) => split_refs(value),
+        None => Vec::new(),
+    }
+}
+
+fn split_refs(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|r| r.trim().trim_matches('`').to_string())
+        .filter(|r| !r.is_empty())
+        .collect()
+}
+
+fn clean_title(raw: &str) -> String {
+    let mut title = raw.replace("**", "");
+    if let Some(start) = title.to_lowercase().find("(depends")
+        && let Some(len) = title[start..].find(')')
+    {
+        title.replace_range(start..start + len + 1, "");
+    }
+    title.replace(SKIPPED_MARKER, "").trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROADMAP: &str = "# Project Roadmap\n\n> Rule\n\n## Milestone 1: Initialization\n- **Goals**: Setup.\n- [x] Initialize Cargo project\n- [x] Add dependencies\n\n## Milestone 2: MVP\n- [x] Feature A\n- [ ] Feature B\n\n## Milestone 3: Testing (depends: 4)\n- [ ] Unit Tests\n\n## Milestone 4: Documentation\n- **Depends**: Milestone 2\n- [ ] README.md\n";
+
+    #[test]
+    fn test_parse_status_and_dependencies() {
+        let roadmap = Roadmap::parse(ROADMAP);
+        let titles: Vec<&str> = roadmap.milestones.iter().map(|m| m.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            vec!["Milestone 1: Initialization", "Milestone 2: MVP", "Milestone 3: Testing", "Milestone 4: Documentation"]
+        );
+        assert_eq!(roadmap.milestones[0].status(), MilestoneStatus::Done);
+        assert_eq!(roadmap.milestones[1].status(), MilestoneStatus::InProgress);
+        assert_eq!(roadmap.milestones[1].progress(), (1, 2));
+        assert_eq!(roadmap.milestones[2].depends, vec!["4"]);
+        assert_eq!(roadmap.milestones[3].depends, vec!["Milestone 2"]);
+        assert_eq!(roadmap.blocked_by(2), vec!["Milestone 4: Documentation"]);
+        assert_eq!(roadmap.next_ready(), Some(1));
+        assert_eq!(roadmap.milestones[1].task_description(), "Milestone 2: MVP\n\n- Feature B");
+
+        // Finishing milestone 2 unblocks 4, which must run before 3
+        let done = ROADMAP.replace("- [ ] Feature B", "- [x] Feature B");
+        assert_eq!(Roadmap::parse(&done).next_ready(), Some(3));
+    }
+
+    #[test]
+    fn test_list_style_roadmap_and_links() {
+        let text = "# Roadmap\n- [x] Setup\n- [ ] **Parser** (depends: Setup)\n  - [ ] Lexer\n  - [ ] Grammar\n- [ ] Docs\n";
+        let roadmap = Roadmap::parse(text);
+        assert_eq!(roadmap.milestones.len(), 3);
+        assert_eq!(roadmap.milestones[1].title, "Parser");
+        assert_eq!(roadmap.milestones[1].items.len(), 2);
+        assert_eq!((roadmap.milestones[1].line, roadmap.milestones[1].end), (3, 5));
+        assert_eq!(roadmap.next_ready(), Some(1));
+
+        let folders = vec!["001-setup".to_string(), "002-parser-lexer".to_string(), "003-parser".to_string()];
+        assert_eq!(roadmap.milestones[1].linked_task(&folders), Some("003-parser"));
+        assert_eq!(roadmap.milestones[2].linked_task(&folders), None);
+    }
+
+    #[test]
+    fn test_prioritize_and_skip() {
+        let blocked = prioritize(ROADMAP, 3).unwrap_err();
+        assert!(blocked.contains("Milestone 2: MVP"));
+        assert!(prioritize(ROADMAP, 2).unwrap_err().contains("Milestone 4: Documentation"));
+
+        let moved = prioritize(&ROADMAP.replace("- **Depends**: Milestone 2\n", ""), 3).unwrap();
+        let roadmap = Roadmap::parse(&moved);
+        assert_eq!(roadmap.milestones[1].title, "Milestone 4: Documentation");
+        assert_eq!(roadmap.milestones[2].title, "Milestone 2: MVP");
+        assert!(moved.ends_with("- [ ] Unit Tests\n"));
+        assert!(prioritize(ROADMAP, 0).is_err());
+
+        let skipped = skip(ROADMAP, 2).unwrap();
+        assert!(skipped.contains("- [-] Unit Tests"));
+        assert_eq!(Roadmap::parse(&skipped).milestones[2].status(), MilestoneStatus::Skipped);
+
+        let list = "- [ ] A\n  - [x] a1\n  - [ ] a2\n- [ ] B\n";
+        let skipped = skip(list, 0).unwrap();
+        assert_eq!(skipped, "- [-] A\n  - [x] a1\n  - [-] a2\n- [ ] B\n");
+        let moved = prioritize(list, 1).unwrap();
+        assert_eq!(moved, "- [ ] B\n- [ ] A\n  - [x] a1\n  - [ ] a2\n");
+    }
+}
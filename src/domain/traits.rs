@@ -32,4 +32,15 @@ pub trait ChatProvider: Send + Sync {
 pub trait LlmProvider: Send + Sync {
     /// Generate a completion
     async fn completion(&self, prompt: &str, model: &str) -> Result<String, String>;
+
+    /// Generate a completion with the agent's provider but a specific model
+    async fn completion_with_model(
+        &self,
+        prompt: &str,
+        agent_name: &str,
+        model: &str,
+    ) -> Result<String, String> {
+        let _ = model;
+        self.completion(prompt, agent_name).await
+    }
 }
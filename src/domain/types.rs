@@ -4,13 +4,43 @@
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Hash)]
 pub enum AgentAction {
     ShellCommand(String),
     WriteFile(String, String), // path, content
-    ReadFile(String),          // path
+    EditFile(String, String),  // path, patch (SEARCH/REPLACE blocks or unified diff)
+    ReadFile(String, Option<LineRange>), // path, lines to show
     ListDir(String),           // path
     Find(String, String),      // path, pattern
+    Search(SearchQuery),       // content search
+    Outline(String),           // path (file or directory)
+    FindSymbol(String),        // name, `Type::name` or `Class.name`
     SwitchMode(String),        // phase (planning, execution)
+    Delegate(String, Vec<String>, usize), // goal, file scope, step budget
+    AskUser(String, Vec<String>),         // question, options (may be empty)
     Done,
 }
+
+/// Content search request (```search``` blocks).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Hash, Default)]
+pub struct SearchQuery {
+    pub pattern: String,
+    /// Directory or file to search, relative to the project
+    pub path: String,
+    /// Only search files whose name or relative path matches this glob
+    pub glob: Option<String>,
+    /// Treat the pattern as plain text instead of a regex
+    pub literal: bool,
+    pub ignore_case: bool,
+    /// Lines of context around each hit
+    pub context: usize,
+}
+
+/// Lines requested by a ```read``` block (1-based, inclusive).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Hash)]
+pub enum LineRange {
+    /// From the first line to the second (or to the end of the file)
+    Span(usize, Option<usize>),
+    /// A window centred on this line
+    Around(usize),
+}
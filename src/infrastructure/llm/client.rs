@@ -160,6 +160,18 @@ impl LlmProvider for Client {
             .map(|r| r.content)
             .map_err(|e| e.message)
     }
+
+    async fn completion_with_model(
+        &self,
+        prompt: &str,
+        agent_name: &str,
+        model: &str,
+    ) -> Result<String, String> {
+        self.prompt_with_model(agent_name, model, prompt)
+            .await
+            .map(|r| r.content)
+            .map_err(|e| e.message)
+    }
 }
 
 #[cfg(test)]
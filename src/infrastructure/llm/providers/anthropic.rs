@@ -242,8 +242,8 @@ pub async fn chat(config: ProviderConfig, context: Context) -> Result<Response,
         // Exponential backoff
         tokio::time::sleep(std::time::Duration::from_millis(500 * (attempt + 1) as u64)).await;
     }
-    
-    return Err(last_error);
+
+    Err(last_error)
     
 } // End of chat function wrapper? No, this replaces the request_builder block.
 
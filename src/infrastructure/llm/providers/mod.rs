@@ -138,7 +138,7 @@ pub async fn list_models(provider: Provider, config: ProviderConfig) -> Result<V
             // If Zai uses /v4/models, we need to check docs.
             // Assuming OpenAI compat for models endpoint too.
             let config_with_url = ProviderConfig {
-                base_url: Some(format!("{}", base_url)), // Base URL usually includes version?
+                base_url: Some(base_url.to_string()), // Base URL usually includes version?
                 // The chat path was /v4/responses.
                 // Let's assume /v4/models is at base_url/v4/models if we strip responses?
                 // Actually openai::list_models appends /models.
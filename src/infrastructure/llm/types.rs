@@ -51,22 +51,14 @@ impl Message {
 }
 
 /// Cache configuration for native provider caching
-#[derive(Debug, Clone)]
+#[derive(Debug, Default, Clone)]
 pub struct CacheConfig {
     /// Maximum age for cached content (for Gemini)
     pub max_age_seconds: Option<u32>,
 }
 
-impl Default for CacheConfig {
-    fn default() -> Self {
-        Self {
-            max_age_seconds: None,
-        }
-    }
-}
-
 /// Context for an LLM request
-#[derive(Debug, Clone)]
+#[derive(Debug, Default, Clone)]
 pub struct Context {
     pub messages: Vec<Message>,
     pub model: Option<String>,
@@ -75,18 +67,6 @@ pub struct Context {
     pub cache: Option<CacheConfig>,
 }
 
-impl Default for Context {
-    fn default() -> Self {
-        Self {
-            messages: Vec::new(),
-            model: None,
-            temperature: None,
-            max_tokens: None,
-            cache: None,
-        }
-    }
-}
-
 impl Context {
     pub fn new() -> Self {
         Self::default()
@@ -165,6 +145,7 @@ pub enum Provider {
     Anthropic,
     Gemini,
     Groq,
+    #[allow(clippy::upper_case_acronyms)]
     XAI,
     DeepAI,
     Zai,
@@ -0,0 +1,158 @@
+//! # Diff
+//!
+//! Line-based unified diffs, used to show what a dry run would have changed.
+
+/// Lines of unchanged context around each hunk.
+const CONTEXT: usize = 3;
+/// Above this many line pairs the LCS table gets too big; the file is shown as fully replaced.
+const MAX_CELLS: usize = 4_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Keep,
+    Add,
+    Remove,
+}
+
+/// Counts of added and removed lines.
+pub fn line_stats(old: &str, new: &str) -> (usize, usize) {
+    let ops = diff_ops(&lines(old), &lines(new));
+    let added = ops.iter().filter(|(op, _, _)| *op == Op::Add).count();
+    let removed = ops.iter().filter(|(op, _, _)| *op == Op::Remove).count();
+    (added, removed)
+}
+
+/// Unified diff between two versions of a file (`old` is empty for new files).
+/// Returns an empty string when nothing changed.
+pub fn unified_diff(path: &str, old: &str, new: &str) -> String {
+    let a = lines(old);
+    let b = lines(new);
+    let ops = diff_ops(&a, &b);
+    if ops.iter().all(|(op, _, _)| *op == Op::Keep) {
+        return String::new();
+    }
+
+    let mut out = if old.is_empty() {
+        format!("--- /dev/null\n+++ b/{}\n", path)
+    } else {
+        format!("--- a/{}\n+++ b/{}\n", path, path)
+    };
+
+    // Group changes into hunks with CONTEXT lines around them
+    let changed: Vec<usize> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, (op, _, _))| *op != Op::Keep)
+        .map(|(i, _)| i)
+        .collect();
+
+    let mut i = 0;
+    while i < changed.len() {
+        let start = changed[i].saturating_sub(CONTEXT);
+        let mut end = changed[i];
+        while i < changed.len() && changed[i] <= end + 2 * CONTEXT {
+            end = changed[i];
+            i += 1;
+        }
+        let end = (end + CONTEXT + 1).min(ops.len());
+        let hunk = &ops[start..end];
+
+        let old_start = hunk.iter().find_map(|(op, ai, _)| (*op != Op::Add).then_some(*ai));
+        let new_start = hunk.iter().find_map(|(op, _, bi)| (*op != Op::Remove).then_some(*bi));
+        let old_len = hunk.iter().filter(|(op, _, _)| *op != Op::Add).count();
+        let new_len = hunk.iter().filter(|(op, _, _)| *op != Op::Remove).count();
+        out.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            old_start.map(|n| n + 1).unwrap_or(0),
+            old_len,
+            new_start.map(|n| n + 1).unwrap_or(0),
+            new_len
+        ));
+        for (op, ai, bi) in hunk {
+            match op {
+                Op::Keep => out.push_str(&format!(" {}\n", a[*ai])),
+                Op::Remove => out.push_str(&format!("-{}\n", a[*ai])),
+                Op::Add => out.push_str(&format!("+{}\n", b[*bi])),
+            }
+        }
+    }
+    out
+}
+
+fn lines(text: &str) -> Vec<&str> {
+    text.lines().collect()
+}
+
+/// Edit script as (op, index in old, index in new).
+fn diff_ops(a: &[&str], b: &[&str]) -> Vec<(Op, usize, usize)> {
+    // Trim the common prefix and suffix so the table only covers the changed middle
+    let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let (am, bm) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+
+    let mut ops: Vec<(Op, usize, usize)> = (0..prefix).map(|i| (Op::Keep, i, i)).collect();
+
+    if am.len() * bm.len() > MAX_CELLS {
+        ops.extend((0..am.len()).map(|i| (Op::Remove, prefix + i, prefix)));
+        ops.extend((0..bm.len()).map(|j| (Op::Add, prefix + am.len(), prefix + j)));
+    } else {
+        // LCS table over the middle, then walk it forwards
+        let (n, m) = (am.len(), bm.len());
+        let mut table = vec![vec![0usize; m + 1]; n + 1];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                table[i][j] = if am[i] == bm[j] {
+                    table[i + 1][j + 1] + 1
+                } else {
+                    table[i + 1][j].max(table[i][j + 1])
+                };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        while i < n || j < m {
+            if i < n && j < m && am[i] == bm[j] {
+                ops.push((Op::Keep, prefix + i, prefix + j));
+                i += 1;
+                j += 1;
+            } else if i < n && (j == m || table[i + 1][j] >= table[i][j + 1]) {
+                // Removals first, like `diff -u`
+                ops.push((Op::Remove, prefix + i, prefix + j));
+                i += 1;
+            } else {
+                ops.push((Op::Add, prefix + i, prefix + j));
+                j += 1;
+            }
+        }
+    }
+
+    let (a_tail, b_tail) = (a.len() - suffix, b.len() - suffix);
+    ops.extend((0..suffix).map(|k| (Op::Keep, a_tail + k, b_tail + k)));
+    ops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unified_diff_hunk() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\n";
+        let new = "a\nb\nc\nD\ne\nf\ng\nh\n";
+        let diff = unified_diff("x.txt", old, new);
+        assert!(diff.starts_with("--- a/x.txt\n+++ b/x.txt\n@@ -1,7 +1,7 @@\n"));
+        assert!(diff.contains("-d\n+D\n"));
+        assert_eq!(line_stats(old, new), (1, 1));
+    }
+
+    #[test]
+    fn test_new_file_and_no_change() {
+        let diff = unified_diff("new.rs", "", "fn main() {}\n");
+        assert!(diff.starts_with("--- /dev/null\n+++ b/new.rs\n@@ -0,0 +1,1 @@\n+fn main() {}"));
+        assert!(unified_diff("same", "x\n", "x\n").is_empty());
+    }
+}
@@ -5,7 +5,12 @@
 //! Handles safe execution of shell commands and filesystem operations.
 //! Enforces sandboxing by validating paths against valid root directories.
 
+use super::patch::{PatchOutcome, apply_patch};
+use super::resolver::PathResolver;
+use super::snapshot::SnapshotStore;
+use crate::domain::paths;
 use anyhow::{Context as AnyhowContext, Result};
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use std::sync::Arc;
@@ -23,10 +28,160 @@ pub struct ToolConfig {
     pub long_commands: Vec<String>,
 }
 
+/// Command and path rules of one project, from its `tasks/specs/construct.yaml`.
+#[derive(Debug, Default, Clone)]
+pub struct ProjectPolicy {
+    pub blocked: Vec<String>,
+    pub long_commands: Vec<String>,
+    pub timeout_default: Option<u64>,
+    pub timeout_long: Option<u64>,
+    pub env: BTreeMap<String, String>,
+    /// Globs relative to the project root that may not be written
+    pub protected_paths: Vec<String>,
+}
+
+impl ProjectPolicy {
+    pub fn from_config(config: &crate::domain::config::ProjectConfig) -> Self {
+        Self {
+            blocked: config.commands.blocked.clone(),
+            long_commands: config.commands.long_commands.clone(),
+            timeout_default: config.commands.timeout,
+            timeout_long: config.commands.long_timeout,
+            env: config.env.clone(),
+            protected_paths: config.protected_paths.clone(),
+        }
+    }
+
+    /// The protected-path glob matching `rel` (the full relative path or its file name).
+    pub fn protects(&self, rel: &str) -> Option<&str> {
+        self.protected_paths
+            .iter()
+            .find(|pattern| match glob::Pattern::new(pattern) {
+                Ok(p) => p.matches(rel) || rel.rsplit('/').next().is_some_and(|name| p.matches(name)),
+                Err(_) => pattern.as_str() == rel,
+            })
+            .map(|p| p.as_str())
+    }
+}
+
+/// Whether `command` is `rule` or starts with it as whole words (`cargo publish` matches
+/// `cargo publish --dry-run` but not `cargo publisher`).
+pub fn command_matches(command: &str, rule: &str) -> bool {
+    let command = command.trim();
+    let rule = rule.trim();
+    !rule.is_empty() && (command == rule || command.starts_with(&format!("{} ", rule)))
+}
+
+/// Whether `path` is a project's `construct.yaml`, `hooks.yaml` or one of its prompt
+/// overrides. These are always protected, whatever `protected_paths` says.
+fn is_agent_config(path: &Path) -> bool {
+    let prompts = Path::new(crate::strings::prompts::PROJECT_PROMPTS_DIR);
+    path.ancestors().skip(1).any(|dir| {
+        let config = [paths::PROJECT_CONFIG_FILE, paths::HOOKS_FILE].iter().any(|f| path == dir.join(f));
+        dir.ends_with(paths::SPECS_DIR) && config || dir.ends_with(prompts)
+    })
+}
+
+/// Whether `command` chains, pipes or substitutes other commands, so a prefix match
+/// says nothing about what actually runs.
+pub fn is_compound(command: &str) -> bool {
+    [";", "&", "|", "$(", "`", "\n", "\r"].iter().any(|s| command.contains(s))
+}
+
+/// Whether `command` may skip approval under one of the `allowed` rules.
+/// Compound commands never do.
+pub fn command_allowed(command: &str, rules: &[String]) -> bool {
+    !is_compound(command) && rules.iter().any(|rule| command_matches(command, rule))
+}
+
+/// The `blocked` rule matching any segment of `command`, if one does. Segments are split on
+/// `;`, `&&`, `||`, `|`, `&`, newlines and substitutions; leading `env`, variable assignments
+/// and wrappers like `sudo` or `sh -c` are skipped, so `cd . && env git push` hits `git push`.
+pub fn command_blocked<'a>(command: &str, rules: &'a [String]) -> Option<&'a str> {
+    command
+        .split([';', '&', '|', '\n', '\r', '`', '(', ')'])
+        .map(|segment| segment.trim().trim_start_matches('$').trim())
+        .filter(|segment| !segment.is_empty())
+        .find_map(|segment| {
+            let mut words = segment.split_whitespace().peekable();
+            let mut wrapped = false;
+            while let Some(word) = words.peek() {
+                let assignment = word.split_once('=').is_some_and(|(name, _)| {
+                    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+                });
+                let wrapper = matches!(*word, "env" | "sudo" | "command" | "exec" | "nohup" | "time" | "nice");
+                if !(assignment || wrapper || wrapped && word.starts_with('-')) {
+                    break;
+                }
+                wrapped |= wrapper;
+                words.next();
+            }
+            let rest = words.collect::<Vec<_>>().join(" ");
+            // `sh -c "git push"` runs its quoted argument
+            if let Some(script) = ["sh -c ", "bash -c ", "zsh -c "].iter().find_map(|p| rest.strip_prefix(p)) {
+                let script = script.trim().trim_matches(['"', '\'']);
+                if let Some(rule) = command_blocked(script, rules) {
+                    return Some(rule);
+                }
+            }
+            let rest = rest.trim_matches(['"', '\'']);
+            rules.iter().find(|rule| command_matches(rest, rule)).map(|r| r.as_str())
+        })
+}
+
+/// In-memory record of what a dry run would have done under one project root.
+#[derive(Debug, Default, Clone)]
+pub struct DryRunOverlay {
+    /// path -> (original content on disk, if any; content the agent wrote)
+    pub files: BTreeMap<PathBuf, (Option<String>, String)>,
+    /// Shell commands that were logged instead of executed
+    pub commands: Vec<String>,
+}
+
+impl DryRunOverlay {
+    /// Unified diff of every file the run would have changed, paths relative to `root`.
+    pub fn diff(&self, root: &Path) -> String {
+        let mut out = String::new();
+        for (path, (original, content)) in &self.files {
+            let rel = path.strip_prefix(root).unwrap_or(path).to_string_lossy();
+            out.push_str(&super::diff::unified_diff(
+                &rel,
+                original.as_deref().unwrap_or(""),
+                content,
+            ));
+        }
+        out
+    }
+
+    /// (files changed, lines added, lines removed)
+    pub fn stats(&self) -> (usize, usize, usize) {
+        let mut changed = 0;
+        let (mut added, mut removed) = (0, 0);
+        for (original, content) in self.files.values() {
+            let original = original.as_deref().unwrap_or("");
+            if original != content {
+                changed += 1;
+                let (a, r) = super::diff::line_stats(original, content);
+                added += a;
+                removed += r;
+            }
+        }
+        (changed, added, removed)
+    }
+}
+
 /// Executes tools (shell, fs) securely.
 #[derive(Debug)]
 pub struct ToolExecutor {
     config: ToolConfig,
+    /// Dry-run overlays keyed by project root. Writes and commands under a root with an
+    /// overlay are recorded instead of performed.
+    overlays: std::sync::Mutex<HashMap<PathBuf, DryRunOverlay>>,
+    /// Undo history keyed by project root: (store, current step). Writes under a root with a
+    /// store snapshot the previous content first.
+    snapshots: std::sync::Mutex<HashMap<PathBuf, (SnapshotStore, usize)>>,
+    /// Project rules keyed by project root, applied to commands and writes under it
+    policies: std::sync::Mutex<HashMap<PathBuf, ProjectPolicy>>,
 }
 
 impl ToolExecutor {
@@ -43,7 +198,148 @@ impl ToolExecutor {
                 timeout_long,
                 long_commands,
             },
+            overlays: std::sync::Mutex::new(HashMap::new()),
+            snapshots: std::sync::Mutex::new(HashMap::new()),
+            policies: std::sync::Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Sets (or with `None` removes) the rules for commands and writes under `root`.
+    pub fn set_project_policy(&self, root: &Path, policy: Option<ProjectPolicy>) {
+        let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        let mut policies = self.policies.lock().unwrap();
+        match policy {
+            Some(policy) => {
+                policies.insert(root, policy);
+            }
+            None => {
+                policies.remove(&root);
+            }
+        }
+    }
+
+    /// The project root and rules covering `path`, if any.
+    fn policy_for(&self, path: &Path) -> Option<(PathBuf, ProjectPolicy)> {
+        let policies = self.policies.lock().unwrap();
+        policies
+            .iter()
+            .filter(|(root, _)| path.starts_with(root))
+            .max_by_key(|(root, _)| root.as_os_str().len())
+            .map(|(root, policy)| (root.clone(), policy.clone()))
+    }
+
+    /// Fails if `path` configures the agent itself or the project's config protects it.
+    fn check_protected(&self, path: &Path) -> Result<()> {
+        if is_agent_config(path) {
+            return Err(anyhow::anyhow!(
+                "`{}` configures the agent and cannot be changed by it",
+                path.display()
+            ));
+        }
+        let Some((root, policy)) = self.policy_for(path) else {
+            return Ok(());
+        };
+        let rel = path.strip_prefix(&root).unwrap_or(path).to_string_lossy().to_string();
+        match policy.protects(&rel) {
+            Some(pattern) => Err(anyhow::anyhow!(
+                "`{}` is protected by the project config (`{}`) and cannot be changed",
+                rel,
+                pattern
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Starts recording writes and commands under `root` instead of performing them.
+    /// Keeps an existing overlay, so consecutive runs see each other's simulated changes.
+    pub fn begin_dry_run(&self, root: &Path) {
+        let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        let mut overlays = self.overlays.lock().unwrap();
+        overlays.entry(root).or_default();
+    }
+
+    /// Stops the dry run under `root` and returns what it recorded.
+    pub fn end_dry_run(&self, root: &Path) -> Option<DryRunOverlay> {
+        let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        self.overlays.lock().unwrap().remove(&root)
+    }
+
+    /// Whether `path` lies under a project with an active dry run.
+    pub fn in_dry_run(&self, path: &Path) -> bool {
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        self.overlays.lock().unwrap().keys().any(|root| path.starts_with(root))
+    }
+
+    /// Snapshot of the overlay for `root`, if a dry run is active there.
+    pub fn dry_run_overlay(&self, root: &Path) -> Option<DryRunOverlay> {
+        let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        self.overlays.lock().unwrap().get(&root).cloned()
+    }
+
+    /// Runs `f` on the overlay covering `path`, if any.
+    fn with_overlay<T>(&self, path: &Path, f: impl FnOnce(&mut DryRunOverlay) -> T) -> Option<T> {
+        let mut overlays = self.overlays.lock().unwrap();
+        overlays
+            .iter_mut()
+            .find(|(root, _)| path.starts_with(root))
+            .map(|(_, overlay)| f(overlay))
+    }
+
+    /// Starts snapshotting writes under the store's project root. Step numbers continue
+    /// after the last step already in the store.
+    pub fn begin_snapshots(&self, store: &SnapshotStore) {
+        let root = store.root().canonicalize().unwrap_or_else(|_| store.root().to_path_buf());
+        let step = store.last_step().unwrap_or(0);
+        let store = SnapshotStore::new(&root, store.dir());
+        self.snapshots.lock().unwrap().insert(root, (store, step));
+    }
+
+    /// Moves the snapshots under `root` on to the next step and returns its number.
+    pub fn next_snapshot_step(&self, root: &Path) -> Option<usize> {
+        let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        let mut snapshots = self.snapshots.lock().unwrap();
+        let (_, step) = snapshots.get_mut(&root)?;
+        *step += 1;
+        Some(*step)
+    }
+
+    pub fn end_snapshots(&self, root: &Path) {
+        let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        self.snapshots.lock().unwrap().remove(&root);
+    }
+
+    /// Restores the project to the end of `step` from its snapshots and drops the later entries.
+    /// Paths go through the same resolver and sandbox checks as agent writes.
+    /// Returns the restored (project-relative) paths.
+    pub async fn rewind(&self, store: &SnapshotStore, step: usize) -> Result<Vec<String>> {
+        let resolver = PathResolver::new(store.root());
+        let mut restored = Vec::new();
+        for (rel, content) in store.rewind_plan(step)? {
+            let resolved = resolver
+                .resolve(&rel)
+                .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+            let safe_path = self.validate_path(&resolved)?;
+            match content {
+                Some(bytes) => {
+                    if let Some(parent) = safe_path.parent() {
+                        tokio::fs::create_dir_all(parent).await?;
+                    }
+                    tokio::fs::write(&safe_path, bytes)
+                        .await
+                        .with_context(|| format!("Failed to restore {}", rel))?;
+                }
+                None => {
+                    if safe_path.exists() {
+                        tokio::fs::remove_file(&safe_path)
+                            .await
+                            .with_context(|| format!("Failed to remove {}", rel))?;
+                    }
+                }
+            }
+            restored.push(rel);
         }
+        store.truncate(step)?;
+        Ok(restored)
     }
 
     /// Validates that a path is safe to access (contained within allowed roots).
@@ -115,6 +411,23 @@ impl ToolExecutor {
             .validate_path(cwd)
             .context("Invalid CWD for command execution")?;
 
+        let policy = self.policy_for(&safe_cwd).map(|(_, p)| p).unwrap_or_default();
+        if let Some(rule) = command_blocked(command, &policy.blocked) {
+            return Err(anyhow::anyhow!(
+                "Command blocked by the project config (`{}`)",
+                rule
+            ));
+        }
+
+        // Dry run: log the command, do not execute it
+        if self
+            .with_overlay(&safe_cwd, |o| o.commands.push(command.to_string()))
+            .is_some()
+        {
+            info!("Dry run: skipped command {}", command);
+            return Ok(format!("[dry run] Command not executed: {}", command));
+        }
+
         // 2. Construct Command
         let mut cmd = if cfg!(target_os = "windows") {
             let mut c = tokio::process::Command::new("cmd");
@@ -127,6 +440,7 @@ impl ToolExecutor {
         };
 
         cmd.current_dir(safe_cwd);
+        cmd.envs(&policy.env);
         cmd.stdout(Stdio::piped());
         cmd.stderr(Stdio::piped());
         cmd.kill_on_drop(true); // Ensure process is killed if timed out (dropped)
@@ -137,10 +451,16 @@ impl ToolExecutor {
 
         // 3. Determine Timeout
         let binary = command.split_whitespace().next().unwrap_or("");
-        let timeout_sec = if self.config.long_commands.iter().any(|c| c == binary) {
-            self.config.timeout_long
+        let timeout_sec = if self
+            .config
+            .long_commands
+            .iter()
+            .chain(policy.long_commands.iter())
+            .any(|c| c == binary)
+        {
+            policy.timeout_long.unwrap_or(self.config.timeout_long)
         } else {
-            self.config.timeout_default
+            policy.timeout_default.unwrap_or(self.config.timeout_default)
         };
 
         // 4. Configure & Spawn
@@ -183,7 +503,7 @@ impl ToolExecutor {
 
         if !output.status.success() {
             if !result.is_empty() {
-                result.push_str("\n");
+                result.push('\n');
             }
             result.push_str(&format!("[Exit Code: {}]", output.status));
         }
@@ -197,33 +517,108 @@ impl ToolExecutor {
 
         info!("Reading file: {:?}", safe_path);
 
+        // Dry run: simulated writes shadow the disk
+        if let Some(Some(content)) = self.with_overlay(&safe_path, |o| {
+            o.files.get(&safe_path).map(|(_, content)| content.clone())
+        }) {
+            return Ok(content);
+        }
+
         tokio::fs::read_to_string(&safe_path)
             .await
             .map_err(|e| anyhow::anyhow!("Failed to read file '{:?}': {}", safe_path, e))
     }
 
+    /// Reads a file for the agent: numbered line ranges, a first page for large files,
+    /// and a refusal (with size and type) for binary files. `display` names the file in headers.
+    pub async fn read_range(
+        &self,
+        path: &str,
+        display: &str,
+        range: Option<&crate::domain::types::LineRange>,
+    ) -> Result<String> {
+        let safe_path = self.validate_path(Path::new(path))?;
+        info!("Reading file: {:?} ({:?})", safe_path, range);
+
+        let simulated = self.with_overlay(&safe_path, |o| {
+            o.files.get(&safe_path).map(|(_, content)| content.clone())
+        });
+        let content = match simulated.flatten() {
+            Some(content) => content,
+            None => {
+                let bytes = tokio::fs::read(&safe_path)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("Failed to read file '{:?}': {}", safe_path, e))?;
+                if let Some(kind) = super::reader::binary_kind(&bytes) {
+                    return Err(anyhow::anyhow!(
+                        "`{}` is a binary file ({}, {}) and cannot be shown as text.",
+                        display,
+                        kind,
+                        super::reader::format_size(bytes.len())
+                    ));
+                }
+                String::from_utf8(bytes)?
+            }
+        };
+        Ok(super::reader::render(display, &content, range))
+    }
+
     pub async fn write_file(&self, path: &str, content: &str) -> Result<()> {
         let path = Path::new(path);
         // Note: For write, validation logic in `validate_path` handles parent existence check
         let safe_path = self.validate_path(path)?;
+        self.check_protected(&safe_path)?;
+
+        // Dry run: record the write, remembering what was on disk before the first one
+        if self
+            .with_overlay(&safe_path, |o| {
+                let entry = o
+                    .files
+                    .entry(safe_path.clone())
+                    .or_insert_with(|| (std::fs::read_to_string(&safe_path).ok(), String::new()));
+                entry.1 = content.to_string();
+            })
+            .is_some()
+        {
+            info!("Dry run: recorded write to {:?}", safe_path);
+            return Ok(());
+        }
 
-        // Helper: ensure parent dir exists if safe_path was resolved via parent
-        if let Some(parent) = safe_path.parent() {
-            if !parent.exists() {
-                tokio::fs::create_dir_all(parent).await?;
+        // Undo history: remember what the file looked like before this step touched it
+        {
+            let snapshots = self.snapshots.lock().unwrap();
+            if let Some((store, step)) = snapshots.values().find(|(s, _)| safe_path.starts_with(s.root()))
+                && let Err(e) = store.record(*step, &safe_path)
+            {
+                tracing::warn!("Failed to snapshot {:?}: {}", safe_path, e);
             }
         }
 
+        // Helper: ensure parent dir exists if safe_path was resolved via parent
+        if let Some(parent) = safe_path.parent()
+            && !parent.exists()
+        {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
         tokio::fs::write(safe_path, content)
             .await
             .context("Failed to write file")
     }
 
+    /// Applies a partial edit (SEARCH/REPLACE blocks or unified diff hunks) to an existing file.
+    pub async fn edit_file(&self, path: &str, patch: &str) -> Result<PatchOutcome> {
+        let original = self.read_file(path).await?;
+        let outcome = apply_patch(&original, patch).map_err(|e| anyhow::anyhow!(e))?;
+        self.write_file(path, &outcome.content).await?;
+        Ok(outcome)
+    }
+
     pub async fn list_dir(&self, path: &str) -> Result<String> {
         let path = Path::new(path);
         let safe_path = self.validate_path(path)?;
 
-        let mut entries = tokio::fs::read_dir(safe_path)
+        let mut entries = tokio::fs::read_dir(&safe_path)
             .await
             .context("Failed to read dir")?;
         let mut listing = String::new();
@@ -237,9 +632,75 @@ impl ToolExecutor {
             };
             listing.push_str(&format!("{} [{}]\n", name, file_type));
         }
+
+        // Dry run: files that only exist in the overlay
+        let simulated: Vec<String> = self
+            .with_overlay(&safe_path, |o| {
+                o.files
+                    .iter()
+                    .filter(|(p, (original, _))| {
+                        original.is_none() && !p.exists() && p.parent() == Some(safe_path.as_path())
+                    })
+                    .filter_map(|(p, _)| p.file_name().map(|n| n.to_string_lossy().to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        for name in simulated {
+            listing.push_str(&format!("{} [FILE]\n", name));
+        }
         Ok(listing)
     }
 
+    /// Searches file contents under `path` (see `search::search`).
+    /// `display` is how `path` is shown in the results.
+    pub async fn search(
+        &self,
+        path: &str,
+        display: &str,
+        query: &crate::domain::types::SearchQuery,
+    ) -> Result<super::search::SearchOutcome> {
+        let safe_path = self.validate_path(Path::new(path))?;
+        // Dry run: simulated writes shadow the disk
+        let overlay: BTreeMap<PathBuf, String> = self
+            .with_overlay(&safe_path, |o| {
+                o.files.iter().map(|(p, (_, content))| (p.clone(), content.clone())).collect()
+            })
+            .unwrap_or_default();
+        if !safe_path.exists() && !overlay.contains_key(&safe_path) {
+            return Err(anyhow::anyhow!("Search path does not exist"));
+        }
+        super::search::search(&safe_path, display, query, &overlay)
+    }
+
+    /// Outline of a source file, or of the top-level items of every source file in a directory.
+    pub async fn outline(&self, path: &str, display: &str) -> Result<String> {
+        let safe_path = self.validate_path(Path::new(path))?;
+        if safe_path.is_dir() {
+            let index = super::outline::SymbolIndex::build(&safe_path);
+            if index.file_count() == 0 {
+                return Ok(format!("[{}: no Rust, Python or TypeScript files]", display));
+            }
+            return Ok(index.render_files(display, super::outline::MAX_OUTLINE_ROWS));
+        }
+        let Some(language) = super::outline::Language::from_path(&safe_path) else {
+            return Err(anyhow::anyhow!(
+                "Outlines are available for Rust, Python and TypeScript files only; use read instead"
+            ));
+        };
+        let content = self.read_file(path).await?;
+        let symbols = super::outline::outline(language, &content);
+        if symbols.is_empty() {
+            return Ok(format!("[{}: no top-level items]", display));
+        }
+        Ok(format!("[{}: {} items]\n{}", display, symbols.len(), super::outline::render(&symbols)))
+    }
+
+    /// Builds the symbol index for the project at `root`.
+    pub fn index_symbols(&self, root: &Path) -> Result<super::outline::SymbolIndex> {
+        let safe_path = self.validate_path(root)?;
+        Ok(super::outline::SymbolIndex::build(&safe_path))
+    }
+
     /// Find files matching a glob pattern within a directory.
     pub async fn find_files(&self, path: &str, pattern: &str) -> Result<String> {
         let path = Path::new(path);
@@ -295,6 +756,26 @@ impl ToolExecutor {
             }
         }
         
+        // Dry run: files that only exist in the overlay
+        let simulated: Vec<PathBuf> = self
+            .with_overlay(&safe_path, |o| {
+                o.files
+                    .iter()
+                    .filter(|(p, (original, _))| original.is_none() && !p.exists() && p.starts_with(&safe_path))
+                    .map(|(p, _)| p.clone())
+                    .collect()
+            })
+            .unwrap_or_default();
+        for entry_path in simulated {
+            let relative = entry_path.strip_prefix(&safe_path).unwrap_or(&entry_path);
+            let file_name = entry_path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+            if pattern_obj.matches(&file_name)
+                || (pattern.contains('/') && pattern_obj.matches(&relative.to_string_lossy()))
+            {
+                listing.push_str(&format!("{} [FILE]\n", relative.display()));
+            }
+        }
+
         if listing.is_empty() {
             Ok("No matching files found.".to_string())
         } else {
@@ -304,3 +785,95 @@ impl ToolExecutor {
 }
 
 pub type SharedToolExecutor = Arc<Mutex<ToolExecutor>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_matches_whole_words() {
+        assert!(command_matches("cargo publish", "cargo publish"));
+        assert!(command_matches("  cargo publish --dry-run", "cargo publish"));
+        assert!(!command_matches("cargo publisher", "cargo publish"));
+        assert!(!command_matches("cargo test", ""));
+    }
+
+    #[test]
+    fn test_compound_commands() {
+        let rules = vec!["git push".to_string()];
+        for command in [
+            "git push",
+            "cd . && git push",
+            "env git push",
+            "GIT_SSH=x env -i git push origin",
+            "cargo test; sudo git push",
+            "echo $(git push)",
+            "true || bash -c 'git push'",
+            "ls\ngit push",
+        ] {
+            assert_eq!(command_blocked(command, &rules), Some("git push"), "{}", command);
+        }
+        assert_eq!(command_blocked("git pushy; git status", &rules), None);
+
+        let allowed = vec!["cargo test".to_string()];
+        assert!(command_allowed("cargo test --lib", &allowed));
+        for command in ["cargo test; rm -rf /", "cargo test && x", "cargo test | sh", "cargo test `x`", "cargo test $(x)", "cargo test\nx"] {
+            assert!(!command_allowed(command, &allowed), "{}", command);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_project_policy() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let tools = ToolExecutor::new(vec![root.to_string_lossy().to_string()], 30, 300, vec![]);
+        let policy = ProjectPolicy {
+            blocked: vec!["git push".to_string()],
+            env: [("CONSTRUCT_TEST".to_string(), "on".to_string())].into(),
+            protected_paths: vec!["Cargo.lock".to_string(), "migrations/*".to_string()],
+            ..Default::default()
+        };
+        tools.set_project_policy(&root, Some(policy));
+
+        let err = tools.execute_command("git push origin main", &root).await.unwrap_err();
+        assert!(err.to_string().contains("blocked by the project config"));
+        let out = tools.execute_command("echo $CONSTRUCT_TEST", &root).await.unwrap();
+        assert!(out.contains("on"));
+
+        let lock = root.join("sub/Cargo.lock");
+        assert!(tools.write_file(&lock.to_string_lossy(), "x").await.is_err());
+        assert!(tools.write_file(&root.join("migrations/001.sql").to_string_lossy(), "x").await.is_err());
+        tools.write_file(&root.join("src.rs").to_string_lossy(), "x").await.unwrap();
+
+        tools.set_project_policy(&root, None);
+        tools.write_file(&lock.to_string_lossy(), "x").await.unwrap();
+
+        // The agent's own config stays protected without any policy
+        for rel in ["tasks/specs/construct.yaml", "tasks/specs/hooks.yaml", "tasks/specs/prompts/coder.md"] {
+            assert!(tools.write_file(&root.join(rel).to_string_lossy(), "x").await.is_err(), "{}", rel);
+        }
+        tools.write_file(&root.join("tasks/specs/plan.md").to_string_lossy(), "x").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_search_and_find_see_overlay() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        std::fs::write(root.join("old.rs"), "fn stale() {}\n").unwrap();
+        let tools = ToolExecutor::new(vec![root.to_string_lossy().to_string()], 30, 300, vec![]);
+        tools.begin_dry_run(&root);
+        tools.write_file(&root.join("old.rs").to_string_lossy(), "fn fresh() {}\n").await.unwrap();
+        tools.write_file(&root.join("src/new.rs").to_string_lossy(), "fn fresh_too() {}\n").await.unwrap();
+
+        let query = crate::domain::types::SearchQuery {
+            pattern: "fn \\w+".to_string(),
+            path: ".".to_string(),
+            ..Default::default()
+        };
+        let found = tools.search(&root.to_string_lossy(), ".", &query).await.unwrap();
+        assert_eq!(found.text, "old.rs:1: fn fresh() {}\nsrc/new.rs:1: fn fresh_too() {}\n");
+
+        let files = tools.find_files(&root.to_string_lossy(), "*.rs").await.unwrap();
+        assert!(files.contains("old.rs [FILE]") && files.contains("src/new.rs [FILE]"));
+    }
+}
@@ -0,0 +1,214 @@
+//! # Git
+//!
+//! Thin wrapper around the `git` CLI for a project directory.
+//! Each task works on its own `task/NNN-slug` branch; the engine commits as checklist items
+//! get ticked, and `.changes` / `.commit` / `.discard` operate relative to the task's base commit.
+
+use anyhow::{Context, Result, bail};
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// Identity used when neither the repository nor the user has one configured.
+const FALLBACK_NAME: &str = "Construct";
+const FALLBACK_EMAIL: &str = "construct@localhost";
+/// Longest slug kept in a branch name (task folder names can be much longer).
+const MAX_SLUG: usize = 40;
+
+#[derive(Debug, Clone)]
+pub struct GitRepo {
+    root: PathBuf,
+}
+
+impl GitRepo {
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Whether the project is the top level of a repository (not a folder inside a larger one).
+    pub async fn is_own_repo(&self) -> bool {
+        let toplevel = self.run(&["rev-parse", "--show-toplevel"]).await.ok();
+        toplevel.is_some_and(|t| {
+            Path::new(t.trim()).canonicalize().ok() == self.root.canonicalize().ok()
+        })
+    }
+
+    pub async fn has_commits(&self) -> bool {
+        self.run(&["rev-parse", "--verify", "-q", "HEAD"]).await.is_ok()
+    }
+
+    /// Uncommitted changes (`git status --porcelain` lines), leaving out paths under `except`.
+    pub async fn uncommitted(&self, except: &[&str]) -> Result<Vec<String>> {
+        let excludes: Vec<String> = except.iter().map(|p| format!(":(exclude){}", p)).collect();
+        let mut args = vec!["status", "--porcelain", "--", "."];
+        args.extend(excludes.iter().map(String::as_str));
+        let status = self.run(&args).await?;
+        Ok(status.lines().map(str::to_string).collect())
+    }
+
+    /// Keeps files matching `pattern` out of commits without touching the project's `.gitignore`.
+    pub fn exclude(&self, pattern: &str) -> Result<()> {
+        let path = self.root.join(".git/info/exclude");
+        let existing = std::fs::read_to_string(&path).unwrap_or_default();
+        if existing.lines().any(|l| l.trim() == pattern) {
+            return Ok(());
+        }
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut content = existing;
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        content.push_str(pattern);
+        content.push('\n');
+        std::fs::write(&path, content).context("Failed to write .git/info/exclude")
+    }
+
+    /// Full hash of `HEAD`.
+    pub async fn head(&self) -> Result<String> {
+        Ok(self.run(&["rev-parse", "HEAD"]).await?.trim().to_string())
+    }
+
+    /// Switches to `branch`, creating it from the current `HEAD` if needed.
+    /// Uncommitted changes are carried over.
+    pub async fn checkout_branch(&self, branch: &str) -> Result<()> {
+        if self.run(&["checkout", "-q", branch]).await.is_err() {
+            self.run(&["checkout", "-q", "-b", branch]).await?;
+        }
+        Ok(())
+    }
+
+    /// Stages everything and commits. Returns the short hash, or `None` if there was nothing to commit.
+    pub async fn commit_all(&self, message: &str) -> Result<Option<String>> {
+        self.run(&["add", "-A"]).await?;
+        if self.run(&["diff", "--cached", "--quiet"]).await.is_ok() {
+            return Ok(None);
+        }
+        self.commit(&["-q", "-m", message]).await?;
+        let short = self.run(&["rev-parse", "--short", "HEAD"]).await?;
+        Ok(Some(short.trim().to_string()))
+    }
+
+    /// Diffstat of the working tree (committed and uncommitted, new files included) against `base`.
+    pub async fn diffstat(&self, base: &str) -> Result<String> {
+        // Intent-to-add makes untracked files show up in the diff
+        self.run(&["add", "-A", "-N"]).await?;
+        let stat = self.run(&["diff", "--stat", base]).await?;
+        Ok(stat.trim_end().to_string())
+    }
+
+    /// Throws away every commit and uncommitted change made since `base`.
+    pub async fn reset_to(&self, base: &str) -> Result<()> {
+        self.run(&["reset", "-q", "--hard", base]).await?;
+        self.run(&["clean", "-q", "-fd"]).await?;
+        Ok(())
+    }
+
+    async fn commit(&self, args: &[&str]) -> Result<()> {
+        let configured = self
+            .run(&["config", "user.email"])
+            .await
+            .is_ok_and(|e| !e.trim().is_empty());
+        let name = format!("user.name={}", FALLBACK_NAME);
+        let email = format!("user.email={}", FALLBACK_EMAIL);
+        let mut full: Vec<&str> = Vec::new();
+        if !configured {
+            full.extend(["-c", &name, "-c", &email]);
+        }
+        full.push("commit");
+        full.extend(args);
+        self.run(&full).await.map(|_| ())
+    }
+
+    async fn run(&self, args: &[&str]) -> Result<String> {
+        let output = tokio::process::Command::new("git")
+            .args(args)
+            .current_dir(&self.root)
+            .output()
+            .await
+            .context("Failed to run git")?;
+        if !output.status.success() {
+            bail!(
+                "git {} failed: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        info!("git {}", args.join(" "));
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+}
+
+/// Branch name for a task folder: `tasks/003-add-login-page` -> `task/003-add-login-page`.
+pub fn task_branch(task_rel: &str) -> String {
+    let folder = task_rel.trim_end_matches('/').rsplit('/').next().unwrap_or(task_rel);
+    let slug: String = folder.chars().take(MAX_SLUG).collect();
+    format!("task/{}", slug.trim_end_matches('-'))
+}
+
+/// Texts of the ticked items (`- [x] ...`) in a tasks.md checklist.
+pub fn checked_items(checklist: &str) -> Vec<String> {
+    crate::domain::checklist::Checklist::parse(checklist).done_items()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_task_branch_and_checked_items() {
+        assert_eq!(task_branch("tasks/003-add-login"), "task/003-add-login");
+        let long = format!("tasks/001-{}", "a-".repeat(40));
+        assert!(task_branch(&long).len() <= "task/".len() + MAX_SLUG);
+        assert!(!task_branch(&long).ends_with('-'));
+
+        let checklist = "# Tasks\n- [x] Scaffold crate\n- [ ] Add lexer\n  - [X] Nested item\n- [x]\n";
+        assert_eq!(checked_items(checklist), vec!["Scaffold crate", "Nested item"]);
+    }
+
+    #[tokio::test]
+    async fn test_commit_diffstat_and_reset() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "one\n").unwrap();
+        let repo = GitRepo::new(dir.path());
+        repo.run(&["init", "-q"]).await.unwrap();
+        repo.commit_all("Initial commit").await.unwrap();
+        repo.checkout_branch("task/001-demo").await.unwrap();
+        let base = repo.head().await.unwrap();
+
+        assert_eq!(repo.commit_all("nothing").await.unwrap(), None);
+
+        std::fs::write(dir.path().join("a.txt"), "two\n").unwrap();
+        assert!(repo.commit_all("Change a").await.unwrap().is_some());
+        std::fs::write(dir.path().join("b.txt"), "new\n").unwrap();
+
+        let stat = repo.diffstat(&base).await.unwrap();
+        assert!(stat.contains("a.txt") && stat.contains("b.txt"));
+
+        repo.reset_to(&base).await.unwrap();
+        assert_eq!(std::fs::read_to_string(dir.path().join("a.txt")).unwrap(), "one\n");
+        assert!(!dir.path().join("b.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_own_repo_and_uncommitted() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = GitRepo::new(dir.path());
+        assert!(!repo.is_own_repo().await);
+        repo.run(&["init", "-q"]).await.unwrap();
+        assert!(repo.is_own_repo().await && !repo.has_commits().await);
+
+        // A project folder inside a larger repository is not a repository of its own
+        std::fs::create_dir_all(dir.path().join("projects/demo")).unwrap();
+        assert!(!GitRepo::new(dir.path().join("projects/demo")).is_own_repo().await);
+
+        std::fs::write(dir.path().join("a.txt"), "one\n").unwrap();
+        std::fs::create_dir_all(dir.path().join("tasks/001-demo")).unwrap();
+        std::fs::write(dir.path().join("tasks/001-demo/tasks.md"), "- [ ] x\n").unwrap();
+        assert_eq!(repo.uncommitted(&["tasks/001-demo"]).await.unwrap(), vec!["?? a.txt"]);
+        repo.commit_all("Initial commit").await.unwrap();
+        assert!(repo.uncommitted(&[]).await.unwrap().is_empty());
+    }
+}
@@ -3,4 +3,14 @@
 //! Provides internal tool execution capabilities (Filesystem, Terminal) with sandboxing.
 //! Replaces the external MCP architecture with a lightweight in-process implementation.
 
+pub mod diff;
 pub mod executor;
+pub mod git;
+pub mod outline;
+pub mod repo_map;
+pub mod retrieval;
+pub mod patch;
+pub mod reader;
+pub mod resolver;
+pub mod search;
+pub mod snapshot;
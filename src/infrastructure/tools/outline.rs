@@ -0,0 +1,513 @@
+//! # Code Outline
+//!
+//! Item-level structure of source files (functions, types, traits, impls, modules) with line
+//! numbers, extracted line by line with regexes: Rust, Python and TypeScript/JavaScript.
+//! `SymbolIndex` keeps the outlines of a whole project so the agent can ask where a symbol is
+//! defined instead of reading files to find it.
+
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::LazyLock;
+
+/// Files larger than this are not indexed.
+const MAX_FILE_BYTES: u64 = 500_000;
+/// Lookups report at most this many definitions.
+pub const MAX_MATCHES: usize = 20;
+/// Rows shown when outlining a directory.
+pub const MAX_OUTLINE_ROWS: usize = 300;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Language {
+    Rust,
+    Python,
+    TypeScript,
+}
+
+impl Language {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "rs" => Some(Language::Rust),
+            "py" => Some(Language::Python),
+            "ts" | "tsx" | "js" | "jsx" | "mjs" | "cjs" => Some(Language::TypeScript),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Symbol {
+    /// `fn`, `struct`, `enum`, `trait`, `impl`, `mod`, `type`, `const`, `static`, `macro`,
+    /// `def`, `class`, `function`, `interface`, `method`
+    pub kind: &'static str,
+    pub name: String,
+    /// Enclosing impl, trait, module or class
+    pub parent: Option<String>,
+    /// 1-based
+    pub line: usize,
+    /// Nesting level, for indentation in outlines
+    pub depth: usize,
+}
+
+impl Symbol {
+    /// `Parent::name` (Rust) or `Parent.name` style qualified name.
+    pub fn qualified(&self) -> String {
+        match &self.parent {
+            Some(parent) => format!("{}::{}", parent, self.name),
+            None => self.name.clone(),
+        }
+    }
+}
+
+static RUST_ITEM: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"^\s*(?:#\[[^\]]*\]\s*)*(?:pub(?:\([^)]*\))?\s+)?(?:default\s+)?(?:(?:async|const|unsafe|extern(?:\s+"[^"]*")?)\s+)*(fn|struct|enum|union|trait|type|mod|const|static|macro_rules!)\s*([A-Za-z_][A-Za-z0-9_]*)"#,
+    )
+    .unwrap()
+});
+static RUST_IMPL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*(?:unsafe\s+)?impl\b(?:\s*<[^{]*?>)?\s+(?:(.+?)\s+for\s+)?([A-Za-z_][A-Za-z0-9_:]*)").unwrap()
+});
+static PY_ITEM: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\s*)(?:async\s+)?(def|class)\s+([A-Za-z_][A-Za-z0-9_]*)").unwrap());
+static TS_ITEM: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^\s*(?:export\s+)?(?:default\s+)?(?:declare\s+)?(?:abstract\s+)?(?:async\s+)?(function\*?|class|interface|type|enum|namespace)\s+([A-Za-z_$][A-Za-z0-9_$]*)",
+    )
+    .unwrap()
+});
+static TS_CONST_FN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*(?:export\s+)?(?:const|let)\s+([A-Za-z_$][A-Za-z0-9_$]*)\s*(?::[^=]+)?=\s*(?:async\s+)?(?:\([^)]*\)|[A-Za-z_$][A-Za-z0-9_$]*)\s*(?::[^=]+)?=>").unwrap()
+});
+static TS_METHOD: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*(?:(?:public|private|protected|static|async|readonly|override|get|set)\s+)*([A-Za-z_$][A-Za-z0-9_$]*)\s*(?:<[^>]*>)?\s*\([^;]*$").unwrap()
+});
+
+/// Extracts the items of a source file.
+pub fn outline(language: Language, source: &str) -> Vec<Symbol> {
+    match language {
+        Language::Rust => outline_braces(source, rust_item),
+        Language::TypeScript => outline_braces(source, ts_item),
+        Language::Python => outline_python(source),
+    }
+}
+
+/// A recognized item: (kind, name, opens a container whose members get listed).
+type Item = (&'static str, String, bool);
+
+fn rust_item(line: &str, _in_container: bool) -> Option<Item> {
+    if let Some(caps) = RUST_IMPL.captures(line) {
+        let target = caps[2].to_string();
+        let name = match caps.get(1) {
+            Some(tr) => format!("{} for {}", tr.as_str().trim(), target),
+            None => target,
+        };
+        return Some(("impl", name, true));
+    }
+    let caps = RUST_ITEM.captures(line)?;
+    let kind = match &caps[1] {
+        "macro_rules!" => "macro",
+        "fn" => "fn",
+        "struct" => "struct",
+        "enum" => "enum",
+        "union" => "union",
+        "trait" => "trait",
+        "type" => "type",
+        "mod" => "mod",
+        "const" => "const",
+        _ => "static",
+    };
+    Some((kind, caps[2].to_string(), matches!(kind, "trait" | "mod")))
+}
+
+fn ts_item(line: &str, in_container: bool) -> Option<Item> {
+    if let Some(caps) = TS_ITEM.captures(line) {
+        let kind = match &caps[1] {
+            k if k.starts_with("function") => "function",
+            "class" => "class",
+            "interface" => "interface",
+            "type" => "type",
+            "enum" => "enum",
+            _ => "namespace",
+        };
+        return Some((kind, caps[2].to_string(), matches!(kind, "class" | "namespace")));
+    }
+    if let Some(caps) = TS_CONST_FN.captures(line) {
+        return Some(("function", caps[1].to_string(), false));
+    }
+    if in_container
+        && let Some(caps) = TS_METHOD.captures(line)
+        && !matches!(&caps[1], "if" | "for" | "while" | "switch" | "catch" | "return" | "function")
+    {
+        return Some(("method", caps[1].to_string(), false));
+    }
+    None
+}
+
+/// Brace-structured languages: items are recorded at file level and directly inside
+/// containers (impl/trait/mod, class/namespace), never inside function bodies.
+fn outline_braces(source: &str, recognize: fn(&str, bool) -> Option<Item>) -> Vec<Symbol> {
+    let mut symbols = Vec::new();
+    // (container name, brace depth of its body)
+    let mut containers: Vec<(String, usize)> = Vec::new();
+    let mut depth = 0usize;
+    let mut in_block_comment = false;
+
+    for (i, raw) in source.lines().enumerate() {
+        let line = strip_code(raw, &mut in_block_comment);
+        let item_depth = containers.last().map(|(_, d)| *d).unwrap_or(0);
+
+        if depth == item_depth
+            && let Some((kind, name, opens)) = recognize(&line, !containers.is_empty())
+        {
+            let parent = containers.last().map(|(n, _)| n.clone());
+            symbols.push(Symbol {
+                kind: if kind == "fn" && parent.is_some() { "method" } else { kind },
+                name: name.clone(),
+                parent,
+                line: i + 1,
+                depth: containers.len(),
+            });
+            if opens {
+                containers.push((name, depth + 1));
+            }
+        }
+
+        for c in line.chars() {
+            match c {
+                '{' => depth += 1,
+                '}' => {
+                    depth = depth.saturating_sub(1);
+                    while containers.last().is_some_and(|(_, d)| *d > depth) {
+                        containers.pop();
+                    }
+                }
+                _ => {}
+            }
+        }
+        // A container declared without a body on this line (`mod foo;`) never opens
+        if line.trim_end().ends_with(';') && containers.last().is_some_and(|(_, d)| *d > depth) {
+            containers.pop();
+        }
+    }
+    symbols
+}
+
+fn outline_python(source: &str) -> Vec<Symbol> {
+    let mut symbols = Vec::new();
+    // (class name, indentation of its body)
+    let mut classes: Vec<(String, usize)> = Vec::new();
+    // Indentation of the def whose body we are in (nested defs are skipped)
+    let mut in_def: Option<usize> = None;
+
+    for (i, line) in source.lines().enumerate() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+        if in_def.is_some_and(|d| indent <= d) {
+            in_def = None;
+        }
+        while classes.last().is_some_and(|(_, body)| indent < *body) {
+            classes.pop();
+        }
+        if in_def.is_some() {
+            continue;
+        }
+        let Some(caps) = PY_ITEM.captures(line) else {
+            continue;
+        };
+        let parent = classes.last().map(|(n, _)| n.clone());
+        let name = caps[3].to_string();
+        let kind = match (&caps[2], parent.is_some()) {
+            ("class", _) => "class",
+            (_, true) => "method",
+            _ => "def",
+        };
+        symbols.push(Symbol {
+            kind,
+            name: name.clone(),
+            parent,
+            line: i + 1,
+            depth: classes.len(),
+        });
+        if kind == "class" {
+            classes.push((name, indent + 1));
+        } else {
+            in_def = Some(indent);
+        }
+    }
+    symbols
+}
+
+/// Drops string and char literal contents and comments so braces inside them are not counted.
+fn strip_code(line: &str, in_block_comment: &mut bool) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    let mut out = String::with_capacity(line.len());
+    let mut in_string: Option<char> = None;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        i += 1;
+        if *in_block_comment {
+            if c == '*' && next == Some('/') {
+                i += 1;
+                *in_block_comment = false;
+            }
+            continue;
+        }
+        if let Some(quote) = in_string {
+            if c == '\\' {
+                i += 1;
+            } else if c == quote {
+                in_string = None;
+                out.push(c);
+            }
+            continue;
+        }
+        match c {
+            '/' if next == Some('/') => break,
+            '/' if next == Some('*') => {
+                i += 1;
+                *in_block_comment = true;
+            }
+            '"' | '`' => {
+                in_string = Some(c);
+                out.push(c);
+            }
+            // Char literals ('{', '\n'); lifetimes ('a) have no closing quote
+            '\'' if next == Some('\\') => {
+                while i < chars.len() && chars[i] != '\'' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            '\'' if chars.get(i + 1) == Some(&'\'') => i += 2,
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Renders an outline as indented `line: kind name` rows.
+pub fn render(symbols: &[Symbol]) -> String {
+    let width = symbols.last().map(|s| s.line.to_string().len()).unwrap_or(1);
+    symbols
+        .iter()
+        .map(|s| {
+            format!(
+                "{}{:>width$}: {} {}\n",
+                "  ".repeat(s.depth),
+                s.line,
+                s.kind,
+                s.name,
+                width = width
+            )
+        })
+        .collect()
+}
+
+/// Outlines of every supported source file in a project, by project-relative path.
+#[derive(Debug, Default, Clone)]
+pub struct SymbolIndex {
+    files: BTreeMap<String, Vec<Symbol>>,
+}
+
+impl SymbolIndex {
+    /// Indexes every supported file under `root`, honouring `.gitignore`.
+    pub fn build(root: &Path) -> Self {
+        let mut index = Self::default();
+        let walker = ignore::WalkBuilder::new(root)
+            .require_git(false)
+            .build();
+        for entry in walker.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if !entry.file_type().is_some_and(|t| t.is_file())
+                || entry.metadata().is_ok_and(|m| m.len() > MAX_FILE_BYTES)
+            {
+                continue;
+            }
+            if let (Some(language), Ok(source)) =
+                (Language::from_path(path), std::fs::read_to_string(path))
+            {
+                let rel = path.strip_prefix(root).unwrap_or(path).to_string_lossy().to_string();
+                index.files.insert(rel, outline(language, &source));
+            }
+        }
+        index
+    }
+
+    /// Re-indexes one file after a write (`None` removes it).
+    pub fn update(&mut self, rel: &str, source: Option<&str>) {
+        match (Language::from_path(Path::new(rel)), source) {
+            (Some(language), Some(source)) => {
+                self.files.insert(rel.to_string(), outline(language, source));
+            }
+            _ => {
+                self.files.remove(rel);
+            }
+        }
+    }
+
+    pub fn file_count(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Indexed files and their symbols, by path.
+    pub fn files(&self) -> impl Iterator<Item = (&str, &[Symbol])> {
+        self.files.iter().map(|(file, symbols)| (file.as_str(), symbols.as_slice()))
+    }
+
+    /// Top-level items of every indexed file, paths prefixed with `display_base`.
+    /// Stops after `max_lines` rows.
+    pub fn render_files(&self, display_base: &str, max_lines: usize) -> String {
+        let mut out = String::new();
+        let mut rows = 0;
+        for (file, symbols) in &self.files {
+            if rows >= max_lines {
+                out.push_str(&format!(
+                    "[Outline cut after {} lines. Outline a subdirectory or a single file for more.]\n",
+                    max_lines
+                ));
+                break;
+            }
+            let path = if display_base == "." {
+                file.clone()
+            } else {
+                format!("{}/{}", display_base.trim_end_matches('/'), file)
+            };
+            let top: Vec<Symbol> = symbols.iter().filter(|s| s.depth == 0).cloned().collect();
+            out.push_str(&format!("{}:\n{}", path, render(&top)));
+            rows += top.len() + 1;
+        }
+        out
+    }
+
+    /// Finds definitions of `query`: `name`, `Parent::name` or `Parent.name`.
+    /// Exact names win; otherwise case-insensitive substring matches are returned.
+    pub fn lookup(&self, query: &str) -> Vec<(&str, &Symbol)> {
+        let query = query.trim().trim_end_matches("()");
+        let (parent, name) = match query.rsplit_once("::").or_else(|| query.rsplit_once('.')) {
+            Some((p, n)) => (Some(p.rsplit("::").next().unwrap_or(p)), n),
+            None => (None, query),
+        };
+        // `impl Trait for Type` members match both `Type::name` and `Trait::name`
+        let parent_matches = |s: &Symbol| {
+            parent.is_none_or(|p| {
+                s.parent.as_deref().is_some_and(|sp| {
+                    sp.split(" for ")
+                        .any(|part| part == p || part.ends_with(&format!("::{}", p)))
+                })
+            })
+        };
+
+        let all = || {
+            self.files
+                .iter()
+                .flat_map(|(file, symbols)| symbols.iter().map(move |s| (file.as_str(), s)))
+        };
+        let exact: Vec<_> = all()
+            .filter(|(_, s)| s.name == name && parent_matches(s))
+            .take(MAX_MATCHES)
+            .collect();
+        if !exact.is_empty() {
+            return exact;
+        }
+        let lower = name.to_lowercase();
+        all()
+            .filter(|(_, s)| s.name.to_lowercase().contains(&lower) && parent_matches(s))
+            .take(MAX_MATCHES)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RUST: &str = r#"
+use std::fmt;
+
+/// Doc with { brace
+pub struct FeedManager {
+    entries: Vec<String>,
+}
+
+impl FeedManager {
+    pub fn new() -> Self {
+        fn helper() {}
+        let s = "}";
+        Self { entries: vec![] }
+    }
+
+    pub async fn update_feed(&mut self) {}
+}
+
+impl fmt::Display for FeedManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { Ok(()) }
+}
+
+pub(crate) mod nested {
+    pub trait Tool {
+        fn run(&self);
+    }
+}
+mod external;
+const LIMIT: usize = 3;
+"#;
+
+    #[test]
+    fn test_rust_outline() {
+        let symbols = outline(Language::Rust, RUST);
+        let names: Vec<String> = symbols.iter().map(|s| format!("{} {}", s.kind, s.qualified())).collect();
+        assert_eq!(
+            names,
+            vec![
+                "struct FeedManager",
+                "impl FeedManager",
+                "method FeedManager::new",
+                "method FeedManager::update_feed",
+                "impl fmt::Display for FeedManager",
+                "method fmt::Display for FeedManager::fmt",
+                "mod nested",
+                "trait nested::Tool",
+                "method Tool::run",
+                "mod external",
+                "const LIMIT",
+            ]
+        );
+        assert_eq!(symbols[3].line, 16);
+    }
+
+    #[test]
+    fn test_python_and_typescript_outline() {
+        let py = "import os\n\nclass Repo:\n    def __init__(self):\n        def inner(): pass\n\n    async def fetch(self):\n        pass\n\ndef main():\n    pass\n";
+        let names: Vec<String> = outline(Language::Python, py).iter().map(|s| format!("{} {}", s.kind, s.qualified())).collect();
+        assert_eq!(names, vec!["class Repo", "method Repo::__init__", "method Repo::fetch", "def main"]);
+
+        let ts = "export class Store {\n  private items: string[] = [];\n  async load(id: string): Promise<void> {\n    if (id) {}\n  }\n}\nexport const render = (x: number) => x;\ninterface Props { a: string }\n";
+        let names: Vec<String> = outline(Language::TypeScript, ts).iter().map(|s| format!("{} {}", s.kind, s.qualified())).collect();
+        assert_eq!(names, vec!["class Store", "method Store::load", "function render", "interface Props"]);
+    }
+
+    #[test]
+    fn test_index_lookup() {
+        let mut index = SymbolIndex::default();
+        index.update("src/feed.rs", Some(RUST));
+        index.update("notes.md", Some("# not code"));
+        assert_eq!(index.file_count(), 1);
+
+        let hits = index.lookup("FeedManager::update_feed");
+        assert_eq!(hits.len(), 1);
+        assert_eq!((hits[0].0, hits[0].1.line), ("src/feed.rs", 16));
+        assert_eq!(index.lookup("Display::fmt")[0].1.name, "fmt");
+        // The struct and both impl blocks
+        let hits = index.lookup("feedman");
+        assert_eq!(hits.len(), 3);
+        assert_eq!(hits[0].1.kind, "struct");
+
+        index.update("src/feed.rs", None);
+        assert!(index.lookup("FeedManager").is_empty());
+    }
+}
@@ -0,0 +1,430 @@
+//! # Patch Engine
+//!
+//! Applies partial file edits produced by the agent instead of whole-file overwrites.
+//! Supports two formats:
+//! - Search/replace blocks (`<<<<<<< SEARCH` / `=======` / `>>>>>>> REPLACE`)
+//! - Unified-diff hunks (`@@ -a,b +c,d @@` followed by ` `, `-` and `+` lines)
+//!
+//! Context is matched exactly first, then ignoring trailing whitespace, then ignoring indentation.
+//! Failures produce a [`PatchError`] whose message is meant to be shown to the model.
+
+use std::fmt;
+
+const SEARCH_MARKER: &str = "<<<<<<< SEARCH";
+const DIVIDER_MARKER: &str = "=======";
+const REPLACE_MARKER: &str = ">>>>>>> REPLACE";
+
+/// Result of a successfully applied patch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatchOutcome {
+    pub content: String,
+    pub added: usize,
+    pub removed: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatchError {
+    /// The patch body contained no recognizable edit blocks.
+    NoEdits,
+    /// A block is structurally broken (e.g. a SEARCH without REPLACE).
+    Malformed(String),
+    /// The context of edit `index` (1-based) was not found in the file.
+    NotFound { index: usize, hint: String },
+    /// The context of edit `index` matches several places in the file.
+    Ambiguous { index: usize, lines: Vec<usize> },
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatchError::NoEdits => write!(
+                f,
+                "No edit blocks found. Use SEARCH/REPLACE blocks or unified diff hunks (@@)."
+            ),
+            PatchError::Malformed(msg) => write!(f, "Malformed patch: {}", msg),
+            PatchError::NotFound { index, hint } => write!(
+                f,
+                "Edit #{} conflict: the SEARCH/context lines were not found in the file. {}",
+                index, hint
+            ),
+            PatchError::Ambiguous { index, lines } => write!(
+                f,
+                "Edit #{} is ambiguous: the SEARCH/context lines match at lines {:?}. Include more surrounding lines.",
+                index, lines
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PatchError {}
+
+/// A single edit: replace `search` lines with `replace` lines.
+#[derive(Debug, Clone, PartialEq)]
+struct Edit {
+    search: Vec<String>,
+    replace: Vec<String>,
+    /// 1-based line hint from a unified diff header, used to break ties.
+    line_hint: Option<usize>,
+}
+
+/// Applies a patch (search/replace blocks or unified diff hunks) to `original`.
+pub fn apply_patch(original: &str, patch: &str) -> Result<PatchOutcome, PatchError> {
+    let edits = if patch.contains(SEARCH_MARKER) {
+        parse_search_replace(patch)?
+    } else if patch.lines().any(|l| l.starts_with("@@")) {
+        parse_unified(patch)?
+    } else {
+        return Err(PatchError::NoEdits);
+    };
+    if edits.is_empty() {
+        return Err(PatchError::NoEdits);
+    }
+
+    let had_trailing_newline = original.ends_with('\n');
+    let mut lines: Vec<String> = original.lines().map(|l| l.to_string()).collect();
+    let mut added = 0;
+    let mut removed = 0;
+
+    for (i, edit) in edits.iter().enumerate() {
+        let index = i + 1;
+        if edit.search.is_empty() {
+            if lines.is_empty() {
+                lines = edit.replace.clone();
+                added += edit.replace.len();
+                continue;
+            }
+            return Err(PatchError::Malformed(format!(
+                "edit #{} has no SEARCH/context lines; use `write` to create new files",
+                index
+            )));
+        }
+
+        let start = locate(&lines, edit, index)?;
+        let common = lcs_len(&edit.search, &edit.replace);
+        removed += edit.search.len() - common;
+        added += edit.replace.len() - common;
+        lines.splice(start..start + edit.search.len(), edit.replace.iter().cloned());
+    }
+
+    let mut content = lines.join("\n");
+    if had_trailing_newline || (original.is_empty() && !content.is_empty()) {
+        content.push('\n');
+    }
+
+    Ok(PatchOutcome {
+        content,
+        added,
+        removed,
+    })
+}
+
+/// Finds the start line of an edit, trying progressively looser comparisons.
+fn locate(lines: &[String], edit: &Edit, index: usize) -> Result<usize, PatchError> {
+    let strategies: [fn(&str, &str) -> bool; 3] = [
+        |a, b| a == b,
+        |a, b| a.trim_end() == b.trim_end(),
+        |a, b| a.trim() == b.trim(),
+    ];
+
+    for eq in strategies {
+        let matches = find_all(lines, &edit.search, eq);
+        match matches.len() {
+            0 => continue,
+            1 => return Ok(matches[0]),
+            _ => {
+                // Unified diffs carry a line number: pick the closest match
+                if let Some(hint) = edit.line_hint {
+                    let best = matches
+                        .iter()
+                        .min_by_key(|m| (**m as i64 - (hint as i64 - 1)).abs())
+                        .copied()
+                        .unwrap_or(matches[0]);
+                    return Ok(best);
+                }
+                return Err(PatchError::Ambiguous {
+                    index,
+                    lines: matches.iter().map(|m| m + 1).collect(),
+                });
+            }
+        }
+    }
+
+    Err(PatchError::NotFound {
+        index,
+        hint: closest_hint(lines, &edit.search),
+    })
+}
+
+fn find_all(lines: &[String], needle: &[String], eq: fn(&str, &str) -> bool) -> Vec<usize> {
+    if needle.len() > lines.len() {
+        return Vec::new();
+    }
+    (0..=lines.len() - needle.len())
+        .filter(|&start| {
+            needle
+                .iter()
+                .zip(&lines[start..start + needle.len()])
+                .all(|(n, l)| eq(l, n))
+        })
+        .collect()
+}
+
+/// Describes the region that matches the most search lines, to help the model fix its context.
+fn closest_hint(lines: &[String], search: &[String]) -> String {
+    if lines.is_empty() {
+        return "The file is empty.".to_string();
+    }
+    let window = search.len().min(lines.len());
+    let mut best = (0, 0);
+    for start in 0..=lines.len() - window {
+        let score = search
+            .iter()
+            .zip(&lines[start..start + window])
+            .filter(|(s, l)| s.trim() == l.trim())
+            .count();
+        if score > best.1 {
+            best = (start, score);
+        }
+    }
+    if best.1 == 0 {
+        return format!(
+            "No similar lines found (first SEARCH line: `{}`). Read the file again before editing.",
+            search[0].trim()
+        );
+    }
+    let (start, score) = best;
+    let end = (start + window).min(lines.len());
+    let mut hint = format!(
+        "Closest match is lines {}-{} ({}/{} lines equal). Current content there:\n",
+        start + 1,
+        end,
+        score,
+        search.len()
+    );
+    for (i, line) in lines[start..end].iter().enumerate() {
+        hint.push_str(&format!("{:>5} | {}\n", start + i + 1, line));
+    }
+    hint
+}
+
+fn parse_search_replace(patch: &str) -> Result<Vec<Edit>, PatchError> {
+    enum Section {
+        Outside,
+        Search,
+        Replace,
+    }
+
+    let mut edits = Vec::new();
+    let mut section = Section::Outside;
+    let mut search = Vec::new();
+    let mut replace = Vec::new();
+
+    for line in patch.lines() {
+        let marker = line.trim_end();
+        match section {
+            Section::Outside => {
+                if marker == SEARCH_MARKER {
+                    section = Section::Search;
+                }
+            }
+            Section::Search => {
+                if marker == DIVIDER_MARKER {
+                    section = Section::Replace;
+                } else if marker == SEARCH_MARKER || marker == REPLACE_MARKER {
+                    return Err(PatchError::Malformed(format!(
+                        "edit #{} is missing the `{}` divider",
+                        edits.len() + 1,
+                        DIVIDER_MARKER
+                    )));
+                } else {
+                    search.push(line.to_string());
+                }
+            }
+            Section::Replace => {
+                if marker == REPLACE_MARKER {
+                    edits.push(Edit {
+                        search: std::mem::take(&mut search),
+                        replace: std::mem::take(&mut replace),
+                        line_hint: None,
+                    });
+                    section = Section::Outside;
+                } else if marker == SEARCH_MARKER {
+                    return Err(PatchError::Malformed(format!(
+                        "edit #{} is missing `{}`",
+                        edits.len() + 1,
+                        REPLACE_MARKER
+                    )));
+                } else {
+                    replace.push(line.to_string());
+                }
+            }
+        }
+    }
+
+    if !matches!(section, Section::Outside) {
+        return Err(PatchError::Malformed(format!(
+            "edit #{} is not terminated with `{}`",
+            edits.len() + 1,
+            REPLACE_MARKER
+        )));
+    }
+    Ok(edits)
+}
+
+fn parse_unified(patch: &str) -> Result<Vec<Edit>, PatchError> {
+    let mut edits = Vec::new();
+    let mut current: Option<Edit> = None;
+
+    for line in patch.lines() {
+        if line.starts_with("@@") {
+            if let Some(edit) = current.take() {
+                edits.push(edit);
+            }
+            current = Some(Edit {
+                search: Vec::new(),
+                replace: Vec::new(),
+                line_hint: parse_hunk_start(line),
+            });
+            continue;
+        }
+        if (line.starts_with("---") || line.starts_with("+++")) && current.is_none() {
+            continue; // File headers
+        }
+        let Some(edit) = current.as_mut() else {
+            continue;
+        };
+        if let Some(rest) = line.strip_prefix('-') {
+            edit.search.push(rest.to_string());
+        } else if let Some(rest) = line.strip_prefix('+') {
+            edit.replace.push(rest.to_string());
+        } else if let Some(rest) = line.strip_prefix(' ') {
+            edit.search.push(rest.to_string());
+            edit.replace.push(rest.to_string());
+        } else if line.is_empty() {
+            // Editors often strip the single space of empty context lines
+            edit.search.push(String::new());
+            edit.replace.push(String::new());
+        } else if line.starts_with('\\') {
+            // "\ No newline at end of file"
+        } else {
+            return Err(PatchError::Malformed(format!(
+                "unexpected line in hunk #{}: `{}`",
+                edits.len() + 1,
+                line
+            )));
+        }
+    }
+    if let Some(edit) = current.take() {
+        edits.push(edit);
+    }
+
+    // Trailing blank context picked up after the last hunk is noise, not context
+    for edit in &mut edits {
+        while edit.search.last().is_some_and(|l| l.is_empty())
+            && edit.replace.last().is_some_and(|l| l.is_empty())
+        {
+            edit.search.pop();
+            edit.replace.pop();
+        }
+    }
+    Ok(edits)
+}
+
+/// Extracts the old-file start line from `@@ -12,5 +12,6 @@`.
+fn parse_hunk_start(header: &str) -> Option<usize> {
+    let old = header.split_whitespace().find(|t| t.starts_with('-'))?;
+    old[1..].split(',').next()?.parse().ok()
+}
+
+/// Length of the longest common subsequence of two line lists.
+pub(crate) fn lcs_len(a: &[String], b: &[String]) -> usize {
+    let mut prev = vec![0usize; b.len() + 1];
+    let mut cur = vec![0usize; b.len() + 1];
+    for x in a {
+        for (j, y) in b.iter().enumerate() {
+            cur[j + 1] = if x == y {
+                prev[j] + 1
+            } else {
+                cur[j].max(prev[j + 1])
+            };
+        }
+        std::mem::swap(&mut prev, &mut cur);
+    }
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE: &str = "fn main() {\n    let x = 1;\n    println!(\"{}\", x);\n}\n";
+
+    #[test]
+    fn test_search_replace_exact() {
+        let patch = "<<<<<<< SEARCH\n    let x = 1;\n=======\n    let x = 2;\n    let y = 3;\n>>>>>>> REPLACE\n";
+        let out = apply_patch(FILE, patch).unwrap();
+        assert_eq!(
+            out.content,
+            "fn main() {\n    let x = 2;\n    let y = 3;\n    println!(\"{}\", x);\n}\n"
+        );
+        assert_eq!((out.added, out.removed), (2, 1));
+    }
+
+    #[test]
+    fn test_search_replace_fuzzy_indentation() {
+        let patch = "<<<<<<< SEARCH\nlet x = 1;\n=======\n    let x = 5;\n>>>>>>> REPLACE";
+        let out = apply_patch(FILE, patch).unwrap();
+        assert!(out.content.contains("    let x = 5;"));
+    }
+
+    #[test]
+    fn test_unified_hunk() {
+        let patch = "--- a/src/main.rs\n+++ b/src/main.rs\n@@ -1,3 +1,3 @@\n fn main() {\n-    let x = 1;\n+    let x = 42;\n     println!(\"{}\", x);\n";
+        let out = apply_patch(FILE, patch).unwrap();
+        assert!(out.content.contains("let x = 42;"));
+        assert_eq!((out.added, out.removed), (1, 1));
+    }
+
+    #[test]
+    fn test_conflict_reports_closest_region() {
+        let patch = "<<<<<<< SEARCH\n    let x = 1;\n    println!(\"{:?}\", x);\n=======\n    let x = 2;\n>>>>>>> REPLACE";
+        let err = apply_patch(FILE, patch).unwrap_err();
+        match &err {
+            PatchError::NotFound { index, hint } => {
+                assert_eq!(*index, 1);
+                assert!(hint.contains("lines 2-3"), "{}", hint);
+            }
+            other => panic!("Expected NotFound, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_ambiguous_match() {
+        let file = "a\nb\na\n";
+        let patch = "<<<<<<< SEARCH\na\n=======\nc\n>>>>>>> REPLACE";
+        assert_eq!(
+            apply_patch(file, patch).unwrap_err(),
+            PatchError::Ambiguous {
+                index: 1,
+                lines: vec![1, 3]
+            }
+        );
+    }
+
+    #[test]
+    fn test_unified_line_hint_breaks_ties() {
+        let file = "a\nb\na\n";
+        let patch = "@@ -3,1 +3,1 @@\n-a\n+c\n";
+        assert_eq!(apply_patch(file, patch).unwrap().content, "a\nb\nc\n");
+    }
+
+    #[test]
+    fn test_malformed_block() {
+        let patch = "<<<<<<< SEARCH\nfoo\n>>>>>>> REPLACE";
+        assert!(matches!(
+            apply_patch(FILE, patch),
+            Err(PatchError::Malformed(_))
+        ));
+        assert_eq!(apply_patch(FILE, "just text"), Err(PatchError::NoEdits));
+    }
+}
@@ -0,0 +1,222 @@
+//! # File Reader
+//!
+//! Range reads for the agent (```read path 120-180```, ```read path around 150```).
+//! Ranges come back as numbered lines with the total line count, large files are cut to a
+//! first page with a notice on how to read further, and binary files are refused.
+
+use crate::domain::types::LineRange;
+
+/// Whole-file reads above either limit are truncated to the first page.
+pub const MAX_LINES: usize = 400;
+pub const MAX_BYTES: usize = 40_000;
+/// Lines per page when a large file is truncated.
+pub const PAGE_LINES: usize = 250;
+/// Lines shown on each side for `around N`.
+pub const AROUND_LINES: usize = 30;
+/// Lines are cut to this many characters (minified code, single-line lockfiles).
+pub const MAX_LINE_CHARS: usize = 2000;
+
+/// Splits a read target into path and optional range:
+/// `src/a.rs`, `src/a.rs 10-20`, `src/a.rs 10-`, `src/a.rs:10-20`, `src/a.rs around 15`.
+pub fn parse_target(target: &str) -> (String, Option<LineRange>) {
+    let target = target.trim();
+
+    let words: Vec<&str> = target.split_whitespace().collect();
+    if words.len() >= 3
+        && words[words.len() - 2].eq_ignore_ascii_case("around")
+        && let Ok(line) = words[words.len() - 1].parse::<usize>()
+    {
+        let path = words[..words.len() - 2].join(" ");
+        return (path, Some(LineRange::Around(line)));
+    }
+    if words.len() >= 2
+        && let Some(range) = parse_span(words[words.len() - 1])
+    {
+        return (words[..words.len() - 1].join(" "), Some(range));
+    }
+    if let Some((path, span)) = target.rsplit_once(':')
+        && let Some(range) = parse_span(span)
+    {
+        return (path.to_string(), Some(range));
+    }
+    (target.to_string(), None)
+}
+
+/// `10-20`, `10-` or `10` (a single line).
+fn parse_span(text: &str) -> Option<LineRange> {
+    let (start, end) = match text.split_once('-') {
+        Some((s, "")) => (s.parse().ok()?, None),
+        Some((s, e)) => (s.parse().ok()?, Some(e.parse().ok()?)),
+        None => {
+            let line = text.parse().ok()?;
+            (line, Some(line))
+        }
+    };
+    Some(LineRange::Span(start, end))
+}
+
+/// Renders `content` for the agent. Small whole-file reads come back unchanged; ranges and
+/// truncated pages are numbered and prefixed with a header giving the total line count.
+/// Every numbered read is capped at `MAX_BYTES`, with long lines cut to `MAX_LINE_CHARS`.
+pub fn render(display: &str, content: &str, range: Option<&LineRange>) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    let total = lines.len();
+
+    let (start, end) = match range {
+        None if total <= MAX_LINES && content.len() <= MAX_BYTES => return content.to_string(),
+        None => (1, total.min(PAGE_LINES)),
+        Some(LineRange::Span(start, end)) => {
+            let start = (*start).max(1);
+            (start, end.unwrap_or(total).min(total))
+        }
+        Some(LineRange::Around(line)) => (
+            line.saturating_sub(AROUND_LINES).max(1),
+            (line + AROUND_LINES).min(total),
+        ),
+    };
+
+    if total == 0 {
+        return format!("[{}: empty file]", display);
+    }
+    if start > total || start > end {
+        return format!(
+            "[{}: has {} lines; requested range {}-{} is outside the file]",
+            display, total, start, end
+        );
+    }
+
+    // Stop at the byte cap, but always show the first requested line
+    let width = end.to_string().len();
+    let mut body = String::new();
+    let mut shown = start - 1;
+    let mut clipped = false;
+    for (i, line) in lines.iter().enumerate().take(end).skip(start - 1) {
+        let (text, cut) = clip(line);
+        let row = format!("{:>width$} | {}\n", i + 1, text, width = width);
+        if shown >= start && body.len() + row.len() > MAX_BYTES {
+            break;
+        }
+        clipped |= cut;
+        body.push_str(&row);
+        shown = i + 1;
+    }
+
+    let mut out = format!("[{}: lines {}-{} of {}]\n{}", display, start, shown, total, body);
+    let more = if range.is_none() { shown < total } else { shown < end };
+    if more {
+        out.push_str(&format!(
+            "[Truncated: file has {} lines ({} bytes). Read more with ```read {} {}-{}```, or ```search``` for what you need. Line numbers are not part of the file.]\n",
+            total,
+            content.len(),
+            display,
+            shown + 1,
+            (shown + PAGE_LINES).min(total)
+        ));
+    }
+    if clipped {
+        out.push_str(&format!(
+            "[Lines longer than {} characters are cut; ```search``` finds text inside them.]\n",
+            MAX_LINE_CHARS
+        ));
+    }
+    out
+}
+
+/// A line cut to `MAX_LINE_CHARS`, and whether it was cut.
+fn clip(line: &str) -> (std::borrow::Cow<'_, str>, bool) {
+    match line.char_indices().nth(MAX_LINE_CHARS) {
+        Some((at, _)) => (format!("{}…", &line[..at]).into(), true),
+        None => (line.into(), false),
+    }
+}
+
+/// Describes a binary file (by magic number), or `None` if the bytes look like text.
+pub fn binary_kind(bytes: &[u8]) -> Option<&'static str> {
+    const MAGIC: &[(&[u8], &str)] = &[
+        (b"\x89PNG", "PNG image"),
+        (b"\xFF\xD8\xFF", "JPEG image"),
+        (b"GIF8", "GIF image"),
+        (b"%PDF", "PDF document"),
+        (b"PK\x03\x04", "ZIP archive"),
+        (b"\x1F\x8B", "gzip archive"),
+        (b"\x7FELF", "ELF executable"),
+        (b"\0asm", "WebAssembly module"),
+        (b"SQLite format 3", "SQLite database"),
+    ];
+    if let Some((_, kind)) = MAGIC.iter().find(|(magic, _)| bytes.starts_with(magic)) {
+        return Some(kind);
+    }
+    let head = &bytes[..bytes.len().min(8000)];
+    if head.contains(&0) || std::str::from_utf8(bytes).is_err() {
+        return Some("binary data");
+    }
+    None
+}
+
+/// Human-readable size (`512 B`, `12.3 KB`, `4.0 MB`).
+pub fn format_size(bytes: usize) -> String {
+    match bytes {
+        b if b < 1024 => format!("{} B", b),
+        b if b < 1024 * 1024 => format!("{:.1} KB", b as f64 / 1024.0),
+        b => format!("{:.1} MB", b as f64 / (1024.0 * 1024.0)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_target() {
+        assert_eq!(parse_target("src/a.rs"), ("src/a.rs".into(), None));
+        assert_eq!(
+            parse_target("src/a.rs 10-20"),
+            ("src/a.rs".into(), Some(LineRange::Span(10, Some(20))))
+        );
+        assert_eq!(parse_target("src/a.rs:7-"), ("src/a.rs".into(), Some(LineRange::Span(7, None))));
+        assert_eq!(parse_target("src/a.rs around 15"), ("src/a.rs".into(), Some(LineRange::Around(15))));
+    }
+
+    #[test]
+    fn test_render_ranges_and_truncation() {
+        let content: String = (1..=1000).map(|i| format!("line {}\n", i)).collect();
+        let out = render("big.txt", &content, Some(&LineRange::Span(9, Some(11))));
+        assert_eq!(out, "[big.txt: lines 9-11 of 1000]\n 9 | line 9\n10 | line 10\n11 | line 11\n");
+
+        let out = render("big.txt", &content, None);
+        assert!(out.starts_with(&format!("[big.txt: lines 1-{} of 1000]", PAGE_LINES)));
+        assert!(out.contains(&format!("```read big.txt {}-{}```", PAGE_LINES + 1, 2 * PAGE_LINES)));
+
+        let out = render("big.txt", &content, Some(&LineRange::Around(1000)));
+        assert!(out.starts_with("[big.txt: lines 970-1000 of 1000]"));
+
+        assert_eq!(render("small.txt", "a\nb\n", None), "a\nb\n");
+    }
+
+    #[test]
+    fn test_render_caps_long_lines_and_bytes() {
+        // A single minified line: cut, with no follow-up range past the end of the file
+        let minified = "x".repeat(100_000);
+        let out = render("app.min.js", &minified, None);
+        assert!(out.starts_with("[app.min.js: lines 1-1 of 1]\n"));
+        assert!(out.len() < MAX_LINE_CHARS + 500);
+        assert!(out.contains("are cut") && !out.contains("Truncated"));
+        assert!(render("app.min.js", &minified, Some(&LineRange::Span(1, Some(1)))).len() < MAX_LINE_CHARS + 500);
+
+        // Explicit ranges stop at the byte cap and say where to continue
+        let wide: String = (1..=300).map(|i| format!("{} {}\n", i, "y".repeat(1000))).collect();
+        let out = render("wide.txt", &wide, Some(&LineRange::Span(1, Some(300))));
+        assert!(out.len() <= MAX_BYTES + 1000);
+        let shown = out.lines().filter(|l| l.contains(" | ")).count();
+        assert!(shown < 300);
+        assert!(out.contains(&format!("```read wide.txt {}-{}```", shown + 1, shown + PAGE_LINES)));
+    }
+
+    #[test]
+    fn test_binary_kind() {
+        assert_eq!(binary_kind(b"\x89PNG\r\n\x1a\n...."), Some("PNG image"));
+        assert_eq!(binary_kind(b"abc\0def"), Some("binary data"));
+        assert_eq!(binary_kind("fn main() {}".as_bytes()), None);
+        assert_eq!(format_size(2048), "2.0 KB");
+    }
+}
@@ -0,0 +1,193 @@
+//! # Repository Map
+//!
+//! Compact view of a project for the prompt context, so the model knows the layout before it
+//! spends steps on `list`: the file tree grouped by directory, then the top-level symbols of
+//! the files most relevant to the task, cut to a character budget.
+
+use super::outline::SymbolIndex;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// Size of the map in characters (roughly 1500 tokens).
+pub const MAP_BUDGET: usize = 6_000;
+/// Files listed before the walk stops.
+const MAX_FILES: usize = 2_000;
+/// Symbols shown per file.
+const MAX_SYMBOLS_PER_FILE: usize = 12;
+
+/// Words too common in task descriptions to say anything about relevance.
+const STOPWORDS: &[&str] = &[
+    "the", "and", "for", "with", "add", "use", "from", "that", "this", "into", "when", "should",
+    "file", "files", "new", "not", "are", "all", "each", "make", "task",
+];
+
+/// Project files relative to `root`, sorted, honouring `.gitignore`.
+/// The top-level `tasks/` folder is left out: its documents are already in the context.
+pub fn list_files(root: &Path) -> Vec<String> {
+    let walker = ignore::WalkBuilder::new(root)
+        .hidden(false)
+        .require_git(false)
+        .filter_entry(|e| e.file_name() != ".git")
+        .sort_by_file_path(|a, b| a.cmp(b))
+        .build();
+    walker
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_some_and(|t| t.is_file()))
+        .filter_map(|e| {
+            let rel = e.path().strip_prefix(root).ok()?.to_string_lossy().to_string();
+            (!rel.starts_with("tasks/")).then_some(rel)
+        })
+        .take(MAX_FILES)
+        .collect()
+}
+
+/// Renders the map for `files`, ranking the symbol section by relevance to `query`
+/// (usually the task request and plan).
+pub fn render(files: &[String], index: &SymbolIndex, query: &str, budget: usize) -> String {
+    if files.is_empty() {
+        return "(Empty project)".to_string();
+    }
+
+    let tree = render_tree(files, budget / 2);
+    let mut out = format!("Files:\n{}", tree);
+
+    let terms = terms(query);
+    let mut ranked: Vec<(usize, &str, Vec<String>)> = index
+        .files()
+        .map(|(file, symbols)| {
+            let top: Vec<String> = symbols
+                .iter()
+                .filter(|s| s.depth == 0)
+                .map(|s| format!("{} {}", s.kind, s.name))
+                .collect();
+            (score(file, &top, &terms), file, top)
+        })
+        .filter(|(_, _, top)| !top.is_empty())
+        .collect();
+    // Relevant files first, then the ones with the most symbols
+    ranked.sort_by(|a, b| b.0.cmp(&a.0).then(b.2.len().cmp(&a.2.len())).then(a.1.cmp(b.1)));
+
+    if ranked.is_empty() {
+        return out;
+    }
+    out.push_str("\nKey symbols:\n");
+    for (shown, (_, file, top)) in ranked.iter().enumerate() {
+        let shown_symbols: Vec<&str> = top.iter().take(MAX_SYMBOLS_PER_FILE).map(|s| s.as_str()).collect();
+        let mut line = format!("{}: {}", file, shown_symbols.join(", "));
+        if top.len() > MAX_SYMBOLS_PER_FILE {
+            line.push_str(&format!(", … (+{})", top.len() - MAX_SYMBOLS_PER_FILE));
+        }
+        if out.len() + line.len() > budget {
+            out.push_str(&format!(
+                "[{} more files with symbols. Use ```outline``` or ```symbol``` to look further.]\n",
+                ranked.len() - shown
+            ));
+            break;
+        }
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out
+}
+
+/// `dir/: a.rs b.rs` per directory, or `dir/ (N files)` per directory when that is too long.
+fn render_tree(files: &[String], budget: usize) -> String {
+    let mut dirs: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for file in files {
+        let (dir, name) = file.rsplit_once('/').unwrap_or(("", file.as_str()));
+        dirs.entry(dir).or_default().push(name);
+    }
+    let label = |dir: &str| if dir.is_empty() { "./".to_string() } else { format!("{}/", dir) };
+
+    let full: String = dirs
+        .iter()
+        .map(|(dir, names)| format!("{}: {}\n", label(dir), names.join(" ")))
+        .collect();
+    if full.len() <= budget {
+        return full;
+    }
+
+    let mut out = String::new();
+    for (shown, (dir, names)) in dirs.iter().enumerate() {
+        let line = format!("{} ({} files)\n", label(dir), names.len());
+        if out.len() + line.len() > budget {
+            out.push_str(&format!("[{} more directories]\n", dirs.len() - shown));
+            break;
+        }
+        out.push_str(&line);
+    }
+    out
+}
+
+fn terms(query: &str) -> BTreeSet<String> {
+    query
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .map(|w| w.to_lowercase())
+        .filter(|w| w.len() >= 3 && !STOPWORDS.contains(&w.as_str()))
+        .collect()
+}
+
+/// Path matches weigh more than symbol matches; symbol matches are capped so big files
+/// do not win on size alone.
+fn score(file: &str, symbols: &[String], terms: &BTreeSet<String>) -> usize {
+    let path = file.to_lowercase();
+    let mut path_score = 0;
+    let mut symbol_score = 0;
+    for term in terms {
+        if path.contains(term.as_str()) {
+            path_score += 5;
+        }
+        symbol_score += symbols.iter().filter(|s| s.to_lowercase().contains(term.as_str())).count();
+    }
+    path_score + symbol_score.min(10)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_ranks_by_task_and_respects_budget() {
+        let mut index = SymbolIndex::default();
+        index.update("src/feed.rs", Some("pub struct FeedManager {}\nfn update_feed() {}\n"));
+        index.update("src/parser.rs", Some("pub fn parse_actions() {}\nfn a() {}\nfn b() {}\n"));
+        let files = vec![
+            "Cargo.toml".to_string(),
+            "src/feed.rs".to_string(),
+            "src/parser.rs".to_string(),
+        ];
+
+        let map = render(&files, &index, "Fix the feed rendering", MAP_BUDGET);
+        assert!(map.starts_with("Files:\n./: Cargo.toml\nsrc/: feed.rs parser.rs\n"));
+        let feed = map.find("src/feed.rs: struct FeedManager").unwrap();
+        let parser = map.find("src/parser.rs: fn parse_actions").unwrap();
+        assert!(feed < parser);
+
+        // Without a matching term the file with more symbols comes first
+        let map = render(&files, &index, "", MAP_BUDGET);
+        assert!(map.find("src/parser.rs:").unwrap() < map.find("src/feed.rs:").unwrap());
+
+        let small = render(&files, &index, "feed", 120);
+        assert!(small.contains("more files with symbols"));
+    }
+
+    #[test]
+    fn test_tree_collapses_when_too_long() {
+        let files: Vec<String> = (0..50).map(|i| format!("src/module_{:02}.rs", i)).collect();
+        assert_eq!(render_tree(&files, 100), "src/ (50 files)\n");
+    }
+
+    #[test]
+    fn test_list_files_skips_ignored_and_tasks() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("target")).unwrap();
+        std::fs::create_dir_all(root.join("tasks/001-x")).unwrap();
+        std::fs::write(root.join(".gitignore"), "target/\n").unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(root.join("target/out"), "").unwrap();
+        std::fs::write(root.join("tasks/001-x/plan.md"), "").unwrap();
+        assert_eq!(list_files(root), vec![".gitignore", "src/main.rs"]);
+    }
+}
@@ -0,0 +1,235 @@
+//! # Path Resolver
+//!
+//! Maps agent-visible paths onto real paths under a project root, and back.
+//! The agent only ever sees paths relative to the project: `src/main.rs`, `./src`, and the
+//! "virtual root" form `/src/main.rs` all resolve to `<project>/src/main.rs`.
+//! Anything that would leave the project (`../x`, symlinks pointing outside) is rejected.
+
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathError {
+    /// The path resolves outside of the project root.
+    Escape(String),
+    /// The path is empty or otherwise unusable.
+    Invalid(String),
+}
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathError::Escape(p) => write!(
+                f,
+                "Access denied: `{}` is outside the project. Use paths relative to the project root.",
+                p
+            ),
+            PathError::Invalid(p) => write!(f, "Invalid path: `{}`", p),
+        }
+    }
+}
+
+impl std::error::Error for PathError {}
+
+#[derive(Debug, Clone)]
+pub struct PathResolver {
+    root: PathBuf,
+}
+
+impl PathResolver {
+    /// Creates a resolver for a project root. The root is canonicalized when it exists.
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        let root = root.as_ref();
+        let root = root.canonicalize().unwrap_or_else(|_| normalize(root));
+        Self { root }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Resolves an agent-visible path to a real path under the project root.
+    pub fn resolve(&self, agent_path: &str) -> Result<PathBuf, PathError> {
+        let trimmed = agent_path.trim();
+        if trimmed.is_empty() {
+            return Err(PathError::Invalid(agent_path.to_string()));
+        }
+
+        let requested = Path::new(trimmed);
+        let relative: PathBuf = if requested.is_absolute() {
+            let normalized = normalize(requested);
+            match normalized.strip_prefix(&self.root) {
+                // Real absolute path inside the project
+                Ok(rest) => rest.to_path_buf(),
+                // Virtual root: "/src/main.rs" means "<project>/src/main.rs"
+                Err(_) => requested.components().skip(1).collect(),
+            }
+        } else {
+            requested.to_path_buf()
+        };
+
+        let mut parts: Vec<&std::ffi::OsStr> = Vec::new();
+        for component in relative.components() {
+            match component {
+                Component::Normal(p) => parts.push(p),
+                Component::CurDir => {}
+                Component::ParentDir => {
+                    if parts.pop().is_none() {
+                        return Err(PathError::Escape(agent_path.to_string()));
+                    }
+                }
+                Component::RootDir | Component::Prefix(_) => {
+                    return Err(PathError::Invalid(agent_path.to_string()));
+                }
+            }
+        }
+
+        let mut resolved = self.root.clone();
+        resolved.extend(parts);
+
+        // Symlinks: the nearest existing ancestor must still live under the root
+        if let Some(real) = nearest_existing(&resolved)
+            .filter(|p| p.starts_with(&self.root))
+            .and_then(|p| p.canonicalize().ok())
+            && !real.starts_with(&self.root)
+        {
+            return Err(PathError::Escape(agent_path.to_string()));
+        }
+
+        Ok(resolved)
+    }
+
+    /// Converts a real path back into the agent-visible form (`.` for the root itself).
+    pub fn to_agent(&self, real: &Path) -> String {
+        match real.strip_prefix(&self.root) {
+            Ok(rest) if rest.as_os_str().is_empty() => ".".to_string(),
+            Ok(rest) => rest.to_string_lossy().to_string(),
+            Err(_) => real.to_string_lossy().to_string(),
+        }
+    }
+
+    /// Agent-visible form of a requested path, for feed labels. Falls back to the raw input.
+    pub fn display(&self, agent_path: &str) -> String {
+        self.resolve(agent_path)
+            .map(|p| self.to_agent(&p))
+            .unwrap_or_else(|_| agent_path.to_string())
+    }
+
+    /// Replaces the real project root in free text (errors, command output) with agent-visible paths.
+    pub fn scrub(&self, text: &str) -> String {
+        let root = self.root.to_string_lossy();
+        text.replace(&format!("{}/", root), "").replace(root.as_ref(), ".")
+    }
+}
+
+/// Lexically normalizes a path (drops `.`, folds `..`) without touching the filesystem.
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other.as_os_str()),
+        }
+    }
+    out
+}
+
+fn nearest_existing(path: &Path) -> Option<&Path> {
+    path.ancestors().find(|p| p.exists())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn resolver() -> PathResolver {
+        PathResolver::new("/projects/demo")
+    }
+
+    #[test]
+    fn test_resolve_forms() {
+        let r = resolver();
+        let expected = PathBuf::from("/projects/demo/src/main.rs");
+        assert_eq!(r.resolve("src/main.rs").unwrap(), expected);
+        assert_eq!(r.resolve("./src/main.rs").unwrap(), expected);
+        assert_eq!(r.resolve("/src/main.rs").unwrap(), expected);
+        assert_eq!(r.resolve("/projects/demo/src/main.rs").unwrap(), expected);
+        assert_eq!(r.resolve("src/../src/main.rs").unwrap(), expected);
+        assert_eq!(r.resolve(".").unwrap(), PathBuf::from("/projects/demo"));
+    }
+
+    #[test]
+    fn test_escapes_fail() {
+        let r = resolver();
+        assert!(matches!(r.resolve("../other"), Err(PathError::Escape(_))));
+        assert!(matches!(r.resolve("src/../../x"), Err(PathError::Escape(_))));
+        assert!(matches!(r.resolve("/../etc/passwd"), Err(PathError::Escape(_))));
+        assert!(matches!(r.resolve(""), Err(PathError::Invalid(_))));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_symlink_escape_fails() {
+        let project = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        std::os::unix::fs::symlink(outside.path(), project.path().join("link")).unwrap();
+
+        let r = PathResolver::new(project.path());
+        assert!(matches!(r.resolve("link/secret.txt"), Err(PathError::Escape(_))));
+        assert!(r.resolve("new/file.txt").is_ok());
+    }
+
+    #[test]
+    fn test_to_agent_and_scrub() {
+        let r = resolver();
+        assert_eq!(r.to_agent(Path::new("/projects/demo")), ".");
+        assert_eq!(r.to_agent(Path::new("/projects/demo/src/lib.rs")), "src/lib.rs");
+        assert_eq!(
+            r.scrub("Failed to read file '/projects/demo/src/x.rs'"),
+            "Failed to read file 'src/x.rs'"
+        );
+    }
+
+    fn segment() -> impl Strategy<Value = String> {
+        prop_oneof![
+            4 => "[a-z0-9_]{1,8}",
+            1 => Just(".".to_string()),
+            1 => Just("..".to_string()),
+            1 => Just(String::new()),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn prop_resolved_paths_stay_under_root(parts in prop::collection::vec(segment(), 1..8), absolute in any::<bool>()) {
+            let r = resolver();
+            let joined = parts.join("/");
+            let input = if absolute { format!("/{}", joined) } else { joined };
+            if let Ok(p) = r.resolve(&input) {
+                prop_assert!(p.starts_with(r.root()));
+            }
+        }
+
+        #[test]
+        fn prop_roundtrip_relative(parts in prop::collection::vec("[a-z0-9_]{1,8}", 1..6)) {
+            let r = resolver();
+            let rel = parts.join("/");
+            let real = r.resolve(&rel).unwrap();
+            prop_assert_eq!(r.to_agent(&real), rel.clone());
+            // The virtual root form resolves to the same place
+            prop_assert_eq!(r.resolve(&format!("/{}", rel)).unwrap(), real);
+        }
+
+        #[test]
+        fn prop_climbing_above_root_fails(parts in prop::collection::vec("[a-z0-9_]{1,8}", 0..4), extra in 1usize..4) {
+            let r = resolver();
+            let ups = vec![".."; parts.len() + extra].join("/");
+            let input = if parts.is_empty() { ups } else { format!("{}/{}", parts.join("/"), ups) };
+            prop_assert!(matches!(r.resolve(&input), Err(PathError::Escape(_))));
+        }
+    }
+}
@@ -0,0 +1,380 @@
+//! # Lexical Retrieval
+//!
+//! BM25 over the text files of a project, so the engine can attach code relevant to the current
+//! question or checklist item without the model guessing paths.
+//! Files are split into overlapping line windows; only term counts are stored, and snippets are
+//! read back from the files when a query matches. The index is saved as JSON under
+//! `data/index/` (one file per project) and refreshed incrementally by modification time.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Where project indexes are stored, relative to the bot's working directory.
+pub const INDEX_DIR: &str = "data/index";
+/// Snippets attached to a prompt.
+pub const TOP_K: usize = 4;
+/// Lines per snippet shown to the model.
+pub const SNIPPET_LINES: usize = 20;
+
+/// Lines per indexed window, and the step between windows (windows overlap by the difference).
+const CHUNK_LINES: usize = 40;
+const CHUNK_STEP: usize = 30;
+/// Files larger than this are not indexed.
+const MAX_FILE_BYTES: u64 = 500_000;
+const K1: f64 = 1.2;
+const B: f64 = 0.75;
+
+const STOPWORDS: &[&str] = &[
+    "the", "and", "for", "with", "that", "this", "from", "into", "are", "was", "not", "but",
+    "all", "any", "can", "has", "have", "use", "when", "what", "how", "why", "does", "should",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Chunk {
+    /// First and last line (1-based, inclusive)
+    start: usize,
+    end: usize,
+    terms: HashMap<String, u32>,
+    len: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FileEntry {
+    modified: u64,
+    size: u64,
+    chunks: Vec<Chunk>,
+}
+
+/// A matching window of a file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hit {
+    pub file: String,
+    pub start: usize,
+    pub end: usize,
+    pub score: f64,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct RetrievalIndex {
+    root: PathBuf,
+    files: BTreeMap<String, FileEntry>,
+}
+
+impl RetrievalIndex {
+    /// Index file for the project at `root` inside `dir` (normally `INDEX_DIR`).
+    pub fn location(dir: &Path, root: &Path) -> PathBuf {
+        let hash = format!("{:x}", Sha256::digest(root.to_string_lossy().as_bytes()));
+        dir.join(format!("{}.json", &hash[..16]))
+    }
+
+    /// Loads the saved index for `root`, or starts an empty one.
+    pub fn load(dir: &Path, root: &Path) -> Self {
+        std::fs::read_to_string(Self::location(dir, root))
+            .ok()
+            .and_then(|s| serde_json::from_str::<Self>(&s).ok())
+            .filter(|index| index.root == root)
+            .unwrap_or_else(|| Self {
+                root: root.to_path_buf(),
+                files: BTreeMap::new(),
+            })
+    }
+
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        std::fs::create_dir_all(dir).context("Failed to create index directory")?;
+        let content = serde_json::to_string(self)?;
+        std::fs::write(Self::location(dir, &self.root), content).context("Failed to write index")
+    }
+
+    /// Number of indexed text files.
+    pub fn file_count(&self) -> usize {
+        self.files.values().filter(|f| !f.chunks.is_empty()).count()
+    }
+
+    /// Re-indexes files that are new or changed since the last refresh and drops deleted ones.
+    /// Returns whether anything changed.
+    pub fn refresh(&mut self) -> bool {
+        let walker = ignore::WalkBuilder::new(&self.root)
+            .hidden(false)
+            .require_git(false)
+            .filter_entry(|e| e.file_name() != ".git")
+            .build();
+
+        let mut seen = HashSet::new();
+        let mut changed = false;
+        for entry in walker.filter_map(|e| e.ok()) {
+            if !entry.file_type().is_some_and(|t| t.is_file()) {
+                continue;
+            }
+            let Ok(rel) = entry.path().strip_prefix(&self.root) else {
+                continue;
+            };
+            let rel = rel.to_string_lossy().to_string();
+            // Task documents are already part of the prompt context
+            if rel.starts_with("tasks/") {
+                continue;
+            }
+            let Some((modified, size)) = stamp(entry.path()) else {
+                continue;
+            };
+            if size > MAX_FILE_BYTES {
+                continue;
+            }
+            seen.insert(rel.clone());
+            if self
+                .files
+                .get(&rel)
+                .is_some_and(|f| f.modified == modified && f.size == size)
+            {
+                continue;
+            }
+            changed = true;
+            // Binary files are kept without chunks so they are not re-read on every refresh
+            let chunks = match std::fs::read(entry.path()) {
+                Ok(bytes) if !bytes.iter().take(8000).any(|b| *b == 0) => {
+                    chunk(&String::from_utf8_lossy(&bytes))
+                }
+                _ => Vec::new(),
+            };
+            self.files.insert(
+                rel,
+                FileEntry {
+                    modified,
+                    size,
+                    chunks,
+                },
+            );
+        }
+
+        let before = self.files.len();
+        self.files.retain(|rel, _| seen.contains(rel));
+        changed || self.files.len() != before
+    }
+
+    /// The best `k` windows for `query`, at most one per file.
+    pub fn search(&self, query: &str, k: usize) -> Vec<Hit> {
+        let terms: Vec<String> = tokenize(query)
+            .into_iter()
+            .filter(|t| !STOPWORDS.contains(&t.as_str()))
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        if terms.is_empty() {
+            return Vec::new();
+        }
+
+        let chunks: Vec<(&str, &Chunk)> = self
+            .files
+            .iter()
+            .flat_map(|(file, entry)| entry.chunks.iter().map(move |c| (file.as_str(), c)))
+            .collect();
+        if chunks.is_empty() {
+            return Vec::new();
+        }
+        let n = chunks.len() as f64;
+        let avg_len = chunks.iter().map(|(_, c)| c.len as f64).sum::<f64>() / n;
+
+        let idf: HashMap<&str, f64> = terms
+            .iter()
+            .map(|t| {
+                let df = chunks.iter().filter(|(_, c)| c.terms.contains_key(t)).count() as f64;
+                (t.as_str(), ((n - df + 0.5) / (df + 0.5) + 1.0).ln())
+            })
+            .collect();
+
+        let mut best: HashMap<&str, Hit> = HashMap::new();
+        for (file, chunk) in &chunks {
+            let score: f64 = terms
+                .iter()
+                .filter_map(|t| chunk.terms.get(t).map(|tf| (t, *tf as f64)))
+                .map(|(t, tf)| {
+                    let norm = K1 * (1.0 - B + B * chunk.len as f64 / avg_len.max(1.0));
+                    idf[t.as_str()] * tf * (K1 + 1.0) / (tf + norm)
+                })
+                .sum();
+            if score <= 0.0 || best.get(file).is_some_and(|h| h.score >= score) {
+                continue;
+            }
+            best.insert(
+                file,
+                Hit {
+                    file: file.to_string(),
+                    start: chunk.start,
+                    end: chunk.end,
+                    score,
+                },
+            );
+        }
+
+        let mut hits: Vec<Hit> = best.into_values().collect();
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.file.cmp(&b.file)));
+        hits.truncate(k);
+        hits
+    }
+}
+
+/// Renders a hit as a cited snippet: the `SNIPPET_LINES` lines of its window with the most
+/// query terms, numbered like `read` ranges.
+pub fn excerpt(content: &str, hit: &Hit, query: &str) -> String {
+    let terms: HashSet<String> = tokenize(query).into_iter().collect();
+    let lines: Vec<&str> = content.lines().collect();
+    let end = hit.end.min(lines.len());
+    if hit.start == 0 || hit.start > end {
+        return String::new();
+    }
+    let weight = |line: &str| tokenize(line).iter().filter(|t| terms.contains(*t)).count();
+    let weights: Vec<usize> = lines[hit.start - 1..end].iter().map(|l| weight(l)).collect();
+
+    // Slide a window over the chunk and keep the densest one
+    let span = SNIPPET_LINES.min(weights.len());
+    let mut best = (0, 0);
+    for offset in 0..=weights.len() - span {
+        let total: usize = weights[offset..offset + span].iter().sum();
+        if total > best.1 {
+            best = (offset, total);
+        }
+    }
+    let from = hit.start + best.0;
+    let to = from + span - 1;
+
+    let width = to.to_string().len();
+    let mut out = format!("[{}: lines {}-{}]\n", hit.file, from, to);
+    for (i, line) in lines.iter().enumerate().take(to).skip(from - 1) {
+        out.push_str(&format!("{:>width$} | {}\n", i + 1, line, width = width));
+    }
+    out
+}
+
+fn chunk(text: &str) -> Vec<Chunk> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < lines.len() {
+        let end = (start + CHUNK_LINES).min(lines.len());
+        let mut terms: HashMap<String, u32> = HashMap::new();
+        let mut len = 0;
+        for line in &lines[start..end] {
+            for token in tokenize(line) {
+                *terms.entry(token).or_default() += 1;
+                len += 1;
+            }
+        }
+        chunks.push(Chunk {
+            start: start + 1,
+            end,
+            terms,
+            len,
+        });
+        if end == lines.len() {
+            break;
+        }
+        start += CHUNK_STEP;
+    }
+    chunks
+}
+
+/// Lowercased words; identifiers also yield their `snake_case` and `camelCase` parts,
+/// so `update_feed` matches a question about the feed.
+fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    for word in text.split(|c: char| !c.is_alphanumeric() && c != '_') {
+        if word.len() < 2 {
+            continue;
+        }
+        let lower = word.to_lowercase();
+        let parts = split_identifier(word);
+        if parts.len() > 1 {
+            tokens.extend(parts.into_iter().filter(|p| p.len() >= 2 && *p != lower));
+        }
+        tokens.push(lower);
+    }
+    tokens
+}
+
+fn split_identifier(word: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    for piece in word.split('_').filter(|p| !p.is_empty()) {
+        let mut current = String::new();
+        let mut prev_lower = false;
+        for c in piece.chars() {
+            if c.is_uppercase() && prev_lower && !current.is_empty() {
+                parts.push(std::mem::take(&mut current));
+            }
+            prev_lower = c.is_lowercase() || c.is_ascii_digit();
+            current.extend(c.to_lowercase());
+        }
+        if !current.is_empty() {
+            parts.push(current);
+        }
+    }
+    parts
+}
+
+/// Modification time (ns since the epoch) and size of a file.
+fn stamp(path: &Path) -> Option<(u64, u64)> {
+    let meta = std::fs::metadata(path).ok()?;
+    let modified = meta
+        .modified()
+        .ok()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?
+        .as_nanos() as u64;
+    Some((modified, meta.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize_splits_identifiers() {
+        assert_eq!(
+            tokenize("fn updateFeed(x: FeedManager)"),
+            vec!["fn", "update", "feed", "updatefeed", "feed", "manager", "feedmanager"]
+        );
+        assert_eq!(split_identifier("parse_actions"), vec!["parse", "actions"]);
+    }
+
+    #[test]
+    fn test_index_search_refresh_and_persistence() {
+        let project = tempfile::tempdir().unwrap();
+        let data = tempfile::tempdir().unwrap();
+        let root = project.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(
+            root.join("src/feed.rs"),
+            "struct FeedManager;\nimpl FeedManager {\n    fn update_feed(&self) {}\n}\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("src/parser.rs"), "fn parse_actions(text: &str) {}\n").unwrap();
+        std::fs::write(root.join("logo.png"), b"\x89PNG\0\0").unwrap();
+
+        let mut index = RetrievalIndex::load(data.path(), root);
+        assert!(index.refresh());
+        assert_eq!(index.file_count(), 2);
+        assert!(!index.refresh());
+
+        let hits = index.search("How does the feed get updated?", TOP_K);
+        assert_eq!(hits[0].file, "src/feed.rs");
+        let content = std::fs::read_to_string(root.join("src/feed.rs")).unwrap();
+        let snippet = excerpt(&content, &hits[0], "feed");
+        assert!(snippet.starts_with("[src/feed.rs: lines 1-4]\n1 | struct FeedManager;"));
+
+        index.save(data.path()).unwrap();
+        std::fs::remove_file(root.join("src/feed.rs")).unwrap();
+        let mut reloaded = RetrievalIndex::load(data.path(), root);
+        assert_eq!(reloaded.file_count(), 2);
+        assert!(reloaded.refresh());
+        assert!(reloaded.search("feed", TOP_K).is_empty());
+        assert_eq!(reloaded.search("parse actions", TOP_K)[0].file, "src/parser.rs");
+    }
+
+    #[test]
+    fn test_chunks_overlap() {
+        let text: String = (1..=100).map(|i| format!("line{}\n", i)).collect();
+        let spans: Vec<(usize, usize)> = chunk(&text).iter().map(|c| (c.start, c.end)).collect();
+        assert_eq!(spans, vec![(1, 40), (31, 70), (61, 100)]);
+    }
+}
@@ -0,0 +1,266 @@
+//! # Content Search
+//!
+//! Native grep for the agent (```search``` blocks), so it does not need `run_command grep`.
+//! Walks the tree with `.gitignore` rules applied (also outside git repositories), skips
+//! binary and very large files, and caps the number of reported hits.
+
+use crate::domain::types::SearchQuery;
+use anyhow::{Context, Result};
+use regex::RegexBuilder;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+/// Hits reported before the search stops.
+pub const MAX_HITS: usize = 100;
+/// Upper bound for context lines around a hit.
+pub const MAX_CONTEXT: usize = 5;
+/// Files larger than this are skipped.
+const MAX_FILE_BYTES: u64 = 1_000_000;
+/// Hit lines are cut to this many characters.
+const MAX_LINE_CHARS: usize = 240;
+
+#[derive(Debug, Default, PartialEq)]
+pub struct SearchOutcome {
+    /// grep-style listing: `path:line: text` for hits, `path-line- text` for context
+    pub text: String,
+    pub hits: usize,
+    pub files: usize,
+    /// Stopped at `MAX_HITS`
+    pub truncated: bool,
+}
+
+/// Parses the body of a ```search``` block:
+///
+/// ```text
+/// pattern: fn parse_\w+
+/// path: src
+/// glob: *.rs
+/// mode: literal
+/// case: insensitive
+/// context: 2
+/// ```
+///
+/// A line without a known key is taken as the pattern.
+pub fn parse_query(inline: &str, body: &str) -> SearchQuery {
+    let mut query = SearchQuery {
+        pattern: inline.trim().to_string(),
+        path: ".".to_string(),
+        ..Default::default()
+    };
+
+    for line in body.lines() {
+        let trimmed = line.trim();
+        if let Some(rest) = trimmed.strip_prefix("pattern:") {
+            query.pattern = rest.trim().to_string();
+        } else if let Some(rest) = trimmed.strip_prefix("path:") {
+            query.path = rest.trim().trim_matches('`').to_string();
+        } else if let Some(rest) = trimmed.strip_prefix("glob:") {
+            query.glob = Some(rest.trim().trim_matches(|c| c == '`' || c == '"').to_string());
+        } else if let Some(rest) = trimmed.strip_prefix("mode:") {
+            query.literal = rest.trim().eq_ignore_ascii_case("literal");
+        } else if let Some(rest) = trimmed.strip_prefix("case:") {
+            query.ignore_case = rest.trim().eq_ignore_ascii_case("insensitive");
+        } else if let Some(rest) = trimmed.strip_prefix("context:") {
+            query.context = rest.trim().parse().unwrap_or(0);
+        } else if !trimmed.is_empty() && query.pattern.is_empty() {
+            query.pattern = trimmed.to_string();
+        }
+    }
+
+    query.context = query.context.min(MAX_CONTEXT);
+    if query.path.is_empty() {
+        query.path = ".".to_string();
+    }
+    query
+}
+
+/// Searches `base` (a directory or a single file). Paths in the output are relative to `base`'s
+/// directory joined onto `display_base`. `overlay` holds simulated file contents (dry run) that
+/// shadow the disk, including files that do not exist yet.
+pub fn search(
+    base: &Path,
+    display_base: &str,
+    query: &SearchQuery,
+    overlay: &BTreeMap<PathBuf, String>,
+) -> Result<SearchOutcome> {
+    let pattern = if query.literal {
+        regex::escape(&query.pattern)
+    } else {
+        query.pattern.clone()
+    };
+    let regex = RegexBuilder::new(&pattern)
+        .case_insensitive(query.ignore_case)
+        .build()
+        .context("Invalid search pattern")?;
+    let glob = query
+        .glob
+        .as_deref()
+        .map(glob::Pattern::new)
+        .transpose()
+        .context("Invalid glob pattern")?;
+
+    let walker = ignore::WalkBuilder::new(base)
+        .hidden(false)
+        .require_git(false)
+        .filter_entry(|e| e.file_name() != ".git")
+        .sort_by_file_path(|a, b| a.cmp(b))
+        .build();
+
+    let mut files: Vec<PathBuf> = walker
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_some_and(|t| t.is_file()))
+        .map(|e| e.into_path())
+        .collect();
+    files.extend(overlay.keys().filter(|p| p.starts_with(base) && !p.exists()).cloned());
+    files.sort();
+    files.dedup();
+
+    let mut outcome = SearchOutcome::default();
+    for path in &files {
+        let path = path.as_path();
+        let relative = path.strip_prefix(base).unwrap_or(path);
+        let display = if relative.as_os_str().is_empty() {
+            // `base` is the file itself
+            display_base.to_string()
+        } else if display_base == "." {
+            relative.to_string_lossy().to_string()
+        } else {
+            format!("{}/{}", display_base.trim_end_matches('/'), relative.to_string_lossy())
+        };
+
+        if let Some(glob) = &glob {
+            let name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+            if !glob.matches(&name) && !glob.matches(&display) {
+                continue;
+            }
+        }
+        let content: Cow<str> = match overlay.get(path) {
+            Some(simulated) => Cow::Borrowed(simulated),
+            None => {
+                if std::fs::metadata(path).is_ok_and(|m| m.len() > MAX_FILE_BYTES) {
+                    continue;
+                }
+                let Ok(bytes) = std::fs::read(path) else {
+                    continue;
+                };
+                if bytes.iter().take(8000).any(|b| *b == 0) {
+                    continue; // binary
+                }
+                Cow::Owned(String::from_utf8_lossy(&bytes).into_owned())
+            }
+        };
+        let lines: Vec<&str> = content.lines().collect();
+
+        let hit_lines: Vec<usize> = lines
+            .iter()
+            .enumerate()
+            .filter(|(_, l)| regex.is_match(l))
+            .map(|(i, _)| i)
+            .collect();
+        if hit_lines.is_empty() {
+            continue;
+        }
+        outcome.files += 1;
+
+        let mut last_printed: Option<usize> = None;
+        for &hit in &hit_lines {
+            if outcome.hits >= MAX_HITS {
+                outcome.truncated = true;
+                return Ok(outcome);
+            }
+            outcome.hits += 1;
+
+            let from = hit.saturating_sub(query.context);
+            let to = (hit + query.context).min(lines.len() - 1);
+            if query.context > 0 && last_printed.is_some_and(|p| from > p + 1) {
+                outcome.text.push_str("--\n");
+            }
+            for (i, line) in lines.iter().enumerate().take(to + 1).skip(from) {
+                if last_printed.is_some_and(|p| i <= p) {
+                    continue;
+                }
+                let sep = if hit_lines.binary_search(&i).is_ok() { ':' } else { '-' };
+                let _ = writeln!(outcome.text, "{}{}{}{} {}", display, sep, i + 1, sep, clip(line));
+                last_printed = Some(i);
+            }
+        }
+        if query.context > 0 {
+            outcome.text.push_str("--\n");
+        }
+    }
+    Ok(outcome)
+}
+
+fn clip(line: &str) -> String {
+    let line = line.trim_end();
+    if line.chars().count() > MAX_LINE_CHARS {
+        let cut: String = line.chars().take(MAX_LINE_CHARS).collect();
+        format!("{}…", cut)
+    } else {
+        line.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_query() {
+        let q = parse_query("", "pattern: fn main\npath: src\nglob: *.rs\nmode: literal\ncontext: 9");
+        assert_eq!(q.pattern, "fn main");
+        assert_eq!(q.path, "src");
+        assert_eq!(q.glob.as_deref(), Some("*.rs"));
+        assert!(q.literal);
+        assert_eq!(q.context, MAX_CONTEXT);
+
+        let q = parse_query("TODO", "");
+        assert_eq!((q.pattern.as_str(), q.path.as_str()), ("TODO", "."));
+    }
+
+    #[test]
+    fn test_search_respects_gitignore_glob_and_context() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("target")).unwrap();
+        std::fs::write(root.join(".gitignore"), "target/\n").unwrap();
+        std::fs::write(root.join("src/lib.rs"), "a\nfn parse() {}\nb\n").unwrap();
+        std::fs::write(root.join("src/notes.md"), "fn parse() in docs\n").unwrap();
+        std::fs::write(root.join("target/gen.rs"), "fn parse() {}\n").unwrap();
+
+        let query = SearchQuery {
+            pattern: "fn parse(".into(),
+            path: ".".into(),
+            literal: true,
+            ..Default::default()
+        };
+        let out = search(root, ".", &query, &BTreeMap::new()).unwrap();
+        assert_eq!((out.hits, out.files), (2, 2));
+        assert!(!out.text.contains("target/"));
+
+        let query = SearchQuery {
+            pattern: r"fn \w+".into(),
+            glob: Some("*.rs".into()),
+            context: 1,
+            ..query
+        };
+        let out = search(&root.join("src"), "src", &SearchQuery { literal: false, ..query }, &BTreeMap::new()).unwrap();
+        assert_eq!(out.text, "src/lib.rs-1- a\nsrc/lib.rs:2: fn parse() {}\nsrc/lib.rs-3- b\n--\n");
+    }
+
+    #[test]
+    fn test_hit_cap() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("many.txt"), "x\n".repeat(MAX_HITS + 10)).unwrap();
+        let query = SearchQuery {
+            pattern: "x".into(),
+            ..Default::default()
+        };
+        let out = search(dir.path(), ".", &query, &BTreeMap::new()).unwrap();
+        assert_eq!(out.hits, MAX_HITS);
+        assert!(out.truncated);
+    }
+}
@@ -0,0 +1,185 @@
+//! # File Snapshots
+//!
+//! Undo history for agent writes that works without git.
+//! Before a file is written, its previous content (or the fact that it did not exist) is stored
+//! in a content-addressed object store under the task folder (`snapshots/objects/<sha256>`),
+//! and an entry is appended to `snapshots/index.jsonl` with the engine step that made the write.
+//! Rewinding to step N restores every file touched after N to its content at the end of step N.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const INDEX_FILE: &str = "index.jsonl";
+const OBJECTS_DIR: &str = "objects";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SnapshotEntry {
+    pub step: usize,
+    /// Path relative to the project root
+    pub path: String,
+    /// Object holding the content before the write; `None` if the file did not exist
+    pub object: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SnapshotStore {
+    root: PathBuf,
+    dir: PathBuf,
+}
+
+impl SnapshotStore {
+    /// Store for the project at `root`, kept in `dir` (e.g. `{root}/tasks/003-foo/snapshots`).
+    pub fn new(root: impl AsRef<Path>, dir: impl AsRef<Path>) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Records the current state of `path` (absolute, under the root) before step `step` writes it.
+    /// Only the first write of a file within a step matters, later ones are skipped.
+    pub fn record(&self, step: usize, path: &Path) -> Result<()> {
+        let rel = path
+            .strip_prefix(&self.root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .to_string();
+        let entries = self.entries();
+        if entries.iter().any(|e| e.step == step && e.path == rel) {
+            return Ok(());
+        }
+
+        let object = match std::fs::read(path) {
+            Ok(bytes) => Some(self.store_object(&bytes)?),
+            Err(_) => None,
+        };
+        let entry = SnapshotEntry { step, path: rel, object };
+
+        std::fs::create_dir_all(&self.dir)?;
+        let mut index = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.dir.join(INDEX_FILE))
+            .context("Failed to open snapshot index")?;
+        writeln!(index, "{}", serde_json::to_string(&entry)?)?;
+        Ok(())
+    }
+
+    /// All recorded entries, oldest first.
+    pub fn entries(&self) -> Vec<SnapshotEntry> {
+        std::fs::read_to_string(self.dir.join(INDEX_FILE))
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect()
+    }
+
+    /// Highest step that wrote a file.
+    pub fn last_step(&self) -> Option<usize> {
+        self.entries().iter().map(|e| e.step).max()
+    }
+
+    /// Files written per step, for listing.
+    pub fn steps(&self) -> BTreeMap<usize, Vec<String>> {
+        let mut steps: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+        for entry in self.entries() {
+            steps.entry(entry.step).or_default().push(entry.path);
+        }
+        steps
+    }
+
+    /// What rewinding to the end of `step` means: for each file written after it, the
+    /// project-relative path and the content to restore (`None` = delete the file).
+    pub fn rewind_plan(&self, step: usize) -> Result<Vec<(String, Option<Vec<u8>>)>> {
+        let mut plan: BTreeMap<String, Option<String>> = BTreeMap::new();
+        for entry in self.entries().into_iter().filter(|e| e.step > step) {
+            // The earliest snapshot after `step` holds the state at the end of `step`
+            plan.entry(entry.path).or_insert(entry.object);
+        }
+        plan.into_iter()
+            .map(|(path, object)| {
+                let content = match object {
+                    Some(hash) => Some(
+                        std::fs::read(self.dir.join(OBJECTS_DIR).join(&hash))
+                            .with_context(|| format!("Missing snapshot object {}", hash))?,
+                    ),
+                    None => None,
+                };
+                Ok((path, content))
+            })
+            .collect()
+    }
+
+    /// Forgets the entries after `step` once they have been restored.
+    pub fn truncate(&self, step: usize) -> Result<()> {
+        let kept: Vec<String> = self
+            .entries()
+            .into_iter()
+            .filter(|e| e.step <= step)
+            .filter_map(|e| serde_json::to_string(&e).ok())
+            .collect();
+        let mut content = kept.join("\n");
+        if !content.is_empty() {
+            content.push('\n');
+        }
+        std::fs::write(self.dir.join(INDEX_FILE), content).context("Failed to rewrite snapshot index")
+    }
+
+    fn store_object(&self, bytes: &[u8]) -> Result<String> {
+        let hash = format!("{:x}", Sha256::digest(bytes));
+        let objects = self.dir.join(OBJECTS_DIR);
+        let path = objects.join(&hash);
+        if !path.exists() {
+            std::fs::create_dir_all(&objects)?;
+            std::fs::write(&path, bytes).context("Failed to write snapshot object")?;
+        }
+        Ok(hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_rewind_plan() {
+        let project = tempfile::tempdir().unwrap();
+        let root = project.path();
+        let store = SnapshotStore::new(root, root.join("tasks/001-x/snapshots"));
+        let a = root.join("a.txt");
+
+        // Step 1 creates a.txt, step 2 changes it twice, step 3 adds b.txt
+        store.record(1, &a).unwrap();
+        std::fs::write(&a, "v1").unwrap();
+        store.record(2, &a).unwrap();
+        std::fs::write(&a, "v2").unwrap();
+        store.record(2, &a).unwrap();
+        std::fs::write(&a, "v3").unwrap();
+        store.record(3, &root.join("b.txt")).unwrap();
+
+        assert_eq!(store.last_step(), Some(3));
+        assert_eq!(store.entries().len(), 3);
+
+        let plan = store.rewind_plan(1).unwrap();
+        assert_eq!(
+            plan,
+            vec![("a.txt".to_string(), Some(b"v1".to_vec())), ("b.txt".to_string(), None)]
+        );
+        assert_eq!(store.rewind_plan(0).unwrap()[0], ("a.txt".to_string(), None));
+
+        store.truncate(1).unwrap();
+        assert_eq!(store.last_step(), Some(1));
+    }
+}
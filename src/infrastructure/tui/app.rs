@@ -81,31 +81,30 @@ impl TuiApp {
 
             terminal.draw(|f| self.draw(f))?;
 
-            if event::poll(std::time::Duration::from_millis(100))? {
-                if let Event::Key(key) = event::read()? {
-                     if key.kind == KeyEventKind::Press {
-                        match key.code {
-                            KeyCode::Char('q') | KeyCode::Char('c') if key.modifiers.contains(crossterm::event::KeyModifiers::CONTROL) => {
-                                self.should_quit = true;
-                            }
-                            KeyCode::Tab | KeyCode::Right => {
-                                self.next_tab().await;
-                            }
-                            KeyCode::BackTab | KeyCode::Left => {
-                                self.prev_tab().await;
-                            }
-                            KeyCode::Char(c) => {
-                                self.input_buffer.push(c);
-                            }
-                            KeyCode::Backspace => {
-                                self.input_buffer.pop();
-                            }
-                            KeyCode::Enter => {
-                                self.handle_submit().await;
-                            }
-                            _ => {}
-                        }
+            if event::poll(std::time::Duration::from_millis(100))?
+                && let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+            {
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Char('c') if key.modifiers.contains(crossterm::event::KeyModifiers::CONTROL) => {
+                        self.should_quit = true;
+                    }
+                    KeyCode::Tab | KeyCode::Right => {
+                        self.next_tab().await;
+                    }
+                    KeyCode::BackTab | KeyCode::Left => {
+                        self.prev_tab().await;
+                    }
+                    KeyCode::Char(c) => {
+                        self.input_buffer.push(c);
+                    }
+                    KeyCode::Backspace => {
+                        self.input_buffer.pop();
+                    }
+                    KeyCode::Enter => {
+                        self.handle_submit().await;
                     }
+                    _ => {}
                 }
             }
 
@@ -140,16 +139,16 @@ impl TuiApp {
         
         // This blocking lock in draw is suboptimal but okay for TUI
         if let Ok(guard) = self.state.try_lock() {
-             for (id, _room) in &guard.rooms {
+             for id in guard.rooms.keys() {
                  // Resolve Friendly Name from Config (Reverse Lookup)
                  let mut display_name = id.clone();
                  for (bridge_name, entries) in &self.config.bridges {
                      for entry in entries {
-                         if let Some(channel) = &entry.channel {
-                             if channel == id {
-                                 display_name = bridge_name.clone();
-                                 break;
-                             }
+                         if let Some(channel) = &entry.channel
+                             && channel == id
+                         {
+                             display_name = bridge_name.clone();
+                             break;
                          }
                      }
                      if display_name != *id { break; }
@@ -226,11 +225,11 @@ impl TuiApp {
                  let mut display_name = (*id).clone();
                  for (bridge_name, entries) in &self.config.bridges {
                      for entry in entries {
-                         if let Some(channel) = &entry.channel {
-                             if channel == *id {
-                                 display_name = bridge_name.clone();
-                                 break;
-                             }
+                         if let Some(channel) = &entry.channel
+                             && channel == *id
+                         {
+                             display_name = bridge_name.clone();
+                             break;
                          }
                      }
                      if display_name != **id { break; }
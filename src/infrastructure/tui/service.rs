@@ -7,6 +7,7 @@ pub struct TuiService {
     pub room_id: String,
 }
 
+#[allow(dead_code)]
 impl TuiService {
     pub fn new(room_id: String) -> Self {
         Self { room_id }
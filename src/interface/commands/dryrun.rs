@@ -0,0 +1,77 @@
+//! # Dry Run Command
+//!
+//! Handles `.dryrun [on|off|diff]`.
+//! While dry run is on, tasks in this room record file writes in memory and log shell commands
+//! instead of running them. `.dryrun off` discards the simulated changes.
+
+use crate::application::state::BotState;
+use crate::domain::config::AppConfig;
+use crate::domain::traits::ChatProvider;
+use crate::infrastructure::tools::executor::SharedToolExecutor;
+use anyhow::Result;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+pub async fn handle_dryrun(
+    config: &AppConfig,
+    state: &Arc<Mutex<BotState>>,
+    tools: SharedToolExecutor,
+    chat: &impl ChatProvider,
+    args: &str,
+) -> Result<()> {
+    // Same root the engine resolves agent paths against
+    let root = {
+        let guard = state.lock().await;
+        guard
+            .rooms
+            .get(&chat.room_id())
+            .and_then(|r| r.current_working_dir.clone())
+            .or_else(|| config.system.projects_dir.clone())
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("."))
+    };
+
+    let msg = match args.trim() {
+        "on" => {
+            set_flag(state, &chat.room_id(), true).await;
+            crate::strings::messages::DRY_RUN_ON.to_string()
+        }
+        "off" => {
+            set_flag(state, &chat.room_id(), false).await;
+            let discarded = tools.lock().await.end_dry_run(&root);
+            let files = discarded.map(|o| o.stats().0).unwrap_or(0);
+            crate::strings::messages::dry_run_off(files)
+        }
+        "diff" => match tools.lock().await.dry_run_overlay(&root) {
+            Some(overlay) => {
+                crate::strings::messages::dry_run_diff(&overlay.diff(&root), &overlay.commands)
+            }
+            None => crate::strings::messages::DRY_RUN_NOTHING.to_string(),
+        },
+        "" => {
+            let enabled = {
+                let guard = state.lock().await;
+                guard.rooms.get(&chat.room_id()).is_some_and(|r| r.dry_run)
+            };
+            let stats = tools
+                .lock()
+                .await
+                .dry_run_overlay(&root)
+                .map(|o| o.stats());
+            crate::strings::messages::dry_run_status(enabled, stats)
+        }
+        _ => crate::strings::messages::DRY_RUN_USAGE.to_string(),
+    };
+
+    chat.send_message(&msg)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    Ok(())
+}
+
+async fn set_flag(state: &Arc<Mutex<BotState>>, room_id: &str, enabled: bool) {
+    let mut guard = state.lock().await;
+    guard.get_room_state(room_id).dry_run = enabled;
+    guard.save();
+}
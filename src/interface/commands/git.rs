@@ -0,0 +1,153 @@
+//! # Git Commands
+//!
+//! Handles `.changes`, `.commit [msg]` and `.discard`.
+//! Tasks run on their own `task/NNN-slug` branch; these commands work relative to the commit
+//! the task started from (`RoomState::task_base`).
+
+use crate::application::state::BotState;
+use crate::domain::traits::ChatProvider;
+use crate::infrastructure::tools::git::{GitRepo, task_branch};
+use anyhow::Result;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Outcome of setting up a task branch.
+pub enum TaskBranch {
+    /// The project is on the task's branch; `base` is the commit `.discard` resets to
+    Started { base: String },
+    /// The task runs without a branch; the reason is shown to the user
+    Skipped(String),
+}
+
+/// Puts the project on the task's branch and commits the task scaffolding. Only projects that
+/// are a repository of their own with a clean tree get a branch: the user's own uncommitted
+/// work must never end up in (and be discarded with) the task.
+pub async fn start_task_branch(workdir: &str, task_rel: &str) -> Result<TaskBranch> {
+    let repo = GitRepo::new(workdir);
+    if !repo.is_own_repo().await {
+        return Ok(TaskBranch::Skipped(crate::strings::messages::GIT_NOT_OWN_REPO.to_string()));
+    }
+    if !repo.has_commits().await {
+        return Ok(TaskBranch::Skipped(crate::strings::messages::GIT_NO_COMMITS.to_string()));
+    }
+    repo.exclude(crate::domain::paths::CHECKPOINT_FILE)?;
+    repo.exclude(&format!("tasks/*/{}/", crate::domain::paths::SNAPSHOTS_DIR))?;
+    let dirty = repo.uncommitted(&[task_rel]).await?;
+    if !dirty.is_empty() {
+        return Ok(TaskBranch::Skipped(crate::strings::messages::git_dirty_tree(&dirty)));
+    }
+
+    let base = repo.head().await?;
+    repo.checkout_branch(&task_branch(task_rel)).await?;
+    let folder = task_rel.rsplit('/').next().unwrap_or(task_rel);
+    repo.commit_all(&format!("Start task {}", folder)).await?;
+    Ok(TaskBranch::Started { base })
+}
+
+pub async fn handle_changes(state: &Arc<Mutex<BotState>>, chat: &impl ChatProvider) -> Result<()> {
+    let Some((workdir, base)) = project_and_base(state, chat).await else {
+        let _ = chat
+            .send_notification(crate::strings::messages::NOT_IN_PROJECT)
+            .await;
+        return Ok(());
+    };
+    let repo = GitRepo::new(&workdir);
+    let base = base.unwrap_or_else(|| "HEAD".to_string());
+
+    let msg = match repo.diffstat(&base).await {
+        Ok(stat) if stat.is_empty() => crate::strings::messages::GIT_NO_CHANGES.to_string(),
+        Ok(stat) => crate::strings::messages::git_changes(&stat),
+        Err(e) => crate::strings::messages::git_failed(&e.to_string()),
+    };
+    chat.send_message(&msg)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    Ok(())
+}
+
+pub async fn handle_commit(
+    state: &Arc<Mutex<BotState>>,
+    chat: &impl ChatProvider,
+    args: &str,
+) -> Result<()> {
+    let Some((workdir, _)) = project_and_base(state, chat).await else {
+        let _ = chat
+            .send_notification(crate::strings::messages::NOT_IN_PROJECT)
+            .await;
+        return Ok(());
+    };
+    let message = match args.trim() {
+        "" => "Manual commit",
+        msg => msg,
+    };
+
+    let repo = GitRepo::new(&workdir);
+    if !repo.is_own_repo().await {
+        let _ = chat
+            .send_notification(crate::strings::messages::GIT_NOT_OWN_REPO)
+            .await;
+        return Ok(());
+    }
+    let msg = match repo.commit_all(message).await {
+        Ok(Some(hash)) => crate::strings::messages::git_committed(&hash, message),
+        Ok(None) => crate::strings::messages::GIT_NOTHING_TO_COMMIT.to_string(),
+        Err(e) => crate::strings::messages::git_failed(&e.to_string()),
+    };
+    let _ = chat.send_notification(&msg).await;
+    Ok(())
+}
+
+pub async fn handle_discard(state: &Arc<Mutex<BotState>>, chat: &impl ChatProvider) -> Result<()> {
+    let running = {
+        let guard = state.lock().await;
+        guard
+            .rooms
+            .get(&chat.room_id())
+            .is_some_and(|r| r.is_task_running())
+    };
+    if running {
+        let _ = chat
+            .send_notification(crate::strings::messages::TASK_ALREADY_RUNNING)
+            .await;
+        return Ok(());
+    }
+
+    let Some((workdir, base)) = project_and_base(state, chat).await else {
+        let _ = chat
+            .send_notification(crate::strings::messages::NOT_IN_PROJECT)
+            .await;
+        return Ok(());
+    };
+    let Some(base) = base else {
+        let _ = chat
+            .send_notification(crate::strings::messages::GIT_NO_TASK_BASE)
+            .await;
+        return Ok(());
+    };
+
+    let msg = match GitRepo::new(&workdir).reset_to(&base).await {
+        Ok(()) => {
+            let mut guard = state.lock().await;
+            // The checkpoint describes work that no longer exists
+            guard.get_room_state(&chat.room_id()).clear_checkpoint();
+            crate::strings::messages::git_discarded(&base)
+        }
+        Err(e) => crate::strings::messages::git_failed(&e.to_string()),
+    };
+    let _ = chat.send_notification(&msg).await;
+    Ok(())
+}
+
+/// Project directory of the room and the base commit of its current task.
+async fn project_and_base(
+    state: &Arc<Mutex<BotState>>,
+    chat: &impl ChatProvider,
+) -> Option<(String, Option<String>)> {
+    let guard = state.lock().await;
+    let room = guard.rooms.get(&chat.room_id())?;
+    let workdir = room
+        .current_working_dir
+        .clone()
+        .or_else(|| room.current_project_path.clone())?;
+    Some((workdir, room.task_base.clone()))
+}
@@ -6,6 +6,7 @@
 use crate::application::state::BotState;
 use crate::domain::traits::{ChatProvider, LlmProvider};
 use crate::infrastructure::tools::executor::SharedToolExecutor;
+use crate::infrastructure::tools::resolver::PathResolver;
 use anyhow::Result;
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -31,11 +32,13 @@ pub async fn handle_status(
         config.system.projects_dir.as_deref(),
     );
 
+    let prompts = crate::strings::prompts::PromptSet::load(room_state.current_working_dir.as_deref());
     let msg = crate::strings::messages::room_status_msg(
         &project,
         &cwd,
         room_state.active_model.as_deref().unwrap_or("Default"),
         room_state.active_agent.as_deref().unwrap_or("Default"),
+        &crate::strings::messages::prompt_sources(&prompts),
     );
 
     // Save state if it was created
@@ -274,21 +277,27 @@ pub async fn handle_read(
         room_state.and_then(|r| r.current_working_dir.clone())
     };
 
-    let resolved_path = if let Some(wd) = cwd {
-        if std::path::Path::new(path).is_absolute() {
-            path.to_string()
-        } else {
-            format!("{}/{}", wd, path)
+    // Inside a project, paths go through the same sandbox resolver the agent uses
+    let (resolved_path, label) = if let Some(wd) = cwd {
+        let resolver = PathResolver::new(&wd);
+        match resolver.resolve(path) {
+            Ok(p) => (p.to_string_lossy().to_string(), resolver.display(path)),
+            Err(e) => {
+                chat.send_notification(&crate::strings::messages::file_read_failed(&e.to_string()))
+                    .await
+                    .map_err(|e| anyhow::anyhow!(e))?;
+                return Ok(());
+            }
         }
     } else {
-        path.to_string()
+        (path.to_string(), path.to_string())
     };
 
     let client = tools.lock().await;
     match client.read_file(&resolved_path).await {
         Ok(content) => {
             chat.send_message(&crate::strings::messages::file_read_success(
-                &resolved_path,
+                &label,
                 &content,
             ))
             .await
@@ -5,10 +5,18 @@
 
 pub mod admin;
 pub mod agent;
+pub mod dryrun;
+pub mod git;
 pub mod help;
 pub mod misc;
 pub mod new;
 pub mod project;
+pub mod queue;
+pub mod roadmap;
+pub mod resume;
+pub mod say;
 pub mod start;
 pub mod task;
+pub mod undo;
+pub mod verify;
 pub mod wizard;
@@ -0,0 +1,194 @@
+//! # Queue Command
+//!
+//! Handles `.queue`, `.queue rm <n>` and `.queue move <from> <to>`.
+//! Tasks submitted while another one is running wait here and start automatically, in order.
+
+use crate::application::engine::ExecutionEngine;
+use crate::application::state::{BotState, RoomState, TaskPhase};
+use crate::domain::config::AppConfig;
+use crate::domain::traits::ChatProvider;
+use anyhow::Result;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+pub async fn handle_queue(
+    state: &Arc<Mutex<BotState>>,
+    chat: &impl ChatProvider,
+    args: &str,
+) -> Result<()> {
+    let parts: Vec<&str> = args.split_whitespace().collect();
+
+    let msg = {
+        let mut guard = state.lock().await;
+        let room = guard.get_room_state(&chat.room_id());
+        let queue = &mut room.task_queue;
+
+        let msg = match parts.as_slice() {
+            [] if queue.is_empty() => crate::strings::messages::QUEUE_EMPTY.to_string(),
+            [] => crate::strings::messages::queue_listing(queue),
+            ["rm", n] => match parse_position(n, queue.len()) {
+                Some(i) => crate::strings::messages::queue_removed(&queue.remove(i)),
+                None => crate::strings::messages::queue_invalid_index(n),
+            },
+            ["move", from, to] => match (
+                parse_position(from, queue.len()),
+                parse_position(to, queue.len()),
+            ) {
+                (Some(f), Some(t)) => {
+                    let item = queue.remove(f);
+                    let msg = crate::strings::messages::queue_moved(&item, t + 1);
+                    queue.insert(t, item);
+                    msg
+                }
+                (None, _) => crate::strings::messages::queue_invalid_index(from),
+                (_, None) => crate::strings::messages::queue_invalid_index(to),
+            },
+            _ => crate::strings::messages::QUEUE_USAGE.to_string(),
+        };
+
+        guard.save();
+        msg
+    };
+
+    chat.send_message(&msg)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    Ok(())
+}
+
+/// Adds a task to the end of the room's queue and returns its 1-based position.
+pub async fn enqueue_task(state: &Arc<Mutex<BotState>>, room_id: &str, task: &str) -> usize {
+    let mut guard = state.lock().await;
+    let room = guard.get_room_state(room_id);
+    room.task_queue.push(task.to_string());
+    let position = room.task_queue.len();
+    guard.save();
+    position
+}
+
+/// Removes and returns the next queued task, if any.
+pub async fn pop_next_task(state: &Arc<Mutex<BotState>>, room_id: &str) -> Option<String> {
+    let mut guard = state.lock().await;
+    let room = guard.get_room_state(room_id);
+    if room.task_queue.is_empty() {
+        return None;
+    }
+    let next = room.task_queue.remove(0);
+    room.queue_running = true;
+    guard.save();
+    Some(next)
+}
+
+/// What a room runs once a task run has ended.
+#[derive(Debug, PartialEq)]
+pub enum NextRun {
+    /// Execute the plan that just finished
+    Execute,
+    /// Start this task, taken off the queue
+    Task(String),
+    /// Nothing is waiting; the inactivity auto-start still applies
+    Idle,
+}
+
+/// Decides what follows a run that ended in `room`. Only rooms working through the queue go on
+/// by themselves: a plan that just finished is executed first, anything else (a finished
+/// execution, max steps, stagnation, an error, `.stop`) hands over to the next queued task.
+pub fn next_run(room: &mut RoomState, plan_ready: bool) -> NextRun {
+    let next = if plan_ready && (room.queue_running || !room.task_queue.is_empty()) {
+        NextRun::Execute
+    } else if room.task_queue.is_empty() {
+        NextRun::Idle
+    } else {
+        NextRun::Task(room.task_queue.remove(0))
+    };
+    room.queue_running = next != NextRun::Idle;
+    next
+}
+
+/// Starts whatever follows a run that just ended, however it ended. `returned` is whether the
+/// run came back with a final message, which in the planning phase means the plan is done.
+/// Boxed because it starts a new run from inside the one that ended.
+pub fn start_next<'a, C>(
+    config: &'a AppConfig,
+    state: &'a Arc<Mutex<BotState>>,
+    engine: &'a ExecutionEngine,
+    chat: &'a C,
+    returned: bool,
+) -> std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send + 'a>>
+where
+    C: ChatProvider + Clone + Send + Sync + 'static,
+{
+    Box::pin(async move {
+        let (next, workdir, feed) = {
+            let mut guard = state.lock().await;
+            let room = guard.get_room_state(&chat.room_id());
+            let plan_ready =
+                returned && matches!(room.task_phase, TaskPhase::Planning | TaskPhase::NewProject);
+            let next = next_run(room, plan_ready);
+            if next != NextRun::Idle {
+                // Starting now, so the inactivity timer must not start it a second time
+                room.task_completion_time = None;
+            }
+            let workdir = room
+                .current_working_dir
+                .clone()
+                .or_else(|| room.current_project_path.clone());
+            let feed = room.feed_manager.clone();
+            guard.save();
+            (next, workdir, feed)
+        };
+        if next != NextRun::Idle
+            && let Some(feed) = feed
+        {
+            feed.lock().await.auto_start_timestamp = None;
+        }
+
+        let started = match next {
+            NextRun::Idle => Ok(()),
+            NextRun::Execute => {
+                super::start::handle_start(config, state, engine, chat, workdir).await
+            }
+            NextRun::Task(task) => {
+                super::task::handle_task(config, state, engine, chat, &task, None, workdir, true)
+                    .await
+            }
+        };
+        if let Err(e) = started {
+            tracing::error!("Starting the next queued task failed: {}", e);
+            let _ = chat
+                .send_notification(&crate::strings::messages::queue_start_failed(&e.to_string()))
+                .await;
+        }
+    })
+}
+
+/// Parses a 1-based queue position into an index.
+fn parse_position(s: &str, len: usize) -> Option<usize> {
+    s.parse::<usize>().ok().filter(|n| *n >= 1 && *n <= len).map(|n| n - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queue_runs_tasks_in_turn() {
+        let mut room = RoomState {
+            task_queue: vec!["first".to_string(), "second".to_string()],
+            ..Default::default()
+        };
+
+        // The running task hit max steps (or failed, or was stopped): the first queued one starts
+        assert_eq!(next_run(&mut room, false), NextRun::Task("first".to_string()));
+        // Its plan is done: it is executed before anything else starts
+        assert_eq!(next_run(&mut room, true), NextRun::Execute);
+        // Its execution ended: the second one starts and runs through the same way
+        assert_eq!(next_run(&mut room, false), NextRun::Task("second".to_string()));
+        assert_eq!(next_run(&mut room, true), NextRun::Execute);
+        assert!(room.task_queue.is_empty());
+        assert_eq!(next_run(&mut room, false), NextRun::Idle);
+
+        // Outside the queue a finished plan waits for `.start` as before
+        assert_eq!(next_run(&mut room, true), NextRun::Idle);
+    }
+}
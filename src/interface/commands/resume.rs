@@ -0,0 +1,166 @@
+//! # Resume Command
+//!
+//! Handles the `.pause` and `.resume` commands.
+//! `.pause` parks the running task after its current action; `.resume` continues a paused task,
+//! or restarts a task that was interrupted (e.g. by a bot restart) from its last checkpoint.
+
+use crate::application::engine::ExecutionEngine;
+use crate::application::state::BotState;
+use crate::domain::config::AppConfig;
+use crate::domain::traits::ChatProvider;
+use anyhow::Result;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+pub async fn handle_pause(state: &Arc<Mutex<BotState>>, chat: &impl ChatProvider) -> Result<()> {
+    let pause_tx = {
+        let guard = state.lock().await;
+        guard
+            .rooms
+            .get(&chat.room_id())
+            .filter(|r| r.is_task_running())
+            .and_then(|r| r.pause_tx.clone())
+    };
+
+    let msg = match pause_tx {
+        Some(tx) => {
+            tx.send_replace(true);
+            crate::strings::messages::PAUSE_REQUESTED
+        }
+        None => crate::strings::messages::NO_RUNNING_TASK,
+    };
+    let _ = chat.send_notification(msg).await;
+    Ok(())
+}
+
+pub async fn handle_resume<C>(
+    config: &AppConfig,
+    state: &Arc<Mutex<BotState>>,
+    engine: &ExecutionEngine,
+    chat: &C,
+) -> Result<()>
+where
+    C: ChatProvider + Clone + Send + Sync + 'static,
+{
+    // A paused run is still alive: just flip the switch back
+    let paused_tx = {
+        let guard = state.lock().await;
+        guard
+            .rooms
+            .get(&chat.room_id())
+            .filter(|r| r.is_paused())
+            .and_then(|r| r.pause_tx.clone())
+    };
+    if let Some(tx) = paused_tx {
+        tx.send_replace(false);
+        let _ = chat
+            .send_notification(crate::strings::messages::TASK_UNPAUSED)
+            .await;
+        return Ok(());
+    }
+
+    let running = {
+        let guard = state.lock().await;
+        guard
+            .rooms
+            .get(&chat.room_id())
+            .is_some_and(|r| r.is_task_running())
+    };
+    if running {
+        let _ = chat
+            .send_notification(crate::strings::messages::TASK_ALREADY_RUNNING)
+            .await;
+        return Ok(());
+    }
+
+    let checkpoint = {
+        let mut guard = state.lock().await;
+        let room = guard.get_room_state(&chat.room_id());
+        room.load_checkpoint()
+    };
+
+    let Some(checkpoint) = checkpoint else {
+        let _ = chat
+            .send_notification(crate::strings::messages::NO_CHECKPOINT)
+            .await;
+        return Ok(());
+    };
+
+    // Hand the checkpoint to the engine and restore the phase it was in
+    {
+        let mut guard = state.lock().await;
+        let room = guard.get_room_state(&chat.room_id());
+        room.task_phase = checkpoint.phase.clone();
+        room.stop_requested = false;
+        room.task_completion_time = None;
+        room.resume_checkpoint = Some(checkpoint.clone());
+    }
+
+    let engine_clone = engine.clone();
+    let chat_clone = chat.clone();
+    let config_owned = config.clone();
+    let state_owned = state.clone();
+
+    let handle = tokio::spawn(async move {
+        let returned = match engine_clone
+            .run_task(
+                &chat_clone,
+                &checkpoint.task,
+                checkpoint.display_task.as_deref(),
+                &checkpoint.agent_name,
+                checkpoint.working_dir.clone(),
+                None,
+                None,
+            )
+            .await
+        {
+            Ok(result) => result.is_some(),
+            Err(e) => {
+                let _ = chat_clone
+                    .send_notification(&crate::strings::messages::task_failed(&e.to_string()))
+                    .await;
+                false
+            }
+        };
+        crate::interface::commands::queue::start_next(
+            &config_owned,
+            &state_owned,
+            &engine_clone,
+            &chat_clone,
+            returned,
+        )
+        .await;
+    });
+
+    {
+        let mut guard = state.lock().await;
+        let room = guard.get_room_state(&chat.room_id());
+        room.task_handle = Some(Arc::new(Mutex::new(Some(handle))));
+    }
+
+    Ok(())
+}
+
+/// Tells the room that a task was interrupted by a restart and can be resumed.
+/// Called once on startup for every joined room.
+pub async fn announce_interrupted_task(
+    state: &Arc<Mutex<BotState>>,
+    chat: &impl ChatProvider,
+) -> Result<()> {
+    let checkpoint = {
+        let guard = state.lock().await;
+        guard
+            .rooms
+            .get(&chat.room_id())
+            .and_then(|r| r.load_checkpoint())
+    };
+
+    if let Some(cp) = checkpoint {
+        let task = cp.display_task.as_deref().unwrap_or(&cp.task);
+        let summary = task.lines().next().unwrap_or(task);
+        chat.send_notification(&crate::strings::messages::task_interrupted(summary, cp.steps))
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,93 @@
+//! # Roadmap Command
+//!
+//! Handles `.roadmap`, `.roadmap next <n>` and `.roadmap skip <n>`.
+//! Shows the milestones of `tasks/specs/roadmap.md` with their progress and lets the user
+//! choose what `.start` picks up next.
+
+use crate::application::state::BotState;
+use crate::domain::roadmap::{self, Roadmap};
+use crate::domain::traits::ChatProvider;
+use anyhow::Result;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+pub async fn handle_roadmap(
+    state: &Arc<Mutex<BotState>>,
+    chat: &impl ChatProvider,
+    args: &str,
+) -> Result<()> {
+    let workdir = {
+        let guard = state.lock().await;
+        guard
+            .rooms
+            .get(&chat.room_id())
+            .and_then(|r| r.current_working_dir.clone())
+    };
+    let Some(wd) = workdir else {
+        let _ = chat
+            .send_notification("⚠️ You are not in a valid project directory.")
+            .await;
+        return Ok(());
+    };
+
+    let path = crate::domain::paths::roadmap_path(&wd);
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        let _ = chat
+            .send_notification(crate::strings::messages::NO_ROADMAP)
+            .await;
+        return Ok(());
+    };
+
+    let parts: Vec<&str> = args.split_whitespace().collect();
+    let msg = match parts.as_slice() {
+        [] => {
+            let folders = task_folders(&wd);
+            crate::strings::messages::roadmap_listing(&Roadmap::parse(&content), &folders)
+        }
+        [action @ ("next" | "skip"), n] => {
+            let Some(index) = n.parse::<usize>().ok().and_then(|n| n.checked_sub(1)) else {
+                chat.send_message(crate::strings::messages::ROADMAP_USAGE)
+                    .await
+                    .map_err(|e| anyhow::anyhow!(e))?;
+                return Ok(());
+            };
+            let updated = if *action == "next" {
+                roadmap::prioritize(&content, index)
+            } else {
+                roadmap::skip(&content, index)
+            };
+            match updated {
+                Ok(updated) => {
+                    std::fs::write(&path, &updated)?;
+                    let title = &Roadmap::parse(&content).milestones[index].title;
+                    if *action == "next" {
+                        crate::strings::messages::roadmap_prioritized(title)
+                    } else {
+                        crate::strings::messages::roadmap_skipped(title)
+                    }
+                }
+                Err(reason) => crate::strings::messages::roadmap_error(&reason),
+            }
+        }
+        _ => crate::strings::messages::ROADMAP_USAGE.to_string(),
+    };
+
+    chat.send_message(&msg)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    Ok(())
+}
+
+/// Names of the task folders (`NNN-slug`) in the project.
+fn task_folders(workdir: &str) -> Vec<String> {
+    std::fs::read_dir(std::path::Path::new(workdir).join("tasks"))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.path().is_dir())
+                .filter_map(|e| e.file_name().to_str().map(str::to_string))
+                .filter(|name| name.split('-').next().is_some_and(|id| id.parse::<u32>().is_ok()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
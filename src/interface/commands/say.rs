@@ -0,0 +1,46 @@
+//! # Say Command
+//!
+//! Handles `.say <text>`.
+//! Sends guidance to the running task; the engine adds it to the transcript before its next LLM call.
+
+use crate::application::state::BotState;
+use crate::domain::traits::ChatProvider;
+use anyhow::Result;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+pub async fn handle_say(
+    state: &Arc<Mutex<BotState>>,
+    chat: &impl ChatProvider,
+    args: &str,
+) -> Result<()> {
+    let text = args.trim();
+    if text.is_empty() {
+        let _ = chat
+            .send_notification(crate::strings::messages::SAY_USAGE)
+            .await;
+        return Ok(());
+    }
+
+    let input_tx = {
+        let guard = state.lock().await;
+        guard
+            .rooms
+            .get(&chat.room_id())
+            .filter(|r| r.is_task_running())
+            .and_then(|r| r.input_tx.clone())
+    };
+
+    let delivered = match input_tx {
+        Some(tx) => tx.send(text.to_string()).await.is_ok(),
+        None => false,
+    };
+
+    let msg = if delivered {
+        crate::strings::messages::GUIDANCE_QUEUED
+    } else {
+        crate::strings::messages::NO_RUNNING_TASK
+    };
+    let _ = chat.send_notification(msg).await;
+    Ok(())
+}
@@ -46,32 +46,32 @@ where
         }
 
         // Auto-detect Start Task if None (e.g. after Architect init)
-        if active_task.is_none() {
-            if let Some(wd) = &workdir {
-                 let tasks_dir = std::path::Path::new(wd).join("tasks");
-                 if tasks_dir.exists() {
-                     if let Ok(entries) = std::fs::read_dir(tasks_dir) {
-                         let mut dirs: Vec<String> = entries
-                             .filter_map(|e| e.ok())
-                             .filter(|e| e.path().is_dir())
-                             .map(|e| e.file_name().to_string_lossy().to_string())
-                             .filter(|name| name != "specs")
-                             .collect();
-                         dirs.sort();
-                         
-                         if let Some(first) = dirs.first() {
-                             let new_task = format!("tasks/{}", first);
-                             
-                             // Update Local Var
-                             active_task = Some(new_task.clone());
-
-                             // Update State
-                             let mut guard = state.lock().await;
-                             let room = guard.get_room_state(&chat.room_id());
-                             room.active_task = Some(new_task);
-                         }
-                     }
-                 }
+        if active_task.is_none()
+            && let Some(wd) = &workdir
+        {
+            let tasks_dir = std::path::Path::new(wd).join("tasks");
+            if tasks_dir.exists()
+                && let Ok(entries) = std::fs::read_dir(tasks_dir)
+            {
+                let mut dirs: Vec<String> = entries
+                    .filter_map(|e| e.ok())
+                    .filter(|e| e.path().is_dir())
+                    .map(|e| e.file_name().to_string_lossy().to_string())
+                    .filter(|name| name != "specs")
+                    .collect();
+                dirs.sort();
+                
+                if let Some(first) = dirs.first() {
+                    let new_task = format!("tasks/{}", first);
+                    
+                    // Update Local Var
+                    active_task = Some(new_task.clone());
+
+                    // Update State
+                    let mut guard = state.lock().await;
+                    let room = guard.get_room_state(&chat.room_id());
+                    room.active_task = Some(new_task);
+                }
             }
         }
 
@@ -88,10 +88,12 @@ where
         let engine_clone = engine.clone();
         let chat_clone = chat.clone();
         let workdir_owned = workdir.clone();
+        let config_owned = config.clone();
+        let state_owned = state.clone();
 
         // Spawn new task loop
         let handle = tokio::spawn(async move {
-            match engine_clone
+            let returned = match engine_clone
                 .run_task(
                     &chat_clone,
                     &task_str,
@@ -103,16 +105,26 @@ where
                 )
                 .await
             {
-                Ok(_) => {
+                Ok(result) => {
                     // Task loop finished. 
                     // Feed handles "Task Complete" display.
+                    result.is_some()
                 }
                 Err(e) => {
                     let _ = chat_clone
                         .send_notification(&crate::strings::messages::task_failed(&e.to_string()))
                         .await;
+                    false
                 }
-            }
+            };
+            crate::interface::commands::queue::start_next(
+                &config_owned,
+                &state_owned,
+                &engine_clone,
+                &chat_clone,
+                returned,
+            )
+            .await;
         });
 
         // Update handle in state
@@ -121,6 +133,21 @@ where
             let room = guard.get_room_state(&chat.room_id());
             room.task_handle = Some(Arc::new(Mutex::new(Some(handle))));
         }
+    } else if let Some(queued) =
+        crate::interface::commands::queue::pop_next_task(state, &chat.room_id()).await
+    {
+        // Queued tasks take precedence over the roadmap
+        crate::interface::commands::task::handle_task(
+            config,
+            state,
+            engine,
+            chat,
+            &queued,
+            None,
+            workdir,
+            true,
+        )
+        .await?;
     } else {
         // Smart Start Logic: Look for next milestone
         if let Some(wd) = workdir {
@@ -129,17 +156,11 @@ where
 
              if roadmap_path.exists() {
                  let content = std::fs::read_to_string(roadmap_path).unwrap_or_default();
-                 let mut next_task = None;
-                 
-                 for line in content.lines() {
-                     let trimmed = line.trim();
-                     if trimmed.starts_with("- [ ]") {
-                         // Found unchecked task
-                         // Extract text
-                         next_task = Some(trimmed[5..].trim().to_string());
-                         break;
-                     }
-                 }
+                 let roadmap = crate::domain::roadmap::Roadmap::parse(&content);
+                 // First milestone whose dependencies are finished, not just the first open box
+                 let next_task = roadmap
+                     .next_ready()
+                     .map(|i| roadmap.milestones[i].task_description());
 
                  if let Some(task_desc) = next_task {
                      // Notification removed as per user request e.g. "🚀 **Found Next Milestone**: ..."
@@ -155,11 +176,17 @@ where
                          Some(wd),
                          true // Create new folder
                      ).await?;
+                 } else if roadmap.has_unfinished() {
+                     let waiting: Vec<String> = (0..roadmap.milestones.len())
+                         .filter(|&i| !roadmap.milestones[i].is_finished() && !roadmap.is_container(i))
+                         .map(|i| roadmap.milestones[i].title.clone())
+                         .collect();
+                     let _ = chat.send_notification(&crate::strings::messages::roadmap_blocked(&waiting)).await;
                  } else {
-                     let _ = chat.send_notification("ℹ️ No pending milestones found in roadmap.md. Use `.task` to create a custom task.").await;
+                     let _ = chat.send_notification(crate::strings::messages::NO_PENDING_MILESTONES).await;
                  }
              } else {
-                 let _ = chat.send_notification("ℹ️ No roadmap.md found. Use `.task` to create a custom task.").await;
+                 let _ = chat.send_notification(crate::strings::messages::NO_ROADMAP).await;
              }
         } else {
              let _ = chat.send_notification("⚠️ You are not in a valid project directory.").await;
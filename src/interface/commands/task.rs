@@ -74,6 +74,7 @@ pub async fn start_task_wizard(
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn handle_task<C>(
     config: &AppConfig,
     state: &Arc<Mutex<BotState>>,
@@ -102,6 +103,8 @@ where
 
         // IMPORTANT: Clear any pending stop request from previous sessions
         room.stop_requested = false;
+        room.verification_failing = false;
+        room.verification_override = false;
 
         // Ensure active agent is set
         if room.active_agent.is_none() {
@@ -130,38 +133,19 @@ where
             let mut max_id = 0;
             if let Ok(entries) = std::fs::read_dir(&tasks_dir) {
                 for entry in entries.flatten() {
-                    if let Some(file_name) = entry.file_name().to_str() {
-                        if let Some(id_str) = file_name.split('-').next() {
-                            if let Ok(id) = id_str.parse::<u32>() {
-                                if id > max_id {
-                                    max_id = id;
-                                }
-                            }
-                        }
+                    if let Some(file_name) = entry.file_name().to_str()
+                        && let Some(id_str) = file_name.split('-').next()
+                        && let Ok(id) = id_str.parse::<u32>()
+                        && id > max_id
+                    {
+                        max_id = id;
                     }
                 }
             }
             let next_id = max_id + 1;
-            // Sanitize Task Description for Folder Name
-            // Use only alphanumeric and dashes, max 30 chars
-            let safe_desc: String = task
-                .chars()
-                .filter(|c| c.is_alphanumeric() || *c == ' ')
-                .map(|c| {
-                    if c == ' ' {
-                        '-'
-                    } else {
-                        c.to_ascii_lowercase()
-                    }
-                })
-                .collect();
-            let safe_desc = if safe_desc.len() > 100 {
-                &safe_desc[..100]
-            } else {
-                &safe_desc
-            };
+            let safe_desc = crate::domain::paths::task_slug(task);
 
-            let task_folder_name = format!("{:03}-{}", next_id, safe_desc.trim_matches('-'));
+            let task_folder_name = format!("{:03}-{}", next_id, safe_desc);
             let task_path = tasks_dir.join(&task_folder_name);
 
             let _ = std::fs::create_dir_all(&task_path);
@@ -191,6 +175,37 @@ where
         room.active_agent.clone().unwrap()
     };
 
+    // Each new task works on its own branch (dry runs leave the repository alone)
+    if create_new_folder {
+        let (task_rel, dry_run) = {
+            let guard = state.lock().await;
+            let room = guard.rooms.get(&chat.room_id());
+            (
+                room.and_then(|r| r.active_task.clone()),
+                room.is_some_and(|r| r.dry_run),
+            )
+        };
+        let base = match (&workdir, task_rel) {
+            (Some(wd), Some(task_rel)) if !dry_run => {
+                match crate::interface::commands::git::start_task_branch(wd, &task_rel).await {
+                    Ok(crate::interface::commands::git::TaskBranch::Started { base }) => Some(base),
+                    Ok(crate::interface::commands::git::TaskBranch::Skipped(reason)) => {
+                        let _ = chat.send_notification(&reason).await;
+                        None
+                    }
+                    Err(e) => {
+                        tracing::warn!("Could not create task branch: {}", e);
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+        let mut guard = state.lock().await;
+        guard.get_room_state(&chat.room_id()).task_base = base;
+        guard.save();
+    }
+
     // Run task using Engine
     let engine_clone = engine.clone();
     let chat_clone = chat.clone();
@@ -198,9 +213,11 @@ where
     let display_task_owned = display_task.map(|s| s.to_string());
     let workdir_owned = workdir.clone();
     let agent_name_owned = agent_name.clone();
+    let config_owned = config.clone();
+    let state_owned = state.clone();
 
     let handle = tokio::spawn(async move {
-        match engine_clone
+        let returned = match engine_clone
             .run_task(
                 &chat_clone,
                 &task_owned,
@@ -212,18 +229,28 @@ where
             )
             .await
         {
-            Ok(_) => {
+            Ok(result) => {
                 // We assume success if Ok
                 // do NOT send TASK_COMPLETE here.
                 // engine.rs handles "Plan Generated" notification for Planning phase.
                 // For Execution phase, we use start.rs.
+                result.is_some()
             }
             Err(e) => {
                 let _ = chat_clone
                     .send_notification(&crate::strings::messages::task_failed(&e.to_string()))
                     .await;
+                false
             }
-        }
+        };
+        crate::interface::commands::queue::start_next(
+            &config_owned,
+            &state_owned,
+            &engine_clone,
+            &chat_clone,
+            returned,
+        )
+        .await;
     });
 
     // Store Handle in RoomState
@@ -0,0 +1,119 @@
+//! # Undo Commands
+//!
+//! Handles `.undo`, `.rewind [N|task]`.
+//! Restores files from the active task's snapshot store, so agent changes can be reverted
+//! in projects that are not git repositories.
+
+use crate::application::state::BotState;
+use crate::domain::traits::ChatProvider;
+use crate::infrastructure::tools::executor::SharedToolExecutor;
+use crate::infrastructure::tools::snapshot::SnapshotStore;
+use anyhow::Result;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Reverts the files written by the most recent step.
+pub async fn handle_undo(
+    state: &Arc<Mutex<BotState>>,
+    tools: SharedToolExecutor,
+    chat: &impl ChatProvider,
+) -> Result<()> {
+    let Some(store) = idle_store(state, chat).await else {
+        return Ok(());
+    };
+    match store.last_step() {
+        Some(last) => rewind_to(state, tools, chat, &store, last - 1).await,
+        None => {
+            let _ = chat
+                .send_notification(crate::strings::messages::UNDO_NOTHING)
+                .await;
+            Ok(())
+        }
+    }
+}
+
+/// `.rewind` lists the steps, `.rewind N` goes back to the end of step N, `.rewind task` to the task start.
+pub async fn handle_rewind(
+    state: &Arc<Mutex<BotState>>,
+    tools: SharedToolExecutor,
+    chat: &impl ChatProvider,
+    args: &str,
+) -> Result<()> {
+    let Some(store) = idle_store(state, chat).await else {
+        return Ok(());
+    };
+
+    let target = match args.trim() {
+        "" => {
+            let steps = store.steps();
+            let msg = if steps.is_empty() {
+                crate::strings::messages::UNDO_NOTHING.to_string()
+            } else {
+                crate::strings::messages::rewind_listing(&steps)
+            };
+            chat.send_message(&msg)
+                .await
+                .map_err(|e| anyhow::anyhow!(e))?;
+            return Ok(());
+        }
+        "task" => 0,
+        n => match n.parse::<usize>() {
+            Ok(step) => step,
+            Err(_) => {
+                let _ = chat
+                    .send_notification(crate::strings::messages::REWIND_USAGE)
+                    .await;
+                return Ok(());
+            }
+        },
+    };
+
+    if store.last_step().is_none_or(|last| target >= last) {
+        let _ = chat
+            .send_notification(crate::strings::messages::UNDO_NOTHING)
+            .await;
+        return Ok(());
+    }
+    rewind_to(state, tools, chat, &store, target).await
+}
+
+async fn rewind_to(
+    state: &Arc<Mutex<BotState>>,
+    tools: SharedToolExecutor,
+    chat: &impl ChatProvider,
+    store: &SnapshotStore,
+    step: usize,
+) -> Result<()> {
+    let msg = match tools.lock().await.rewind(store, step).await {
+        Ok(restored) => {
+            let mut guard = state.lock().await;
+            // The checkpoint describes work that was just reverted
+            guard.get_room_state(&chat.room_id()).clear_checkpoint();
+            crate::strings::messages::rewound(step, &restored)
+        }
+        Err(e) => crate::strings::messages::rewind_failed(&e.to_string()),
+    };
+    let _ = chat.send_notification(&msg).await;
+    Ok(())
+}
+
+/// Snapshot store of the active task, or `None` (with a notice) if there is none or a task is running.
+async fn idle_store(state: &Arc<Mutex<BotState>>, chat: &impl ChatProvider) -> Option<SnapshotStore> {
+    let (running, store) = {
+        let guard = state.lock().await;
+        let room = guard.rooms.get(&chat.room_id());
+        (
+            room.is_some_and(|r| r.is_task_running()),
+            room.and_then(|r| r.snapshot_store()),
+        )
+    };
+    let notice = if running {
+        crate::strings::messages::TASK_ALREADY_RUNNING
+    } else if store.is_none() {
+        crate::strings::messages::NO_ACTIVE_TASK
+    } else {
+        return store;
+    };
+    let _ = chat.send_notification(notice).await;
+    None
+}
@@ -0,0 +1,78 @@
+//! # Verify Command
+//!
+//! Handles `.verify` and `.verify override`.
+//! Runs the project's verification pipeline on demand, or lets the user accept a task despite failing checks.
+
+use crate::application::state::BotState;
+use crate::domain::config::AppConfig;
+use crate::domain::traits::ChatProvider;
+use crate::infrastructure::tools::executor::{ProjectPolicy, SharedToolExecutor};
+use anyhow::Result;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+pub async fn handle_verify(
+    config: &AppConfig,
+    state: &Arc<Mutex<BotState>>,
+    tools: SharedToolExecutor,
+    chat: &impl ChatProvider,
+    args: &str,
+) -> Result<()> {
+    if args.trim() == "override" {
+        {
+            let mut guard = state.lock().await;
+            let room = guard.get_room_state(&chat.room_id());
+            room.verification_override = true;
+        }
+        let _ = chat
+            .send_notification(crate::strings::messages::VERIFICATION_OVERRIDDEN)
+            .await;
+        return Ok(());
+    }
+
+    let workdir = {
+        let guard = state.lock().await;
+        guard
+            .rooms
+            .get(&chat.room_id())
+            .and_then(|r| r.current_working_dir.clone())
+    };
+    let Some(wd) = workdir else {
+        let _ = chat
+            .send_notification(crate::strings::messages::NOT_IN_PROJECT)
+            .await;
+        return Ok(());
+    };
+
+    // The project's construct.yaml may override the pipeline and restrict its commands
+    let config = config.for_project(&wd);
+    let pipeline = config.verification.pipeline_for(&wd);
+    if pipeline.is_empty() {
+        let _ = chat
+            .send_notification(crate::strings::messages::NO_VERIFICATION_PIPELINE)
+            .await;
+        return Ok(());
+    }
+
+    tools.lock().await.set_project_policy(
+        Path::new(&wd),
+        config.project.as_ref().map(ProjectPolicy::from_config),
+    );
+    let report =
+        crate::application::verification::run_pipeline(&tools, &pipeline, Path::new(&wd)).await;
+
+    {
+        let mut guard = state.lock().await;
+        let room = guard.get_room_state(&chat.room_id());
+        room.verification_failing = !report.is_green();
+    }
+
+    chat.send_message(&crate::strings::messages::verification_report(
+        report.is_green(),
+        &report.to_history(),
+    ))
+    .await
+    .map_err(|e| anyhow::anyhow!(e))?;
+    Ok(())
+}
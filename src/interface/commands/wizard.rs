@@ -316,7 +316,7 @@ pub async fn handle_step(
                 match &creation_result {
                     Ok(path) => {
                         let sanitized_path = crate::application::utils::sanitize_path(
-                            &path,
+                            path,
                             config.system.projects_dir.as_deref(),
                         );
                         f.clean_stack();
@@ -375,7 +375,9 @@ pub async fn handle_step(
                         // Displaying simplified path in prompt text is fine as long as CWD is set correctly in backend.
                         let current_date =
                             chrono::Local::now().format("%Y-%m-%d %H:%M").to_string();
+                        let prompts = crate::strings::prompts::PromptSet::load(Some(path));
                         let prompt = crate::strings::prompts::new_project_prompt(
+                            &prompts,
                             &name,
                             &description,
                             &sanitized_path,
@@ -441,13 +443,13 @@ pub async fn start_agent_wizard(
     let mut allowed_agents: Vec<String> = Vec::new();
 
     // Check bridges
-    for (_bridge_name, entries) in &config.bridges {
+    for entries in config.bridges.values() {
         let mut is_room_bridge = false;
         for entry in entries {
-            if let Some(chan) = &entry.channel {
-                if chan == &room_id {
-                    is_room_bridge = true;
-                }
+            if let Some(chan) = &entry.channel
+                && chan == &room_id
+            {
+                is_room_bridge = true;
             }
         }
 
@@ -28,7 +28,6 @@ use std::collections::{HashSet, VecDeque};
 use std::fs;
 use std::sync::{Arc, OnceLock};
 use tokio::sync::Mutex;
-use tracing;
 
 use crate::application::project::ProjectManager;
 use crate::application::router::CommandRouter;
@@ -132,6 +131,13 @@ async fn main() -> Result<()> {
         let mut guard = state.lock().await;
         for room in guard.rooms.values_mut() {
             room.ensure_feed_manager(tools.clone(), config.system.projects_dir.clone());
+
+            // Rebuild the feed of a task that was interrupted mid-run
+            if let (Some(cp), Some(feed)) = (room.load_checkpoint(), &room.feed_manager)
+                && let Some(snapshot) = cp.feed
+            {
+                feed.lock().await.restore(snapshot);
+            }
         }
     }
 
@@ -162,12 +168,11 @@ async fn main() -> Result<()> {
     let mut allowed_startup_rooms = HashSet::new();
     for bridges in config.bridges.values() {
         for bridge in bridges {
-            if let Some(service) = &bridge.service {
-                if service == "matrix" {
-                    if let Some(channel) = &bridge.channel {
-                        allowed_startup_rooms.insert(channel.clone());
-                    }
-                }
+            if let Some(service) = &bridge.service
+                && service == "matrix"
+                && let Some(channel) = &bridge.channel
+            {
+                allowed_startup_rooms.insert(channel.clone());
             }
         }
     }
@@ -191,10 +196,25 @@ async fn main() -> Result<()> {
                 tokio::time::sleep(std::time::Duration::from_secs(2)).await;
 
                 for room in rooms {
+                    let chat = MatrixService::new(room.clone());
+
+                    // Offer `.resume` in any room whose task was interrupted by the restart
+                    if let Err(e) = crate::interface::commands::resume::announce_interrupted_task(
+                        &startup_state,
+                        &chat,
+                    )
+                    .await
+                    {
+                        tracing::error!(
+                            "Failed to announce interrupted task in room {}: {}",
+                            room.room_id(),
+                            e
+                        );
+                    }
+
                     if !allowed_startup_rooms.contains(room.room_id().as_str()) {
                         continue;
                     }
-                    let chat = MatrixService::new(room.clone());
                     if let Err(e) = crate::interface::commands::misc::handle_status(
                         &startup_config,
                         &startup_state,
@@ -230,7 +250,13 @@ async fn main() -> Result<()> {
 
             let rooms_to_check: Vec<(String, Option<i64>)> = {
                 let guard = auto_state.lock().await;
-                guard.rooms.iter().map(|(id, r)| (id.clone(), r.task_completion_time)).collect()
+                guard
+                    .rooms
+                    .iter()
+                    // A live run (paused ones included) is never auto-continued on top of
+                    .filter(|(_, r)| !r.is_task_running())
+                    .map(|(id, r)| (id.clone(), r.task_completion_time))
+                    .collect()
             };
 
             for (room_id, completion_time) in rooms_to_check {
@@ -357,7 +383,7 @@ async fn main() -> Result<()> {
 
                     // Dispatch
                     if let Err(e) = router
-                        .route(&chat, &body, original_msg.sender.as_str())
+                        .route(&chat, body, original_msg.sender.as_str())
                         .await
                     {
                         tracing::error!("Failed to route message: {}", e);
@@ -14,14 +14,23 @@ pub const MAIN: &str = concat!(
     "* ask [msg]: Chat with agent\n",
     "* task: Start a new task\n",
     "* start: Start/resume tasks\n",
+    "* pause: Pause the running task after the current action\n",
     "* stop: Stop tasks\n",
+    "* say [text]: Steer the running task\n",
+    "* resume: Resume a paused or interrupted task\n",
+    "* queue [rm n | move a b]: Show/edit queued tasks\n",
+    "* roadmap [next n | skip n]: Show/reorder milestones\n",
     "\n",
     "**🐙 Git**\n",
-    "* changes\n",
-    "* commit [msg]\n",
-    "* discard\n",
+    "* changes: Diffstat since the task started\n",
+    "* commit [msg]: Commit all changes\n",
+    "* discard: Reset to the task start\n",
+    "* undo: Revert the agent's last step\n",
+    "* rewind [n|task]: List steps / go back to step n\n",
     "\n",
     "**🔨 Build**\n",
+    "* verify [override]: Run checks / accept failing checks\n",
+    "* dryrun [on|off|diff]: Simulate tasks without touching files\n",
     "* check\n",
     "* build\n",
     "* deploy\n",
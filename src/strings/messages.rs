@@ -62,11 +62,250 @@ pub fn file_read_failed(err: &str) -> String {
     format!("Failed to read file: {err}")
 }
 
-pub fn room_status_msg(project: &str, cwd: &str, model: &str, agent: &str) -> String {
+pub fn room_status_msg(project: &str, cwd: &str, model: &str, agent: &str, prompts: &str) -> String {
     format!(
-        "**Project**: {project}\n**CWD**: {cwd}\n**Model**: {model}\n**Agent**: {agent}"
+        "**Project**: {project}\n**CWD**: {cwd}\n**Model**: {model}\n**Agent**: {agent}\n**Prompts**: {prompts}"
     )
 }
 
+/// `built-in`, or the overridden templates with their source and any rejected overrides.
+pub fn prompt_sources(prompts: &crate::strings::prompts::PromptSet) -> String {
+    use crate::strings::prompts::PromptSource;
+    let mut lines = Vec::new();
+    for t in &prompts.templates {
+        if t.source != PromptSource::BuiltIn {
+            lines.push(format!("- `{}`: {}", t.name, t.source));
+        }
+        for (source, reason) in &t.rejected {
+            lines.push(format!("- ⚠️ `{}` in {} ignored: {}", t.name, source, reason));
+        }
+    }
+    if lines.is_empty() {
+        "built-in".to_string()
+    } else {
+        format!("overrides (others built-in)\n{}", lines.join("\n"))
+    }
+}
+
+pub const NO_CHECKPOINT: &str = "ℹ️ No interrupted task to resume.";
+
+pub fn task_interrupted(task: &str, steps: usize) -> String {
+    format!("⏸️ **Task Interrupted**: {task} (after {steps} steps).\nType `.resume` to continue where it left off.")
+}
+
+pub const VERIFICATION_OVERRIDDEN: &str =
+    "⚠️ Verification override set. The task may complete even if checks fail.";
+pub const NO_VERIFICATION_PIPELINE: &str =
+    "ℹ️ No verification commands configured for this project (see `verification` in config.yaml).";
+
+pub fn verification_report(green: bool, report: &str) -> String {
+    let icon = if green { "✅" } else { "❌" };
+    format!("{icon} **Verification**\n```\n{report}\n```")
+}
+
+pub const TASK_PAUSED: &str = "⏸️ **Task Paused**. Type `.resume` to continue from this step.";
+pub const PAUSE_REQUESTED: &str = "⏸️ Pausing after the current action...";
+pub const TASK_ALREADY_RUNNING: &str = "ℹ️ A task is already running in this room.";
+pub const TASK_UNPAUSED: &str = "▶️ Resuming task.";
+
+pub const DRY_RUN_ON: &str = "🧪 **Dry run on**. Tasks will record file changes in memory and skip shell commands. `read`, `list`, `find` and `search` see the simulated files; directory outlines, `symbol` lookups, the repository map and retrieved snippets still show the disk. Nothing is written to disk, not even the task checkpoint, so a dry run cannot be resumed after a restart.";
+pub const DRY_RUN_NOTHING: &str = "ℹ️ No simulated changes in this project.";
+pub const DRY_RUN_USAGE: &str = "Usage: `.dryrun [on|off|diff]`";
+
+pub fn dry_run_off(discarded_files: usize) -> String {
+    format!("🧪 **Dry run off**. Discarded simulated changes to {discarded_files} file(s).")
+}
+
+pub fn dry_run_status(enabled: bool, stats: Option<(usize, usize, usize)>) -> String {
+    let state = if enabled { "on" } else { "off" };
+    match stats {
+        Some((files, added, removed)) => format!(
+            "🧪 Dry run is **{state}**. Simulated: {files} file(s) changed (+{added}/-{removed}). Use `.dryrun diff` to see them."
+        ),
+        None => format!("🧪 Dry run is **{state}**."),
+    }
+}
+
+pub fn dry_run_diff(diff: &str, commands: &[String]) -> String {
+    let mut out = String::from("🧪 **Dry Run: Changes Not Applied**\n");
+    if diff.is_empty() {
+        out.push_str("No file changes.\n");
+    } else {
+        out.push_str(&format!("```diff\n{diff}```\n"));
+    }
+    if !commands.is_empty() {
+        out.push_str("**Skipped commands:**\n");
+        for cmd in commands {
+            out.push_str(&format!("* `{cmd}`\n"));
+        }
+    }
+    out
+}
+
+pub const GIT_NO_CHANGES: &str = "ℹ️ No changes since the task started.";
+pub const GIT_NOTHING_TO_COMMIT: &str = "ℹ️ Nothing to commit.";
+pub const GIT_NO_TASK_BASE: &str = "ℹ️ No task branch to discard. Start a task with `.task` first.";
+pub const GIT_NOT_OWN_REPO: &str = "ℹ️ The project is not a git repository of its own, so tasks run without a task branch and `.commit`, `.changes` and `.discard` are unavailable. Run `git init` in the project to use them.";
+pub const GIT_NO_COMMITS: &str = "ℹ️ The project's repository has no commits yet: the task runs without a task branch. Make a first commit to use `.changes` and `.discard`.";
+
+pub fn git_dirty_tree(changes: &[String]) -> String {
+    const SHOWN: usize = 5;
+    let mut list: Vec<String> = changes.iter().take(SHOWN).map(|c| format!("`{}`", c.trim())).collect();
+    if changes.len() > SHOWN {
+        list.push(format!("and {} more", changes.len() - SHOWN));
+    }
+    format!(
+        "⚠️ The project has uncommitted changes ({}): the task runs without a task branch so they are not mixed into it. Commit (`.commit`) or stash them before the next `.task` to get `.changes` and `.discard`.",
+        list.join(", ")
+    )
+}
+
+pub fn git_changes(stat: &str) -> String {
+    format!("🐙 **Changes since task start**\n```\n{stat}\n```")
+}
+
+pub fn git_committed(hash: &str, message: &str) -> String {
+    format!("🐙 Committed `{hash}`: {message}")
+}
+
+pub fn git_discarded(base: &str) -> String {
+    let short: String = base.chars().take(7).collect();
+    format!("🗑️ **Changes discarded**. Project reset to task start (`{short}`).")
+}
+
+pub fn git_failed(error: &str) -> String {
+    format!("⚠️ Git error: {error}")
+}
+
+pub const NO_ACTIVE_TASK: &str = "ℹ️ No active task in this room.";
+pub const UNDO_NOTHING: &str = "ℹ️ Nothing to undo.";
+pub const REWIND_USAGE: &str = "Usage: `.rewind`, `.rewind <step>`, `.rewind task`";
+
+pub fn rewind_listing(steps: &std::collections::BTreeMap<usize, Vec<String>>) -> String {
+    let mut out = String::from("⏪ **Snapshots** (`.rewind <step>` restores the end of that step)\n");
+    for (step, files) in steps {
+        out.push_str(&format!("* Step {}: {}\n", step, files.join(", ")));
+    }
+    out
+}
+
+pub fn rewound(step: usize, files: &[String]) -> String {
+    let target = if step == 0 {
+        "the start of the task".to_string()
+    } else {
+        format!("the end of step {step}")
+    };
+    format!("⏪ Restored {} file(s) to {target}: {}", files.len(), files.join(", "))
+}
+
+pub fn rewind_failed(error: &str) -> String {
+    format!("⚠️ Rewind failed: {error}")
+}
+
+pub const SAY_USAGE: &str = "Usage: `.say <guidance for the running task>`";
+pub const GUIDANCE_QUEUED: &str = "💬 Guidance queued. The agent will see it before its next step.";
+pub const NO_RUNNING_TASK: &str = "ℹ️ No task is running in this room.";
+
+pub fn agent_question(minutes: u64) -> String {
+    format!("❓ **The agent has a question** (see the feed). Reply in the room to answer; without an answer the task carries on after {minutes} minutes.")
+}
+
+pub fn agent_stuck(action: &str) -> String {
+    format!("🔁 **Agent looks stuck**: it keeps trying to {action} with the same result.\nReply `.approve` to let it continue, `.deny` to stop the task.")
+}
+
+pub const QUEUE_EMPTY: &str = "ℹ️ Task queue is empty.";
+pub const QUEUE_USAGE: &str = "Usage: `.queue`, `.queue rm <n>`, `.queue move <from> <to>`";
+
+pub fn task_queued(position: usize) -> String {
+    format!("📥 A task is already running. Queued as #{position} (see `.queue`).")
+}
+
+pub fn queue_listing(items: &[String]) -> String {
+    let mut out = String::from("📋 **Task Queue**\n");
+    for (i, item) in items.iter().enumerate() {
+        let summary = item.lines().next().unwrap_or(item);
+        out.push_str(&format!("{}. {}\n", i + 1, summary));
+    }
+    out
+}
+
+pub fn queue_removed(task: &str) -> String {
+    format!("🗑️ Removed from queue: {task}")
+}
+
+pub fn queue_moved(task: &str, position: usize) -> String {
+    format!("↕️ Moved to #{position}: {task}")
+}
+
+pub fn queue_invalid_index(index: &str) -> String {
+    format!("⚠️ No queued task at position `{index}`.")
+}
+
+pub fn queue_start_failed(error: &str) -> String {
+    format!("⚠️ Could not start the next queued task: {error}")
+}
+
+pub const ROADMAP_USAGE: &str = "Usage: `.roadmap`, `.roadmap next <n>`, `.roadmap skip <n>`";
+pub const NO_ROADMAP: &str = "ℹ️ No roadmap.md found. Use `.task` to create a custom task.";
+pub const NO_PENDING_MILESTONES: &str =
+    "ℹ️ No pending milestones found in roadmap.md. Use `.task` to create a custom task.";
+
+pub fn roadmap_listing(roadmap: &crate::domain::roadmap::Roadmap, task_folders: &[String]) -> String {
+    use crate::domain::roadmap::MilestoneStatus;
+    if roadmap.milestones.is_empty() {
+        return "🗺️ The roadmap has no milestones yet.".to_string();
+    }
+    let mut out = String::from("🗺️ **Roadmap**\n");
+    for (i, m) in roadmap.milestones.iter().enumerate() {
+        let blocked = roadmap.blocked_by(i);
+        let icon = match m.status() {
+            MilestoneStatus::Done => "✅",
+            MilestoneStatus::Skipped => "⏭️",
+            _ if !blocked.is_empty() => "🔒",
+            MilestoneStatus::InProgress => "🔄",
+            MilestoneStatus::Pending => "⏳",
+        };
+        let mut line = format!("{}{}. {} {}", "  ".repeat(m.depth), i + 1, icon, m.title);
+        let (resolved, total) = m.progress();
+        if total > 0 {
+            line.push_str(&format!(" ({resolved}/{total})"));
+        }
+        if !m.is_finished() && !blocked.is_empty() {
+            line.push_str(&format!(" — waits for {}", blocked.join(", ")));
+        }
+        if let Some(folder) = m.linked_task(task_folders) {
+            line.push_str(&format!(" → `tasks/{folder}`"));
+        }
+        out.push_str(&line);
+        out.push('\n');
+    }
+    match roadmap.next_ready() {
+        Some(i) => out.push_str(&format!("\n**Next:** {}", roadmap.milestones[i].title)),
+        None if roadmap.has_unfinished() => out.push_str("\n**Next:** none, all remaining milestones are blocked"),
+        None => out.push_str("\n🎉 All milestones are finished."),
+    }
+    out
+}
+
+pub fn roadmap_blocked(waiting: &[String]) -> String {
+    format!(
+        "🔒 All remaining milestones wait on unfinished dependencies ({}). Check `.roadmap`.",
+        waiting.join(", ")
+    )
+}
+
+pub fn roadmap_prioritized(title: &str) -> String {
+    format!("↕️ `{title}` is now the next milestone.")
+}
+
+pub fn roadmap_skipped(title: &str) -> String {
+    format!("⏭️ Skipped milestone: {title}")
+}
+
+pub fn roadmap_error(reason: &str) -> String {
+    format!("⚠️ {reason}")
+}
+
 pub const WIZARD_CANCELLED: &str = "❌ Wizard cancelled.";
 // Note: We might want a dynamic one for wizard success to show path, but let's stick to what we see in the code or make it dynamic.
@@ -26,14 +26,14 @@ impl<'a> PromptRenderer<'a> {
         }
 
         // Validate: Check for unreplaced placeholders
-        if let Some(start) = result.find("{{") {
-            if let Some(end) = result[start..].find("}}") {
-                let placeholder = &result[start..start + end + 2];
-                // Check if it looks like a valid UPPERCASE variable {{VAR_NAME}}
-                // This prevents flagging literal {{ }} usage if we ever have it, though unlikely to be partial.
-                // For safety, we flag ANY {{...}} pattern as suspicious if it remains.
-                tracing::error!("Construct: [PROMPT RENDER ERROR] Unreplaced placeholder found in output: {}", placeholder);
-            }
+        if let Some(start) = result.find("{{")
+            && let Some(end) = result[start..].find("}}")
+        {
+            let placeholder = &result[start..start + end + 2];
+            // Check if it looks like a valid UPPERCASE variable {{VAR_NAME}}
+            // This prevents flagging literal {{ }} usage if we ever have it, though unlikely to be partial.
+            // For safety, we flag ANY {{...}} pattern as suspicious if it remains.
+            tracing::error!("Construct: [PROMPT RENDER ERROR] Unreplaced placeholder found in output: {}", placeholder);
         }
         
         result
@@ -47,9 +47,167 @@ pub const CONTEXT_TEMPLATE: &str = include_str!("../../prompts/context.md");
 pub const ASSISTANT_TEMPLATE: &str = include_str!("../../prompts/assistant.md");
 pub const TOOLS_TEMPLATE: &str = include_str!("../../prompts/tools.md");
 
+/// Prompt overrides inside a project (e.g. `tasks/specs/prompts/developer.md`).
+pub const PROJECT_PROMPTS_DIR: &str = "tasks/specs/prompts";
+/// Bot-wide prompt overrides, relative to the bot's working directory.
+pub const DATA_PROMPTS_DIR: &str = "data/prompts";
 
+/// Built-in prompts: file name, content, placeholders the renderer fills in, and the ones an
+/// override must keep.
+const BUILT_IN: &[(&str, &str, &[&str], &[&str])] = &[
+    (
+        "new_project.md",
+        NEW_PROJECT_TEMPLATE,
+        &[
+            "{{TEMPLATE_ROADMAP}}", "{{TEMPLATE_ARCHITECTURE}}", "{{TEMPLATE_PLAN}}",
+            "{{TEMPLATE_PROGRESS}}", "{{TEMPLATE_WALKTHROUGH}}", "{{TEMPLATE_TASKS}}",
+            "{{TEMPLATE_GUIDELINES}}", "{{NAME}}", "{{REQUIREMENTS}}", "{{WORKDIR}}",
+            "{{ACTIVE_TASK}}", "{{CURRENT_DATE}}",
+        ],
+        &["{{REQUIREMENTS}}"],
+    ),
+    (
+        "architect.md",
+        ARCHITECT_TEMPLATE,
+        &[
+            "{{TEMPLATE_PLAN}}", "{{TEMPLATE_PROGRESS}}", "{{TEMPLATE_WALKTHROUGH}}",
+            "{{TEMPLATE_TASKS}}", "{{TEMPLATE_ROADMAP}}", "{{TEMPLATE_ARCHITECTURE}}",
+            "{{TOOLS}}", "{{CWD}}", "{{ACTIVE_TASK}}", "{{CONTEXT}}", "{{CURRENT_DATE}}",
+        ],
+        &["{{CONTEXT}}", "{{TOOLS}}"],
+    ),
+    (
+        "developer.md",
+        DEVELOPER_TEMPLATE,
+        &["{{CWD}}", "{{CONTEXT}}", "{{ACTIVE_TASK}}", "{{CURRENT_DATE}}", "{{TOOLS}}"],
+        &["{{CONTEXT}}", "{{TOOLS}}"],
+    ),
+    (
+        "context.md",
+        CONTEXT_TEMPLATE,
+        &[
+            "{{HISTORY}}", "{{PROGRESS}}", "{{ROADMAP}}", "{{ARCHITECTURE}}",
+            "{{TASKS_CHECKLIST}}", "{{PLAN}}", "{{GUIDELINES}}", "{{REPO_MAP}}",
+            "{{RELEVANT_CODE}}",
+        ],
+        &["{{HISTORY}}"],
+    ),
+    ("assistant.md", ASSISTANT_TEMPLATE, &["{{CWD}}", "{{CONTEXT}}"], &["{{CONTEXT}}"]),
+    ("tools.md", TOOLS_TEMPLATE, &[], &[]),
+];
 
+/// Where a prompt template was loaded from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PromptSource {
+    Project,
+    Data,
+    BuiltIn,
+}
+
+impl std::fmt::Display for PromptSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PromptSource::Project => write!(f, "{}", PROJECT_PROMPTS_DIR),
+            PromptSource::Data => write!(f, "{}", DATA_PROMPTS_DIR),
+            PromptSource::BuiltIn => write!(f, "built-in"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PromptTemplate {
+    pub name: &'static str,
+    pub content: String,
+    pub source: PromptSource,
+    /// Overrides that were found but failed validation, with the reason
+    pub rejected: Vec<(PromptSource, String)>,
+}
+
+/// The prompt templates for one project, resolved from the search path: the project's
+/// `tasks/specs/prompts/`, then `data/prompts/`, then the built-in defaults.
+#[derive(Debug, Clone)]
+pub struct PromptSet {
+    pub templates: Vec<PromptTemplate>,
+}
+
+impl PromptSet {
+    pub fn load(workdir: Option<&str>) -> Self {
+        let mut dirs = Vec::new();
+        if let Some(wd) = workdir {
+            dirs.push((PromptSource::Project, std::path::Path::new(wd).join(PROJECT_PROMPTS_DIR)));
+        }
+        dirs.push((PromptSource::Data, std::path::PathBuf::from(DATA_PROMPTS_DIR)));
+        Self::load_from(&dirs)
+    }
+
+    fn load_from(dirs: &[(PromptSource, std::path::PathBuf)]) -> Self {
+        let templates = BUILT_IN
+            .iter()
+            .map(|(name, built_in, allowed, required)| {
+                let mut rejected = Vec::new();
+                for (source, dir) in dirs {
+                    let Ok(content) = std::fs::read_to_string(dir.join(name)) else {
+                        continue;
+                    };
+                    match validate(&content, allowed, required) {
+                        Ok(()) => {
+                            return PromptTemplate {
+                                name,
+                                content,
+                                source: source.clone(),
+                                rejected,
+                            };
+                        }
+                        Err(reason) => {
+                            tracing::warn!("Ignoring prompt override {:?}: {}", dir.join(name), reason);
+                            rejected.push((source.clone(), reason));
+                        }
+                    }
+                }
+                PromptTemplate {
+                    name,
+                    content: built_in.to_string(),
+                    source: PromptSource::BuiltIn,
+                    rejected,
+                }
+            })
+            .collect();
+        Self { templates }
+    }
+
+    /// Content of a template by file name (`developer.md`).
+    pub fn get(&self, name: &str) -> &str {
+        self.templates
+            .iter()
+            .find(|t| t.name == name)
+            .map(|t| t.content.as_str())
+            .unwrap_or_default()
+    }
+}
+
+/// Checks that an override only uses placeholders the renderer fills in and keeps the
+/// required ones.
+fn validate(content: &str, allowed: &[&str], required: &[&str]) -> Result<(), String> {
+    let mut rest = content;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start..].find("}}") else {
+            break;
+        };
+        let placeholder = &rest[start..start + len + 2];
+        if !allowed.contains(&placeholder) {
+            return Err(format!("unknown placeholder {}", placeholder));
+        }
+        rest = &rest[start + len + 2..];
+    }
+    match required.iter().find(|p| !content.contains(**p)) {
+        Some(missing) => Err(format!("missing placeholder {}", missing)),
+        None => Ok(()),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn build_context(
+    prompts: &PromptSet,
     history: &str,
     progress: &str,
     roadmap: &str,
@@ -57,8 +215,10 @@ fn build_context(
     tasks_checklist: &str,
     plan: &str,
     guidelines: &str,
+    repo_map: &str,
+    relevant_code: &str,
 ) -> String {
-    PromptRenderer::new(CONTEXT_TEMPLATE)
+    PromptRenderer::new(prompts.get("context.md"))
         .set("{{HISTORY}}", history)
         .set("{{PROGRESS}}", progress)
         .set("{{ROADMAP}}", roadmap)
@@ -66,35 +226,40 @@ fn build_context(
         .set("{{TASKS_CHECKLIST}}", tasks_checklist)
         .set("{{PLAN}}", plan)
         .set("{{GUIDELINES}}", guidelines)
+        .set("{{REPO_MAP}}", repo_map)
+        .set("{{RELEVANT_CODE}}", relevant_code)
         .render()
 }
 
-pub fn new_project_prompt(name: &str, requirements: &str, workdir: &str, date: &str) -> String {
+pub fn new_project_prompt(prompts: &PromptSet, name: &str, requirements: &str, workdir: &str, date: &str) -> String {
     let context = build_context(
+        prompts,
         "(New Project)",
         "(New Project - No history)",
         "(No roadmap yet)",
         "(No architecture yet)",
         "(New Project Initialization)",
         "(No plan yet)",
-        "(Review templates/guidelines.md)"
+        "(Review templates/guidelines.md)",
+        "(Empty project)",
+        "(Empty project)",
     );
 
-    let architect_layer = PromptRenderer::new(ARCHITECT_TEMPLATE)
+    let architect_layer = PromptRenderer::new(prompts.get("architect.md"))
         .set("{{TEMPLATE_PLAN}}", templates::PLAN_TEMPLATE)
         .set("{{TEMPLATE_PROGRESS}}", templates::PROGRESS_TEMPLATE)
         .set("{{TEMPLATE_WALKTHROUGH}}", templates::WALKTHROUGH_TEMPLATE)
         .set("{{TEMPLATE_TASKS}}", templates::TASKS_TEMPLATE)
         .set("{{TEMPLATE_ROADMAP}}", templates::ROADMAP_TEMPLATE)
         .set("{{TEMPLATE_ARCHITECTURE}}", templates::ARCHITECTURE_TEMPLATE)
-        .set("{{TOOLS}}", TOOLS_TEMPLATE)
+        .set("{{TOOLS}}", prompts.get("tools.md"))
         .set("{{CWD}}", workdir)
         .set("{{ACTIVE_TASK}}", ".")
         .set("{{CONTEXT}}", &context)
         .set("{{CURRENT_DATE}}", date)
         .render();
 
-    let specific_instructions = PromptRenderer::new(NEW_PROJECT_TEMPLATE)
+    let specific_instructions = PromptRenderer::new(prompts.get("new_project.md"))
         .set("{{TEMPLATE_ROADMAP}}", templates::ROADMAP_TEMPLATE)
         .set("{{TEMPLATE_ARCHITECTURE}}", templates::ARCHITECTURE_TEMPLATE)
         .set("{{TEMPLATE_PLAN}}", templates::PLAN_TEMPLATE)
@@ -115,7 +280,9 @@ pub fn new_project_prompt(name: &str, requirements: &str, workdir: &str, date: &
     )
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn planning_mode_turn(
+    prompts: &PromptSet,
     cwd: &str,
     roadmap: &str,
     tasks_checklist: &str,
@@ -126,8 +293,10 @@ pub fn planning_mode_turn(
     history: &str,
     date: &str,
     guidelines: &str,
+    repo_map: &str,
 ) -> String {
     let context = build_context(
+        prompts,
         history,
         progress,
         roadmap,
@@ -135,16 +304,18 @@ pub fn planning_mode_turn(
         tasks_checklist,
         plan,
         guidelines,
+        repo_map,
+        "(Not used during planning)",
     );
 
-    PromptRenderer::new(ARCHITECT_TEMPLATE)
+    PromptRenderer::new(prompts.get("architect.md"))
         .set("{{TEMPLATE_PLAN}}", templates::PLAN_TEMPLATE)
         .set("{{TEMPLATE_PROGRESS}}", templates::PROGRESS_TEMPLATE)
         .set("{{TEMPLATE_WALKTHROUGH}}", templates::WALKTHROUGH_TEMPLATE)
         .set("{{TEMPLATE_TASKS}}", templates::TASKS_TEMPLATE)
         .set("{{TEMPLATE_ROADMAP}}", templates::ROADMAP_TEMPLATE)
         .set("{{TEMPLATE_ARCHITECTURE}}", templates::ARCHITECTURE_TEMPLATE)
-        .set("{{TOOLS}}", TOOLS_TEMPLATE)
+        .set("{{TOOLS}}", prompts.get("tools.md"))
         .set("{{CWD}}", cwd)
         .set("{{ACTIVE_TASK}}", active_task)
         .set("{{CONTEXT}}", &context)
@@ -152,7 +323,9 @@ pub fn planning_mode_turn(
         .render()
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn execution_mode_turn(
+    prompts: &PromptSet,
     cwd: &str,
     roadmap: &str,
     tasks_checklist: &str,
@@ -163,8 +336,11 @@ pub fn execution_mode_turn(
     history: &str,
     date: &str,
     guidelines: &str,
+    repo_map: &str,
+    relevant_code: &str,
 ) -> String {
     let context = build_context(
+        prompts,
         history,
         progress,
         roadmap,
@@ -172,18 +348,22 @@ pub fn execution_mode_turn(
         tasks_checklist,
         plan,
         guidelines,
+        repo_map,
+        relevant_code,
     );
 
-    PromptRenderer::new(DEVELOPER_TEMPLATE)
+    PromptRenderer::new(prompts.get("developer.md"))
         .set("{{CWD}}", cwd)
         .set("{{CONTEXT}}", &context)
         .set("{{ACTIVE_TASK}}", active_task)
         .set("{{CURRENT_DATE}}", date)
-        .set("{{TOOLS}}", TOOLS_TEMPLATE)
+        .set("{{TOOLS}}", prompts.get("tools.md"))
         .render()
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn assistant_mode_turn(
+    prompts: &PromptSet,
     cwd: &str,
     roadmap: &str,
     tasks_checklist: &str,
@@ -192,8 +372,11 @@ pub fn assistant_mode_turn(
     progress: &str,
     history: &str,
     guidelines: &str,
+    repo_map: &str,
+    relevant_code: &str,
 ) -> String {
     let context = build_context(
+        prompts,
         history,
         progress,
         roadmap,
@@ -201,9 +384,11 @@ pub fn assistant_mode_turn(
         tasks_checklist,
         plan,
         guidelines,
+        repo_map,
+        relevant_code,
     );
 
-    PromptRenderer::new(ASSISTANT_TEMPLATE)
+    PromptRenderer::new(prompts.get("assistant.md"))
         .set("{{CWD}}", cwd)
         .set("{{CONTEXT}}", &context)
 
@@ -228,6 +413,44 @@ mod tests {
         assert_eq!(renderer.render(), "Hello {{MISSING}}");
     }
 
+    #[test]
+    fn test_prompt_overrides_search_path() {
+        let project = tempfile::tempdir().unwrap();
+        let data = tempfile::tempdir().unwrap();
+        std::fs::write(project.path().join("developer.md"), "Dev {{CONTEXT}} {{TOOLS}}").unwrap();
+        std::fs::write(data.path().join("developer.md"), "Data {{CONTEXT}} {{TOOLS}}").unwrap();
+        std::fs::write(data.path().join("tools.md"), "Custom tools").unwrap();
+        // Invalid: uses a placeholder the renderer never fills in
+        std::fs::write(project.path().join("assistant.md"), "{{CONTEXT}} {{NAME}}").unwrap();
+        // Invalid: drops the context
+        std::fs::write(data.path().join("context.md"), "Nothing here").unwrap();
+
+        let prompts = PromptSet::load_from(&[
+            (PromptSource::Project, project.path().to_path_buf()),
+            (PromptSource::Data, data.path().to_path_buf()),
+        ]);
+        let source = |name: &str| prompts.templates.iter().find(|t| t.name == name).unwrap();
+        assert_eq!(source("developer.md").source, PromptSource::Project);
+        assert_eq!(source("tools.md").source, PromptSource::Data);
+        assert_eq!(source("architect.md").source, PromptSource::BuiltIn);
+        assert_eq!(source("assistant.md").source, PromptSource::BuiltIn);
+        assert_eq!(source("assistant.md").rejected[0].1, "unknown placeholder {{NAME}}");
+        assert_eq!(source("context.md").rejected[0].1, "missing placeholder {{HISTORY}}");
+
+        let turn = execution_mode_turn(
+            &prompts, ".", "", "", "", "", "", "tasks/001", "", "", "", "", "",
+        );
+        assert!(turn.starts_with("Dev "));
+        assert!(turn.ends_with(" Custom tools"));
+    }
+
+    #[test]
+    fn test_built_in_prompts_are_valid() {
+        for (name, content, allowed, required) in BUILT_IN {
+            assert_eq!(validate(content, allowed, required), Ok(()), "{}", name);
+        }
+    }
+
     #[test]
     fn test_prompt_renderer_partial_replace() {
         let renderer = PromptRenderer::new("{{A}} and {{B}}")